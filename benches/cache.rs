@@ -0,0 +1,231 @@
+//! Benchmarks applying a recorded event stream to the cache from a thread per
+//! shard, while other threads read from the cache as commands would.
+//!
+//! The stream is read from the recording given as the first argument - such as
+//! one written via `Client::record_to` - or is otherwise generated from the
+//! large guild in the test resources:
+//!
+//! ```sh
+//! cargo bench --bench cache -- session.jsonl
+//! ```
+//!
+//! Each stream is applied twice: once taking the cache's write lock for every
+//! event, and once via `CacheSink::update`, which only takes the write lock
+//! for events which add to the cache's own maps.
+
+#[macro_use] extern crate serde_json;
+extern crate serenity;
+
+use serde_json::Value;
+use serenity::client::gateway::Recording;
+use serenity::ext::cache::{Cache, CacheSink};
+use serenity::model::event::{Event, GatewayEvent};
+use serenity::model::GuildId;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Instant;
+
+const SHARDS: u64 = 4;
+const GUILDS_PER_SHARD: u64 = 2;
+const EVENTS_PER_GUILD: usize = 25_000;
+const READERS: usize = 4;
+
+// The events received by each shard, in the order they were received.
+type Stream = BTreeMap<Option<[u64; 2]>, Vec<Event>>;
+
+fn main() {
+    // Cargo passes `--bench` to the benchmark, along with the arguments given.
+    let payloads = match env::args().skip(1).find(|arg| !arg.starts_with("--")) {
+        Some(path) => recorded(&path),
+        None => generated(),
+    };
+    let stream = decode(payloads);
+    let count = stream.values().map(|events| events.len()).sum::<usize>();
+
+    println!("{} events over {} shards, with {} readers", count, stream.len(), READERS);
+
+    run("global write lock", &stream, |cache, event| cache.write().unwrap().update(event));
+    run("per-guild locks", &stream, |cache, event| CacheSink::update(cache, event));
+}
+
+fn run<F>(name: &str, stream: &Stream, update: F)
+    where F: Fn(&RwLock<Cache>, &Event) + Send + Sync + 'static {
+    let cache = Arc::new(RwLock::new(Cache::default()));
+    let update = Arc::new(update);
+
+    // Guilds are added before timing, so that only their updates are measured.
+    let mut stream = stream.clone();
+
+    for events in stream.values_mut() {
+        let (setup, updates) = events.drain(..).partition(|event| match *event {
+            Event::GuildCreate(_) | Event::Ready(_) => true,
+            _ => false,
+        });
+
+        for event in setup {
+            cache.write().unwrap().update(&event);
+        }
+
+        *events = updates;
+    }
+
+    let count = stream.values().map(|events| events.len()).sum::<usize>();
+
+    let guild_ids = cache.read().unwrap().guilds.keys().cloned().collect::<Vec<GuildId>>();
+    let done = Arc::new(AtomicBool::new(false));
+    let lookups = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+
+    let readers = (0..READERS).map(|reader| {
+        let cache = cache.clone();
+        let done = done.clone();
+        let guild_ids = guild_ids.clone();
+        let lookups = lookups.clone();
+
+        thread::spawn(move || {
+            let mut count = 0;
+
+            for guild_id in guild_ids.iter().cycle().skip(reader) {
+                if done.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                // Similar to calculating a member's permissions.
+                let cache = cache.read().unwrap();
+
+                if let Some(guild) = cache.guild(*guild_id) {
+                    let guild = guild.read().unwrap();
+                    let _ = guild.members.get(&cache.user.id).map(|member| member.roles.len());
+                }
+
+                count += 1;
+            }
+
+            lookups.fetch_add(count, Ordering::SeqCst);
+        })
+    }).collect::<Vec<_>>();
+
+    let shards = stream.into_iter().map(|(_, events)| {
+        let cache = cache.clone();
+        let update = update.clone();
+
+        thread::spawn(move || for event in &events {
+            (update)(&cache, event);
+        })
+    }).collect::<Vec<_>>();
+
+    for shard in shards {
+        shard.join().unwrap();
+    }
+
+    let elapsed = started.elapsed();
+    done.store(true, Ordering::SeqCst);
+
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    let secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) / 1e9;
+
+    println!("{}: {:.3}s, {:.0} events/s, {:.0} lookups/s",
+             name,
+             secs,
+             count as f64 / secs,
+             lookups.load(Ordering::SeqCst) as f64 / secs);
+}
+
+fn recorded(path: &str) -> Vec<(Option<[u64; 2]>, Value)> {
+    Recording::open(path)
+        .expect("Error opening recording")
+        .map(|recorded| {
+            let recorded = recorded.expect("Error reading recording");
+
+            (recorded.shard_info, recorded.payload)
+        })
+        .collect()
+}
+
+// Generates a stream of presence, member and voice state updates to copies of
+// the large guild, with the guilds split between shards.
+fn generated() -> Vec<(Option<[u64; 2]>, Value)> {
+    let file = File::open("./tests/resources/guild_create_1.json").unwrap();
+    let guild: Value = serde_json::from_reader(file).unwrap();
+    let channel_id = guild["channels"][0]["id"].clone();
+    let users = guild["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|member| member["user"].clone())
+        .collect::<Vec<_>>();
+
+    let mut payloads = vec![];
+    let mut guilds = vec![];
+
+    for n in 1..SHARDS * GUILDS_PER_SHARD + 1 {
+        let guild_id = (n << 22).to_string();
+        let shard = Some([n % SHARDS, SHARDS]);
+
+        let mut guild = guild.clone();
+        guild["id"] = Value::String(guild_id.clone());
+
+        payloads.push((shard, dispatch("GUILD_CREATE", guild)));
+        guilds.push((guild_id, shard));
+    }
+
+    for i in 0..EVENTS_PER_GUILD {
+        let user = &users[i % users.len()];
+        let first_half = i % 8 < 4;
+
+        for &(ref guild_id, shard) in &guilds {
+            let payload = match i % 4 {
+                0 | 1 => dispatch("PRESENCE_UPDATE", json!({
+                    "game": null,
+                    "guild_id": guild_id,
+                    "status": if first_half { "online" } else { "idle" },
+                    "user": {"id": user["id"]},
+                })),
+                2 => dispatch("GUILD_MEMBER_UPDATE", json!({
+                    "guild_id": guild_id,
+                    "nick": format!("nick {}", i),
+                    "roles": [],
+                    "user": user,
+                })),
+                _ => dispatch("VOICE_STATE_UPDATE", json!({
+                    "channel_id": if first_half { channel_id.clone() } else { Value::Null },
+                    "deaf": false,
+                    "guild_id": guild_id,
+                    "mute": false,
+                    "self_deaf": false,
+                    "self_mute": false,
+                    "session_id": "",
+                    "suppress": false,
+                    "user_id": user["id"],
+                })),
+            };
+
+            payloads.push((shard, payload));
+        }
+    }
+
+    payloads
+}
+
+fn dispatch(kind: &str, data: Value) -> Value {
+    json!({"op": 0, "s": 0, "t": kind, "d": data})
+}
+
+fn decode(payloads: Vec<(Option<[u64; 2]>, Value)>) -> Stream {
+    let mut stream = Stream::new();
+
+    for (shard, payload) in payloads {
+        if let Ok(GatewayEvent::Dispatch(_, event)) = GatewayEvent::decode(payload) {
+            stream.entry(shard).or_insert_with(Vec::new).push(event);
+        }
+    }
+
+    stream
+}
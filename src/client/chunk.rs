@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use ::model::event::GuildMembersChunkEvent;
+use ::model::{GuildId, UserId};
+
+#[cfg(feature="cache")]
+use ::client::CACHE;
+
+/// The maximum number of members that Discord sends in a single chunk.
+const CHUNK_SIZE: usize = 1000;
+
+static NONCE: AtomicUsize = ATOMIC_USIZE_INIT;
+
+lazy_static! {
+    static ref PENDING: Mutex<HashMap<String, Arc<Progress>>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Copy, Default)]
+struct Received {
+    chunks: u64,
+    members: u64,
+}
+
+#[derive(Default)]
+struct State {
+    /// The guilds which have not yet received all of their chunks, with what
+    /// has been received for them so far.
+    remaining: HashMap<GuildId, Received>,
+    not_found: Vec<UserId>,
+}
+
+// Stops tracking a request once every handle to it has been dropped, so that
+// requests whose chunks never arrive are not kept around forever.
+struct Request {
+    nonce: String,
+    progress: Arc<Progress>,
+}
+
+impl Drop for Request {
+    fn drop(&mut self) {
+        PENDING.lock().unwrap().remove(&self.nonce);
+    }
+}
+
+#[derive(Default)]
+struct Progress {
+    condvar: Condvar,
+    state: Mutex<State>,
+}
+
+/// A handle to a request for the members of one or more guilds, made via
+/// [`Context::request_guild_members`] or [`Shard::request_guild_members`].
+///
+/// The request is complete once every chunk of members for every requested
+/// guild has been received. If the `cache` feature is enabled, the members
+/// will have been inserted into the [`Cache`] by that point.
+///
+/// **Note**: If the shard reconnects before all chunks have been received, the
+/// remaining chunks will never arrive. Prefer [`wait_timeout`] over [`wait`]
+/// where this matters. A request stops being tracked once it times out, or
+/// once every handle to it has been dropped.
+///
+/// [`Cache`]: ../ext/cache/struct.Cache.html
+/// [`Context::request_guild_members`]: struct.Context.html#method.request_guild_members
+/// [`Shard::request_guild_members`]: gateway/struct.Shard.html#method.request_guild_members
+/// [`wait`]: #method.wait
+/// [`wait_timeout`]: #method.wait_timeout
+#[derive(Clone)]
+pub struct MemberChunks(Arc<Request>);
+
+impl MemberChunks {
+    #[doc(hidden)]
+    pub fn new(guild_ids: &[GuildId]) -> MemberChunks {
+        let nonce = NONCE.fetch_add(1, Ordering::SeqCst).to_string();
+        let progress = Arc::new(Progress::default());
+
+        {
+            let mut state = progress.state.lock().unwrap();
+            state.remaining = guild_ids
+                .iter()
+                .map(|guild_id| (*guild_id, Received::default()))
+                .collect();
+        }
+
+        if !guild_ids.is_empty() {
+            PENDING.lock().unwrap().insert(nonce.clone(), progress.clone());
+        }
+
+        MemberChunks(Arc::new(Request {
+            nonce: nonce,
+            progress: progress,
+        }))
+    }
+
+    /// Marks a received chunk against the request it was in response to, if
+    /// any.
+    ///
+    /// This must be called after the chunk has been applied to the cache.
+    #[doc(hidden)]
+    pub fn received(event: &GuildMembersChunkEvent) {
+        let nonce = match event.nonce {
+            Some(ref nonce) => nonce,
+            None => return,
+        };
+
+        let progress = match PENDING.lock().unwrap().get(nonce) {
+            Some(progress) => progress.clone(),
+            None => return,
+        };
+
+        let mut state = progress.state.lock().unwrap();
+        state.not_found.extend(event.not_found.iter().cloned());
+
+        let done = match state.remaining.get_mut(&event.guild_id) {
+            Some(received) => {
+                received.chunks += 1;
+                received.members += event.members.len() as u64;
+
+                // Older gateway versions do not send the chunk count; in that
+                // case, the final chunk is the first one that is not full, or
+                // the one which brings the total up to the guild's size.
+                match event.chunk_count {
+                    Some(count) => received.chunks >= count,
+                    None => {
+                        event.members.len() < CHUNK_SIZE
+                            || has_all_members(event.guild_id, received.members)
+                    },
+                }
+            },
+            None => false,
+        };
+
+        if done {
+            state.remaining.remove(&event.guild_id);
+        }
+
+        if state.remaining.is_empty() {
+            PENDING.lock().unwrap().remove(nonce);
+            progress.condvar.notify_all();
+        }
+    }
+
+    /// Whether every chunk for every requested guild has been received.
+    pub fn is_complete(&self) -> bool {
+        self.0.progress.state.lock().unwrap().remaining.is_empty()
+    }
+
+    /// The nonce that was sent with the request, which is also present on
+    /// each [`GuildMembersChunkEvent`] sent in response.
+    ///
+    /// [`GuildMembersChunkEvent`]: ../model/event/struct.GuildMembersChunkEvent.html
+    pub fn nonce(&self) -> &str {
+        &self.0.nonce
+    }
+
+    /// The Ids of requested users which were found to not be members of the
+    /// guilds, in the chunks that have been received so far.
+    pub fn not_found(&self) -> Vec<UserId> {
+        self.0.progress.state.lock().unwrap().not_found.clone()
+    }
+
+    /// Blocks the current thread until the request is complete.
+    pub fn wait(&self) {
+        let mut state = self.0.progress.state.lock().unwrap();
+
+        while !state.remaining.is_empty() {
+            state = self.0.progress.condvar.wait(state).unwrap();
+        }
+    }
+
+    /// Blocks the current thread until the request is complete, or until the
+    /// timeout elapses.
+    ///
+    /// Returns whether the request completed. If it did not, the request is
+    /// abandoned, and chunks received afterwards are no longer tracked.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.0.progress.state.lock().unwrap();
+
+        while !state.remaining.is_empty() {
+            let now = Instant::now();
+
+            if now >= deadline {
+                PENDING.lock().unwrap().remove(&self.0.nonce);
+
+                return false;
+            }
+
+            state = self.0.progress.condvar.wait_timeout(state, deadline - now).unwrap().0;
+        }
+
+        true
+    }
+}
+
+// Whether the number of members received for a guild is its total number of
+// members, which is only known if the guild is cached.
+#[cfg(feature="cache")]
+fn has_all_members(guild_id: GuildId, received: u64) -> bool {
+    CACHE.read()
+        .unwrap()
+        .guild(guild_id)
+        .map_or(false, |guild| received >= guild.read().unwrap().member_count)
+}
+
+#[cfg(not(feature="cache"))]
+fn has_all_members(_: GuildId, _: u64) -> bool {
+    false
+}
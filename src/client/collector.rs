@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use ::model::{Message, Reaction};
+
+static ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+lazy_static! {
+    static ref MESSAGES: Collectors<Message> = Collectors::default();
+    static ref REACTIONS: Collectors<Reaction> = Collectors::default();
+}
+
+struct Collector<T> {
+    filter: Box<Fn(&T) -> bool + Send>,
+    id: usize,
+    sender: Sender<T>,
+}
+
+struct Collectors<T> {
+    collectors: Mutex<Vec<Collector<T>>>,
+}
+
+impl<T: Clone> Collectors<T> {
+    fn register<F>(&self, filter: F) -> (usize, Receiver<T>)
+        where F: Fn(&T) -> bool + Send + 'static {
+        let id = ID.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = mpsc::channel();
+
+        self.collectors.lock().unwrap().push(Collector {
+            filter: Box::new(filter),
+            id: id,
+            sender: tx,
+        });
+
+        (id, rx)
+    }
+
+    fn unregister(&self, id: usize) {
+        self.collectors.lock().unwrap().retain(|collector| collector.id != id);
+    }
+
+    fn received(&self, item: &T) {
+        let mut collectors = self.collectors.lock().unwrap();
+
+        // Collectors whose receiver has gone away are removed.
+        collectors.retain(|collector| {
+            !(collector.filter)(item) || collector.sender.send(item.clone()).is_ok()
+        });
+    }
+
+    /// Collects matching items until `max` have been collected or the timeout
+    /// expires.
+    fn collect<F>(&self, filter: F, max: usize, timeout: Duration) -> Vec<T>
+        where F: Fn(&T) -> bool + Send + 'static {
+        let (id, rx) = self.register(filter);
+        let deadline = Instant::now() + timeout;
+        let mut items = vec![];
+
+        while items.len() < max {
+            let now = Instant::now();
+
+            if now >= deadline {
+                break;
+            }
+
+            match rx.recv_timeout(deadline - now) {
+                Ok(item) => items.push(item),
+                Err(_) => break,
+            }
+        }
+
+        self.unregister(id);
+
+        items
+    }
+}
+
+impl<T> Default for Collectors<T> {
+    fn default() -> Collectors<T> {
+        Collectors {
+            collectors: Mutex::new(vec![]),
+        }
+    }
+}
+
+/// Waits for the next message received on any shard which matches the filter.
+pub fn await_message<F>(filter: F, timeout: Duration) -> Option<Message>
+    where F: Fn(&Message) -> bool + Send + 'static {
+    MESSAGES.collect(filter, 1, timeout).pop()
+}
+
+/// Collects reactions added on any shard which match the filter.
+pub fn await_reactions<F>(filter: F, max: usize, timeout: Duration) -> Vec<Reaction>
+    where F: Fn(&Reaction) -> bool + Send + 'static {
+    REACTIONS.collect(filter, max, timeout)
+}
+
+/// Passes a received message to any waiting collectors.
+pub fn message_received(message: &Message) {
+    MESSAGES.received(message);
+}
+
+/// Passes an added reaction to any waiting collectors.
+pub fn reaction_added(reaction: &Reaction) {
+    REACTIONS.received(reaction);
+}
@@ -1,8 +1,11 @@
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use super::collector;
 use super::gateway::Shard;
+use super::MemberChunks;
 use super::rest;
 use typemap::ShareMap;
-use ::utils::builder::EditProfile;
+use ::utils::builder::{EditProfile, RequestGuildMembers};
 use ::internal::prelude::*;
 use ::model::*;
 
@@ -43,6 +46,13 @@ pub struct Context {
     pub shard: Arc<Mutex<Shard>>,
     /// The queue of messages that are sent after context goes out of scope.
     pub queue: String,
+    /// The raw payload of the dispatch that the event was decoded from.
+    ///
+    /// This is only present if [`Client::keep_raw_events`] is enabled, and
+    /// can be used to access fields which the models do not yet support.
+    ///
+    /// [`Client::keep_raw_events`]: struct.Client.html#method.keep_raw_events
+    pub raw_event: Option<Arc<Value>>,
 }
 
 impl Context {
@@ -62,9 +72,60 @@ impl Context {
             data: data,
             shard: shard,
             queue: String::new(),
+            raw_event: None,
         }
     }
 
+    /// Blocks the current thread until a message matching the filter is
+    /// received on any shard, returning `None` if the timeout expires first.
+    ///
+    /// This does not need an [`on_message`] handler to be attached. Messages
+    /// received before this is called are not matched.
+    ///
+    /// **Note**: This should be called from a command or event handler, which
+    /// are run on the client's worker threads, rather than from a shard's
+    /// thread.
+    ///
+    /// **Note**: The worker thread is occupied until this returns. While every
+    /// worker is waiting, further events queue up and - with the default
+    /// [`Backpressure::Block`] - the shards stop receiving events once the
+    /// queues are full, so the awaited message may not arrive until the timeout.
+    /// Keep the timeout short, or increase the pool's [`size`].
+    ///
+    /// # Examples
+    ///
+    /// Ask the author of a message to confirm an action:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::client::Context;
+    /// # use serenity::model::Message;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn confirm(context: &Context, message: &Message) {
+    /// let _ = message.reply("Are you sure? (yes/no)");
+    ///
+    /// let (author_id, channel_id) = (message.author.id, message.channel_id);
+    ///
+    /// let reply = context.await_message(move |m| {
+    ///     m.author.id == author_id && m.channel_id == channel_id
+    /// }, Duration::from_secs(30));
+    ///
+    /// match reply {
+    ///     Some(ref reply) if reply.content == "yes" => println!("Confirmed"),
+    ///     Some(_) => println!("Cancelled"),
+    ///     None => println!("Timed out"),
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`Backpressure::Block`]: enum.Backpressure.html#variant.Block
+    /// [`on_message`]: struct.Client.html#method.on_message
+    /// [`size`]: struct.PoolConfig.html#method.size
+    pub fn await_message<F>(&self, filter: F, timeout: Duration) -> Option<Message>
+        where F: Fn(&Message) -> bool + Send + 'static {
+        collector::await_message(filter, timeout)
+    }
+
     /// Edits the current user's profile settings.
     ///
     /// Refer to `EditProfile`'s documentation for its methods.
@@ -134,6 +195,38 @@ impl Context {
         self.shard.lock().unwrap().set_status(OnlineStatus::Invisible);
     }
 
+    /// Requests members of one or multiple [`Guild`]s over the shard which
+    /// dispatched the event, returning a handle which completes once every
+    /// chunk of members has been received.
+    ///
+    /// Refer to [`Shard::request_guild_members`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Fetch all members of the guild a message was sent in before counting
+    /// them:
+    ///
+    /// ```rust,ignore
+    /// // assuming you are in a context and have a `guild_id`
+    ///
+    /// let chunks = context.request_guild_members(&[guild_id], |r| r);
+    ///
+    /// if chunks.wait_timeout(Duration::from_secs(30)) {
+    ///     let count = CACHE.read().unwrap().guild(guild_id).map(|g| g.read().unwrap().members.len());
+    ///
+    ///     println!("{:?} members", count);
+    /// }
+    /// ```
+    ///
+    /// [`Guild`]: ../model/struct.Guild.html
+    /// [`Shard::request_guild_members`]: gateway/struct.Shard.html#method.request_guild_members
+    pub fn request_guild_members<F>(&self, guild_ids: &[GuildId], f: F) -> MemberChunks
+        where F: FnOnce(RequestGuildMembers) -> RequestGuildMembers {
+        self.shard.lock()
+            .unwrap()
+            .request_guild_members(guild_ids, f)
+    }
+
     /// "Resets" the current user's presence, by setting the game to `None`,
     /// the online status to [`Online`], and `afk` to `false`.
     ///
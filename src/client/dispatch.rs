@@ -1,29 +1,98 @@
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
-use super::event_store::EventStore;
+use super::MemberChunks;
+use super::collector;
+use super::event_store::{Callback, EventStore};
+use super::pool::WorkerPool;
+use super::ready::{self, Completion};
 use super::Context;
 use super::gateway::Shard;
 use typemap::ShareMap;
+use ::internal::prelude::*;
 use ::model::event::Event;
-use ::model::{ChannelId, Message};
+use ::model::{Channel, ChannelId, GuildId, GuildStatus, Message};
+
+#[cfg(feature="cache")]
+use ::model::{Change, Member, VoiceState};
 
 #[cfg(feature="framework")]
 use ::ext::framework::Framework;
 
 #[cfg(feature="cache")]
 use super::CACHE;
+#[cfg(feature="cache")]
+use ::ext::cache::lookup;
 
+// Retrieves the closures registered for an event along with the
+// `EventHandler`s, or `None` if there are neither.
 macro_rules! handler {
     ($field:ident, $event_store:ident) => {
-        $event_store.read()
-            .unwrap()
-            .$field
-            .as_ref()
-            .cloned()
+        {
+            let event_store = $event_store.read().unwrap();
+
+            event_store.$field.callbacks(&event_store.handlers)
+        }
     }
 }
 
+// Calls each handler in registration order, on a worker of the pool, with
+// clones of the context and arguments. `EventHandler`s have the given method
+// called.
+macro_rules! call {
+    ($pool:ident, $key:ident, $handlers:ident, $method:ident($context:ident$(, $arg:expr)*)) => {
+        $pool.execute($key, move || {
+            for handler in &$handlers {
+                match *handler {
+                    Callback::Closure(ref closure) => {
+                        (closure)($context.clone()$(, $arg.clone())*)
+                    },
+                    Callback::Handler(ref handler) => {
+                        handler.$method($context.clone()$(, $arg.clone())*)
+                    },
+                }
+            }
+        })
+    }
+}
+
+// Updates the cache while only holding a read lock on it - and so only locking
+// the guild or channel being updated - optionally falling back to the write
+// lock if the update could not be done in place.
 macro_rules! update {
+    (shared $method:ident, $event:expr) => {
+        {
+            #[cfg(feature="cache")]
+            {
+                CACHE.read().unwrap().$method(&$event)
+            }
+        }
+    };
+    (shared $method:ident or $fallback:ident, @$event:expr) => {
+        {
+            #[cfg(feature="cache")]
+            {
+                let updated = CACHE.read().unwrap().$method(&mut $event);
+
+                match updated {
+                    Some(updated) => updated,
+                    None => CACHE.write().unwrap().$fallback(&mut $event),
+                }
+            }
+        }
+    };
+    (shared $method:ident or $fallback:ident, $event:expr) => {
+        {
+            #[cfg(feature="cache")]
+            {
+                let updated = CACHE.read().unwrap().$method(&$event);
+
+                match updated {
+                    Some(updated) => updated,
+                    None => CACHE.write().unwrap().$fallback(&$event),
+                }
+            }
+        }
+    };
     ($method:ident, @$event:expr) => {
         {
             #[cfg(feature="cache")]
@@ -60,8 +129,12 @@ macro_rules! update {
 
 fn context(channel_id: Option<ChannelId>,
            conn: &Arc<Mutex<Shard>>,
-           data: &Arc<Mutex<ShareMap>>) -> Context {
-    Context::new(channel_id, conn.clone(), data.clone())
+           data: &Arc<Mutex<ShareMap>>,
+           raw: &Option<Arc<Value>>) -> Context {
+    let mut context = Context::new(channel_id, conn.clone(), data.clone());
+    context.raw_event = raw.clone();
+
+    context
 }
 
 #[cfg(feature="framework")]
@@ -69,25 +142,53 @@ pub fn dispatch(event: Event,
                 conn: &Arc<Mutex<Shard>>,
                 framework: &Arc<Mutex<Framework>>,
                 data: &Arc<Mutex<ShareMap>>,
-                event_store: &Arc<RwLock<EventStore>>) {
+                event_store: &Arc<RwLock<EventStore>>,
+                pool: &WorkerPool,
+                raw: Option<Arc<Value>>) {
+    let event = match apply_middleware(event, conn, event_store) {
+        Some(event) => event,
+        None => return,
+    };
+
+    #[cfg(feature="cache")]
+    {
+        CACHE.read().unwrap().record_event(&event);
+        update_sink(&event);
+        lookup::update(&event);
+    }
+
+    let copy = if event_store.read().unwrap().events.is_empty() {
+        None
+    } else {
+        Some(event.clone())
+    };
+
     match event {
         Event::MessageCreate(event) => {
+            update!(shared try_update_with_message_create or update_with_message_create, event);
+
             let context = context(Some(event.message.channel_id),
                                   conn,
-                                  data);
+                                  data,
+                                  &raw);
             let mut framework = framework.lock().unwrap();
 
             if framework.initialized {
                 dispatch_message(context.clone(),
                                  event.message.clone(),
-                                 event_store);
+                                 event_store,
+                                 pool);
 
-                framework.dispatch(context, event.message);
+                framework.dispatch(context, event.message, pool);
             } else {
-                dispatch_message(context, event.message, event_store);
+                dispatch_message(context, event.message, event_store, pool);
             }
         },
-        other => handle_event(other, conn, data, event_store),
+        other => handle_event(other, conn, data, event_store, pool, raw),
+    }
+
+    if let Some(event) = copy {
+        publish(event, conn, event_store);
     }
 }
 
@@ -95,23 +196,386 @@ pub fn dispatch(event: Event,
 pub fn dispatch(event: Event,
                 conn: &Arc<Mutex<Shard>>,
                 data: &Arc<Mutex<ShareMap>>,
-                event_store: &Arc<RwLock<EventStore>>) {
+                event_store: &Arc<RwLock<EventStore>>,
+                pool: &WorkerPool,
+                raw: Option<Arc<Value>>) {
+    let event = match apply_middleware(event, conn, event_store) {
+        Some(event) => event,
+        None => return,
+    };
+
+    #[cfg(feature="cache")]
+    {
+        CACHE.read().unwrap().record_event(&event);
+        update_sink(&event);
+        lookup::update(&event);
+    }
+
+    let copy = if event_store.read().unwrap().events.is_empty() {
+        None
+    } else {
+        Some(event.clone())
+    };
+
     match event {
         Event::MessageCreate(event) => {
-            let context = context(Some(event.message.channel_id), conn, data);
+            update!(shared try_update_with_message_create or update_with_message_create, event);
+
+            let context = context(Some(event.message.channel_id), conn, data, &raw);
             dispatch_message(context,
                              event.message,
-                             event_store);
+                             event_store,
+                             pool);
         },
-        other => handle_event(other, conn, data, event_store),
+        other => handle_event(other, conn, data, event_store, pool, raw),
+    }
+
+    if let Some(event) = copy {
+        publish(event, conn, event_store);
     }
 }
 
+// Sends the event to each `Events` iterator, removing those which have been
+// dropped. The senders are cloned out of the event store, so that a blocking
+// send does not hold its lock.
+fn publish(event: Event, conn: &Arc<Mutex<Shard>>, event_store: &Arc<RwLock<EventStore>>) {
+    let shard_info = conn.lock().unwrap().shard_info();
+    let senders = event_store.read().unwrap().events.clone();
+
+    let closed = senders.into_iter()
+        .filter(|sender| !sender.send((shard_info, event.clone())))
+        .collect::<Vec<_>>();
+
+    if !closed.is_empty() {
+        event_store.write()
+            .unwrap()
+            .events
+            .retain(|sender| !closed.iter().any(|closed| closed.same(sender)));
+    }
+}
+
+// Applies the event to the sink set via `Client::with_cache_sink`, if any. The
+// global `CACHE` is updated separately, while handling the event.
+#[cfg(feature="cache")]
+fn update_sink(event: &Event) {
+    if let Some(sink) = ::ext::cache::sink() {
+        sink.update(event);
+    }
+}
+
+// Passes the event through each middleware in the order they were added,
+// stopping if one of them drops it.
+fn apply_middleware(event: Event,
+                    conn: &Arc<Mutex<Shard>>,
+                    event_store: &Arc<RwLock<EventStore>>)
+                    -> Option<Event> {
+    let middleware = match event_store.read().unwrap().middleware.get() {
+        Some(middleware) => middleware,
+        None => return Some(event),
+    };
+
+    let mut event = event;
+
+    for middleware in &middleware {
+        event = match (middleware)(conn, event) {
+            Some(event) => event,
+            None => return None,
+        };
+    }
+
+    Some(event)
+}
+
 fn dispatch_message(context: Context,
                     message: Message,
-                    event_store: &Arc<RwLock<EventStore>>) {
-    if let Some(handler) = handler!(on_message, event_store) {
-        thread::spawn(move || (handler)(context, message));
+                    event_store: &Arc<RwLock<EventStore>>,
+                    pool: &WorkerPool) {
+    collector::message_received(&message);
+
+    if let Some(handlers) = handler!(on_message, event_store) {
+        let key = pool.key(Some(message.channel_id), None);
+
+        call!(pool, key, handlers, on_message(context, message));
+    }
+}
+
+// Waits for the guilds which were unavailable in a shard's READY to be
+// received, dispatching the cache ready events once they all have been or the
+// timeout has passed.
+fn wait_for_guilds(guild_ids: &[GuildId],
+                   conn: &Arc<Mutex<Shard>>,
+                   data: &Arc<Mutex<ShareMap>>,
+                   event_store: &Arc<RwLock<EventStore>>,
+                   pool: &WorkerPool) {
+    let shard_info = conn.lock().unwrap().shard_info();
+    let (generation, completion) = event_store.read()
+        .unwrap()
+        .ready
+        .lock()
+        .unwrap()
+        .started(shard_info, guild_ids);
+
+    if let Some(completion) = completion {
+        cache_ready(completion, conn, data, event_store, pool);
+
+        return;
+    }
+
+    let conn = conn.clone();
+    let data = data.clone();
+    let event_store = event_store.clone();
+    let pool = pool.clone();
+
+    thread::spawn(move || {
+        thread::sleep(ready::timeout());
+
+        let completion = event_store.read()
+            .unwrap()
+            .ready
+            .lock()
+            .unwrap()
+            .timed_out(shard_info, generation);
+
+        if let Some(completion) = completion {
+            cache_ready(completion, &conn, &data, &event_store, &pool);
+        }
+    });
+}
+
+fn guild_received(guild_id: GuildId,
+                  available: bool,
+                  conn: &Arc<Mutex<Shard>>,
+                  data: &Arc<Mutex<ShareMap>>,
+                  event_store: &Arc<RwLock<EventStore>>,
+                  pool: &WorkerPool) {
+    let shard_info = conn.lock().unwrap().shard_info();
+
+    let completion = event_store.read()
+        .unwrap()
+        .ready
+        .lock()
+        .unwrap()
+        .guild_received(shard_info, guild_id, available);
+
+    if let Some(completion) = completion {
+        cache_ready(completion, conn, data, event_store, pool);
+    }
+}
+
+fn cache_ready(completion: Completion,
+               conn: &Arc<Mutex<Shard>>,
+               data: &Arc<Mutex<ShareMap>>,
+               event_store: &Arc<RwLock<EventStore>>,
+               pool: &WorkerPool) {
+    let key = None;
+
+    if let Some(handlers) = handler!(on_shard_cache_ready, event_store) {
+        let context = context(None, conn, data, &None);
+        let unavailable = completion.shard;
+
+        call!(pool, key, handlers, on_shard_cache_ready(context, unavailable));
+    }
+
+    if let Some(unavailable) = completion.client {
+        client_cache_ready(unavailable, conn, data, event_store, pool);
+    }
+}
+
+// Stops expecting a shard which failed to start, dispatching the client's
+// cache ready event if the shards which did start are all ready. The context
+// is that of a shard which started, as the failed shard has none.
+pub fn shard_failed(conn: Option<&Arc<Mutex<Shard>>>,
+                    data: &Arc<Mutex<ShareMap>>,
+                    event_store: &Arc<RwLock<EventStore>>,
+                    pool: &WorkerPool) {
+    let unavailable = event_store.read()
+        .unwrap()
+        .ready
+        .lock()
+        .unwrap()
+        .shard_failed();
+
+    if let (Some(unavailable), Some(conn)) = (unavailable, conn) {
+        client_cache_ready(unavailable, conn, data, event_store, pool);
+    }
+}
+
+fn client_cache_ready(unavailable: Vec<GuildId>,
+                      conn: &Arc<Mutex<Shard>>,
+                      data: &Arc<Mutex<ShareMap>>,
+                      event_store: &Arc<RwLock<EventStore>>,
+                      pool: &WorkerPool) {
+    if let Some(handlers) = handler!(on_cache_ready, event_store) {
+        let context = context(None, conn, data, &None);
+
+        call!(pool, None, handlers, on_cache_ready(context, unavailable));
+    }
+}
+
+// Retrieves the change in name of a guild channel from the cached version.
+#[cfg(feature="cache")]
+fn channel_rename(before: Option<&Channel>, after: &Channel) -> Option<Change<String>> {
+    match (before, after) {
+        (Some(&Channel::Guild(ref before)), &Channel::Guild(ref after)) => {
+            let before = before.read().unwrap();
+            let after = after.read().unwrap();
+
+            Change::new(&*before, &*after).field(|channel| &channel.name)
+        },
+        _ => None,
+    }
+}
+
+// Dispatches the events derived from a change to a cached member.
+#[cfg(feature="cache")]
+fn dispatch_member_changes(guild_id: GuildId,
+                           change: &Change<Member>,
+                           context: &Context,
+                           event_store: &Arc<RwLock<EventStore>>,
+                           pool: &WorkerPool,
+                           key: Option<u64>) {
+    if let Some(handlers) = handler!(on_guild_member_nickname_update, event_store) {
+        if let Some(nick) = change.field(|member| &member.nick) {
+            let (context, member) = (context.clone(), change.new.clone());
+
+            call!(pool, key, handlers,
+                  on_guild_member_nickname_update(context, guild_id, member, nick));
+        }
+    }
+
+    if let Some(handlers) = handler!(on_guild_member_role_add, event_store) {
+        for role_id in change.added(|member| &member.roles) {
+            let (context, handlers, member) = (context.clone(), handlers.clone(), change.new.clone());
+
+            call!(pool, key, handlers,
+                  on_guild_member_role_add(context, guild_id, member, role_id));
+        }
+    }
+
+    if let Some(handlers) = handler!(on_guild_member_role_remove, event_store) {
+        for role_id in change.removed(|member| &member.roles) {
+            let (context, handlers, member) = (context.clone(), handlers.clone(), change.new.clone());
+
+            call!(pool, key, handlers,
+                  on_guild_member_role_remove(context, guild_id, member, role_id));
+        }
+    }
+}
+
+// Dispatches the events derived from a change to a user's voice state, where
+// `before` is the cached state - if the user was in a voice channel.
+#[cfg(feature="cache")]
+fn dispatch_voice_changes(guild_id: GuildId,
+                          before: Option<VoiceState>,
+                          after: &VoiceState,
+                          context: &Context,
+                          event_store: &Arc<RwLock<EventStore>>,
+                          pool: &WorkerPool,
+                          key: Option<u64>) {
+    let user_id = after.user_id;
+
+    match (before.as_ref().and_then(|state| state.channel_id), after.channel_id) {
+        (None, Some(channel_id)) => {
+            if let Some(handlers) = handler!(on_voice_channel_join, event_store) {
+                let context = context.clone();
+
+                call!(pool, key, handlers,
+                      on_voice_channel_join(context, guild_id, user_id, channel_id));
+            }
+        },
+        (Some(channel_id), None) => {
+            if let Some(handlers) = handler!(on_voice_channel_leave, event_store) {
+                let context = context.clone();
+
+                call!(pool, key, handlers,
+                      on_voice_channel_leave(context, guild_id, user_id, channel_id));
+            }
+        },
+        (Some(old), Some(new)) if old != new => {
+            if let Some(handlers) = handler!(on_voice_channel_move, event_store) {
+                let (context, change) = (context.clone(), Change::new(old, new));
+
+                call!(pool, key, handlers,
+                      on_voice_channel_move(context, guild_id, user_id, change));
+            }
+        },
+        _ => {},
+    }
+
+    // Server mute and deafen are only compared while the user remains in a
+    // voice channel.
+    let change = match before {
+        Some(ref before) if after.channel_id.is_some() => Change::new(before, after),
+        _ => return,
+    };
+
+    if let Some(handlers) = handler!(on_voice_server_deaf_update, event_store) {
+        if let Some(deaf) = change.field(|state| &state.deaf) {
+            let (context, deafened) = (context.clone(), deaf.new);
+
+            call!(pool, key, handlers,
+                  on_voice_server_deaf_update(context, guild_id, user_id, deafened));
+        }
+    }
+
+    if let Some(handlers) = handler!(on_voice_server_mute_update, event_store) {
+        if let Some(mute) = change.field(|state| &state.mute) {
+            let (context, muted) = (context.clone(), mute.new);
+
+            call!(pool, key, handlers,
+                  on_voice_server_mute_update(context, guild_id, user_id, muted));
+        }
+    }
+}
+
+// The channel and guild which an event relates to, if known, for determining
+// its ordering key.
+fn ids(event: &Event) -> (Option<ChannelId>, Option<GuildId>) {
+    fn channel(channel: &Channel) -> (Option<ChannelId>, Option<GuildId>) {
+        match *channel {
+            Channel::Guild(ref c) => {
+                let c = c.read().unwrap();
+
+                (Some(c.id), Some(c.guild_id))
+            },
+            _ => (Some(channel.id()), None),
+        }
+    }
+
+    match *event {
+        Event::ChannelCreate(ref e) => channel(&e.channel),
+        Event::ChannelDelete(ref e) => channel(&e.channel),
+        Event::ChannelUpdate(ref e) => channel(&e.channel),
+        Event::ChannelPinsUpdate(ref e) => (Some(e.channel_id), None),
+        Event::ChannelRecipientAdd(ref e) => (Some(e.channel_id), None),
+        Event::ChannelRecipientRemove(ref e) => (Some(e.channel_id), None),
+        Event::GuildBanAdd(ref e) => (None, Some(e.guild_id)),
+        Event::GuildBanRemove(ref e) => (None, Some(e.guild_id)),
+        Event::GuildCreate(ref e) => (None, Some(e.guild.id)),
+        Event::GuildDelete(ref e) => (None, Some(e.guild.id)),
+        Event::GuildEmojisUpdate(ref e) => (None, Some(e.guild_id)),
+        Event::GuildIntegrationsUpdate(ref e) => (None, Some(e.guild_id)),
+        Event::GuildMemberAdd(ref e) => (None, Some(e.guild_id)),
+        Event::GuildMemberRemove(ref e) => (None, Some(e.guild_id)),
+        Event::GuildMemberUpdate(ref e) => (None, Some(e.guild_id)),
+        Event::GuildMembersChunk(ref e) => (None, Some(e.guild_id)),
+        Event::GuildRoleCreate(ref e) => (None, Some(e.guild_id)),
+        Event::GuildRoleDelete(ref e) => (None, Some(e.guild_id)),
+        Event::GuildRoleUpdate(ref e) => (None, Some(e.guild_id)),
+        Event::GuildUnavailable(ref e) => (None, Some(e.guild_id)),
+        Event::GuildUpdate(ref e) => (None, Some(e.guild.id)),
+        Event::MessageCreate(ref e) => (Some(e.message.channel_id), None),
+        Event::MessageDelete(ref e) => (Some(e.channel_id), None),
+        Event::MessageDeleteBulk(ref e) => (Some(e.channel_id), None),
+        Event::MessageUpdate(ref e) => (Some(e.channel_id), None),
+        Event::PresenceUpdate(ref e) => (None, e.guild_id),
+        Event::ReactionAdd(ref e) => (Some(e.reaction.channel_id), None),
+        Event::ReactionRemove(ref e) => (Some(e.reaction.channel_id), None),
+        Event::ReactionRemoveAll(ref e) => (Some(e.channel_id), None),
+        Event::TypingStart(ref e) => (Some(e.channel_id), None),
+        Event::VoiceStateUpdate(ref e) => (e.voice_state.channel_id, e.guild_id),
+        Event::WebhookUpdate(ref e) => (Some(e.channel_id), Some(e.guild_id)),
+        _ => (None, None),
     }
 }
 
@@ -119,102 +583,122 @@ fn dispatch_message(context: Context,
 fn handle_event(event: Event,
                 conn: &Arc<Mutex<Shard>>,
                 data: &Arc<Mutex<ShareMap>>,
-                event_store: &Arc<RwLock<EventStore>>) {
+                event_store: &Arc<RwLock<EventStore>>,
+                pool: &WorkerPool,
+                raw: Option<Arc<Value>>) {
+    let (channel_id, guild_id) = ids(&event);
+    let key = pool.key(channel_id, guild_id);
+
     match event {
         Event::ChannelCreate(event) => {
-            if let Some(handler) = handler!(on_channel_create, event_store) {
+            if let Some(handlers) = handler!(on_channel_create, event_store) {
                 update!(update_with_channel_create, event);
-                let context = context(Some(event.channel.id()), conn, data);
+                let context = context(Some(event.channel.id()), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel));
+                call!(pool, key, handlers, on_channel_create(context, event.channel));
             } else {
                 update!(update_with_channel_create, event);
             }
         },
         Event::ChannelDelete(event) => {
-            if let Some(handler) = handler!(on_channel_delete, event_store) {
+            if let Some(handlers) = handler!(on_channel_delete, event_store) {
                 update!(update_with_channel_delete, event);
-                let context = context(None, conn, data);
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel));
+                call!(pool, key, handlers, on_channel_delete(context, event.channel));
             } else {
                 update!(update_with_channel_delete, event);
             }
         },
         Event::ChannelPinsUpdate(event) => {
-            if let Some(handler) = handler!(on_channel_pins_update, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_channel_pins_update, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                call!(pool, key, handlers, on_channel_pins_update(context, event));
             }
         },
         Event::ChannelRecipientAdd(mut event) => {
             update!(update_with_channel_recipient_add, @event);
 
-            if let Some(handler) = handler!(on_channel_recipient_addition, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_channel_recipient_addition, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel_id, event.user));
+                call!(pool, key, handlers, on_recipient_add(context, event.channel_id, event.user));
             }
         },
         Event::ChannelRecipientRemove(event) => {
             update!(update_with_channel_recipient_remove, event);
 
-            if let Some(handler) = handler!(on_channel_recipient_removal, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_channel_recipient_removal, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel_id, event.user));
+                call!(pool, key, handlers,
+                      on_recipient_remove(context, event.channel_id, event.user));
             }
         },
         Event::ChannelUpdate(event) => {
-            if let Some(handler) = handler!(on_channel_update, event_store) {
-                let context = context(Some(event.channel.id()), conn, data);
+            let channel_id = event.channel.id();
 
-                feature_cache! {{
-                    let before = CACHE.read().unwrap().channel(event.channel.id());
-                    update!(update_with_channel_update, event);
+            feature_cache! {{
+                let before = CACHE.read().unwrap().channel(channel_id);
+                let rename = channel_rename(before.as_ref(), &event.channel);
+                update!(update_with_channel_update, event);
 
-                    thread::spawn(move || (handler)(context, before, event.channel));
-                } else {
-                    thread::spawn(move || (handler)(context, event.channel));
-                }}
+                if let Some(handlers) = handler!(on_channel_update, event_store) {
+                    let context = context(Some(channel_id), conn, data, &raw);
+
+                    call!(pool, key, handlers, on_channel_update(context, before, event.channel));
+                }
+
+                if let Some(handlers) = handler!(on_channel_rename, event_store) {
+                    if let Some(name) = rename {
+                        let context = context(Some(channel_id), conn, data, &raw);
+
+                        call!(pool, key, handlers, on_channel_rename(context, channel_id, name));
+                    }
+                }
             } else {
-                update!(update_with_channel_update, event);
-            }
+                if let Some(handlers) = handler!(on_channel_update, event_store) {
+                    let context = context(Some(channel_id), conn, data, &raw);
+
+                    call!(pool, key, handlers, on_channel_update(context, event.channel));
+                }
+            }}
         },
         Event::GuildBanAdd(event) => {
-            if let Some(handler) = handler!(on_guild_ban_addition, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_ban_addition, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.user));
+                call!(pool, key, handlers, on_member_ban(context, event.guild_id, event.user));
             }
         },
         Event::GuildBanRemove(event) => {
-            if let Some(handler) = handler!(on_guild_ban_removal, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_ban_removal, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.user));
+                call!(pool, key, handlers, on_member_unban(context, event.guild_id, event.user));
             }
         },
         Event::GuildCreate(event) => {
             update!(update_with_guild_create, event);
+            guild_received(event.guild.id, true, conn, data, event_store, pool);
 
-            if let Some(handler) = handler!(on_guild_create, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_create, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild));
+                call!(pool, key, handlers, on_guild_create(context, event.guild));
             }
         },
         Event::GuildDelete(event) => {
-            if let Some(handler) = handler!(on_guild_delete, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_delete, event_store) {
+                let context = context(None, conn, data, &raw);
 
                 feature_cache! {{
                     let full = update!(update_with_guild_delete, event);
 
-                    thread::spawn(move || (handler)(context, event.guild, full));
+                    call!(pool, key, handlers, on_guild_delete(context, event.guild, full));
                 } else {
-                    thread::spawn(move || (handler)(context, event.guild));
+                    call!(pool, key, handlers, on_guild_delete(context, event.guild));
                 }}
             } else {
                 #[cfg(feature="cache")]
@@ -224,40 +708,45 @@ fn handle_event(event: Event,
             }
         },
         Event::GuildEmojisUpdate(event) => {
-            update!(update_with_guild_emojis_update, event);
+            update!(shared update_with_guild_emojis_update, event);
 
-            if let Some(handler) = handler!(on_guild_emojis_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_emojis_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.emojis));
+                call!(pool, key, handlers,
+                      on_guild_emojis_update(context, event.guild_id, event.emojis));
             }
         },
         Event::GuildIntegrationsUpdate(event) => {
-            if let Some(handler) = handler!(on_guild_integrations_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_integrations_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id));
+                call!(pool, key, handlers, on_guild_integrations_update(context, event.guild_id));
             }
         },
         Event::GuildMemberAdd(mut event) => {
-            update!(update_with_guild_member_add, @event);
+            update!(shared try_update_with_guild_member_add or update_with_guild_member_add,
+                    @event);
 
-            if let Some(handler) = handler!(on_guild_member_addition, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_member_addition, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.member));
+                call!(pool, key, handlers,
+                      on_guild_member_add(context, event.guild_id, event.member));
             }
         },
         Event::GuildMemberRemove(event) => {
-            if let Some(handler) = handler!(on_guild_member_removal, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_member_removal, event_store) {
+                let context = context(None, conn, data, &raw);
 
                 feature_cache! {{
                     let member = update!(update_with_guild_member_remove, event);
 
-                    thread::spawn(move || (handler)(context, event.guild_id, event.user, member));
+                    call!(pool, key, handlers,
+                          on_guild_member_remove(context, event.guild_id, event.user, member));
                 } else {
-                    thread::spawn(move || (handler)(context, event.guild_id, event.user));
+                    call!(pool, key, handlers,
+                          on_guild_member_remove(context, event.guild_id, event.user));
                 }}
             } else {
                 #[cfg(feature="cache")]
@@ -267,95 +756,121 @@ fn handle_event(event: Event,
             }
         },
         Event::GuildMemberUpdate(event) => {
-            if let Some(handler) = handler!(on_guild_member_update, event_store) {
-                let context = context(None, conn, data);
+            feature_cache! {{
+                let (before, after) = update!(shared try_update_with_guild_member_update
+                                              or update_with_guild_member_update, event);
+                let handlers = handler!(on_guild_member_update, event_store);
 
-                feature_cache! {{
-                    let before = update!(update_with_guild_member_update, event);
+                if handlers.is_some() || before.is_some() {
+                    let change = before.clone().map(|before| Change::new(before, after.clone()));
 
-                    // This is safe to unwrap, as the update would have created
-                    // the member if it did not exist. So, there is be _no_ way
-                    // that this could fail under any circumstance.
-                    let after = CACHE.read()
-                        .unwrap()
-                        .member(event.guild_id, event.user.id)
-                        .unwrap()
-                        .clone();
+                    if let Some(handlers) = handlers {
+                        let context = context(None, conn, data, &raw);
 
-                    thread::spawn(move || (handler)(context, before, after));
-                } else {
-                    thread::spawn(move || (handler)(context, event));
-                }}
+                        call!(pool, key, handlers, on_guild_member_update(context, before, after));
+                    }
+
+                    if let Some(change) = change {
+                        let context = context(None, conn, data, &raw);
+
+                        dispatch_member_changes(event.guild_id, &change, &context, event_store, pool, key);
+                    }
+                }
             } else {
-                update!(update_with_guild_member_update, event);
-            }
+                if let Some(handlers) = handler!(on_guild_member_update, event_store) {
+                    let context = context(None, conn, data, &raw);
+
+                    call!(pool, key, handlers, on_guild_member_update(context, event));
+                }
+            }}
         },
         Event::GuildMembersChunk(event) => {
             update!(update_with_guild_members_chunk, event);
+            MemberChunks::received(&event);
 
-            if let Some(handler) = handler!(on_guild_members_chunk, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_members_chunk, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.members));
+                call!(pool, key, handlers,
+                      on_guild_members_chunk(context, event.guild_id, event.members));
             }
         },
         Event::GuildRoleCreate(event) => {
-            update!(update_with_guild_role_create, event);
+            update!(shared update_with_guild_role_create, event);
 
-            if let Some(handler) = handler!(on_guild_role_create, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_role_create, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.role));
+                call!(pool, key, handlers,
+                      on_guild_role_create(context, event.guild_id, event.role));
             }
         },
         Event::GuildRoleDelete(event) => {
-            if let Some(handler) = handler!(on_guild_role_delete, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_role_delete, event_store) {
+                let context = context(None, conn, data, &raw);
 
                 feature_cache! {{
-                    let role = update!(update_with_guild_role_delete, event);
+                    let role = update!(shared update_with_guild_role_delete, event);
 
-                    thread::spawn(move || (handler)(context, event.guild_id, event.role_id, role));
+                    call!(pool, key, handlers,
+                          on_guild_role_delete(context, event.guild_id, event.role_id, role));
                 } else {
-                    thread::spawn(move || (handler)(context, event.guild_id, event.role_id));
+                    call!(pool, key, handlers,
+                          on_guild_role_delete(context, event.guild_id, event.role_id));
                 }}
             } else {
                 #[cfg(feature="cache")]
                 {
-                    let _ = update!(update_with_guild_role_delete, event);
+                    let _ = update!(shared update_with_guild_role_delete, event);
                 }
             }
         },
         Event::GuildRoleUpdate(event) => {
-            if let Some(handler) = handler!(on_guild_role_update, event_store) {
-                let context = context(None, conn, data);
+            let guild_id = event.guild_id;
 
-                feature_cache! {{
-                    let before = update!(update_with_guild_role_update, event);
+            feature_cache! {{
+                let before = update!(shared update_with_guild_role_update, event);
+                let rename = before.as_ref().and_then(|before| {
+                    Change::new(before, &event.role).field(|role| &role.name)
+                });
 
-                    thread::spawn(move || (handler)(context, event.guild_id, before, event.role));
-                } else {
-                    thread::spawn(move || (handler)(context, event.guild_id, event.role));
-                }}
+                if let Some(handlers) = handler!(on_guild_role_update, event_store) {
+                    let context = context(None, conn, data, &raw);
+                    let role = event.role.clone();
+
+                    call!(pool, key, handlers,
+                          on_guild_role_update(context, guild_id, before, role));
+                }
+
+                if let Some(handlers) = handler!(on_guild_role_rename, event_store) {
+                    if let Some(name) = rename {
+                        let context = context(None, conn, data, &raw);
+
+                        call!(pool, key, handlers,
+                              on_guild_role_rename(context, guild_id, event.role, name));
+                    }
+                }
             } else {
-                #[cfg(feature="cache")]
-                {
-                    let _ = update!(update_with_guild_role_update, event);
+                if let Some(handlers) = handler!(on_guild_role_update, event_store) {
+                    let context = context(None, conn, data, &raw);
+
+                    call!(pool, key, handlers, on_guild_role_update(context, guild_id, event.role));
                 }
-            }
+            }}
         },
         Event::GuildUnavailable(event) => {
             update!(update_with_guild_unavailable, event);
+            guild_received(event.guild_id, false, conn, data, event_store, pool);
 
-            if let Some(handler) = handler!(on_guild_unavailable, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_unavailable, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id));
+                call!(pool, key, handlers, on_guild_unavailable(context, event.guild_id));
             }
         },
         Event::GuildUpdate(event) => {
-            if let Some(handler) = handler!(on_guild_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_guild_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
                 feature_cache! {{
                     let before = CACHE.read()
@@ -363,120 +878,171 @@ fn handle_event(event: Event,
                         .guilds
                         .get(&event.guild.id)
                         .cloned();
-                    update!(update_with_guild_update, event);
+                    update!(shared update_with_guild_update, event);
 
-                    thread::spawn(move || (handler)(context, before, event.guild));
+                    call!(pool, key, handlers, on_guild_update(context, before, event.guild));
                 } else {
-                    thread::spawn(move || (handler)(context, event.guild));
+                    call!(pool, key, handlers, on_guild_update(context, event.guild));
                 }}
             } else {
-                update!(update_with_guild_update, event);
+                update!(shared update_with_guild_update, event);
             }
         },
         // Already handled by the framework check macro
         Event::MessageCreate(_) => {},
         Event::MessageDeleteBulk(event) => {
-            if let Some(handler) = handler!(on_message_delete_bulk, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_message_delete_bulk, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
+
+                feature_cache! {{
+                    let messages = update!(update_with_message_delete_bulk, event);
 
-                thread::spawn(move || (handler)(context, event.channel_id, event.ids));
+                    call!(pool, key, handlers,
+                          on_message_delete_bulk(context, event.channel_id, event.ids, messages));
+                } else {
+                    call!(pool, key, handlers,
+                          on_message_delete_bulk(context, event.channel_id, event.ids));
+                }}
+            } else {
+                #[cfg(feature="cache")]
+                {
+                    let _ = update!(update_with_message_delete_bulk, event);
+                }
             }
         },
         Event::MessageDelete(event) => {
-            if let Some(handler) = handler!(on_message_delete, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_message_delete, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel_id, event.message_id));
+                feature_cache! {{
+                    let message = update!(update_with_message_delete, event);
+
+                    call!(pool, key, handlers,
+                          on_message_delete(context, event.channel_id, event.message_id, message));
+                } else {
+                    call!(pool, key, handlers,
+                          on_message_delete(context, event.channel_id, event.message_id));
+                }}
+            } else {
+                #[cfg(feature="cache")]
+                {
+                    let _ = update!(update_with_message_delete, event);
+                }
             }
         },
         Event::MessageUpdate(event) => {
-            if let Some(handler) = handler!(on_message_update, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_message_update, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                feature_cache! {{
+                    let before = update!(update_with_message_update, event);
+
+                    call!(pool, key, handlers, on_message_update(context, before, event));
+                } else {
+                    call!(pool, key, handlers, on_message_update(context, event));
+                }}
+            } else {
+                #[cfg(feature="cache")]
+                {
+                    let _ = update!(update_with_message_update, event);
+                }
             }
         },
         Event::PresencesReplace(event) => {
             update!(update_with_presences_replace, event);
 
-            if let Some(handler) = handler!(on_presence_replace, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_presence_replace, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.presences));
+                call!(pool, key, handlers, on_presence_replace(context, event.presences));
             }
         },
         Event::PresenceUpdate(mut event) => {
-            update!(update_with_presence_update, @event);
+            update!(shared try_update_with_presence_update or update_with_presence_update, @event);
 
-            if let Some(handler) = handler!(on_presence_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_presence_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                call!(pool, key, handlers, on_presence_update(context, event));
             }
         },
         Event::ReactionAdd(event) => {
-            if let Some(handler) = handler!(on_reaction_add, event_store) {
-                let context = context(Some(event.reaction.channel_id), conn, data);
+            collector::reaction_added(&event.reaction);
+
+            if let Some(handlers) = handler!(on_reaction_add, event_store) {
+                let context = context(Some(event.reaction.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.reaction));
+                call!(pool, key, handlers, on_reaction_add(context, event.reaction));
             }
         },
         Event::ReactionRemove(event) => {
-            if let Some(handler) = handler!(on_reaction_remove, event_store) {
-                let context = context(Some(event.reaction.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_reaction_remove, event_store) {
+                let context = context(Some(event.reaction.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.reaction));
+                call!(pool, key, handlers, on_reaction_remove(context, event.reaction));
             }
         },
         Event::ReactionRemoveAll(event) => {
-            if let Some(handler) = handler!(on_reaction_remove_all, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_reaction_remove_all, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.channel_id, event.message_id));
+                call!(pool, key, handlers,
+                      on_reaction_remove_all(context, event.channel_id, event.message_id));
             }
         },
         Event::Ready(event) => {
-            if let Some(handler) = handler!(on_ready, event_store) {
+            let unavailable = event.ready
+                .guilds
+                .iter()
+                .filter_map(|guild| match *guild {
+                    GuildStatus::Offline(ref guild) => Some(guild.id),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(handlers) = handler!(on_ready, event_store) {
                 update!(update_with_ready, event);
 
-                let context = context(None, conn, data);
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.ready));
+                call!(pool, key, handlers, on_ready(context, event.ready));
             } else {
                 update!(update_with_ready, event);
             }
+
+            wait_for_guilds(&unavailable, conn, data, event_store, pool);
         },
         Event::Resumed(event) => {
-            if let Some(handler) = handler!(on_resume, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_resume, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                call!(pool, key, handlers, on_resume(context, event));
             }
         },
         Event::TypingStart(event) => {
-            if let Some(handler) = handler!(on_typing_start, event_store) {
-                let context = context(Some(event.channel_id), conn, data);
+            if let Some(handlers) = handler!(on_typing_start, event_store) {
+                let context = context(Some(event.channel_id), conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                call!(pool, key, handlers, on_typing_start(context, event));
             }
         },
         Event::Unknown(event) => {
-            if let Some(handler) = handler!(on_unknown, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_unknown, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.kind, event.value));
+                call!(pool, key, handlers, on_unknown(context, event.kind, event.value));
             }
         },
         Event::UserUpdate(event) => {
-            if let Some(handler) = handler!(on_user_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_user_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
                 feature_cache! {{
                     let before = update!(update_with_user_update, event);
 
-                    thread::spawn(move || (handler)(context, before, event.current_user));
+                    call!(pool, key, handlers, on_user_update(context, before, event.current_user));
                 } else {
-                    thread::spawn(move || (handler)(context, event.current_user));
+                    call!(pool, key, handlers, on_user_update(context, event.current_user));
                 }}
             } else {
                 #[cfg(feature="cache")]
@@ -486,26 +1052,62 @@ fn handle_event(event: Event,
             }
         },
         Event::VoiceServerUpdate(event) => {
-            if let Some(handler) = handler!(on_voice_server_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_voice_server_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event));
+                call!(pool, key, handlers, on_voice_server_update(context, event));
             }
         },
         Event::VoiceStateUpdate(event) => {
-            update!(update_with_voice_state_update, event);
+            let guild_id = event.guild_id;
 
-            if let Some(handler) = handler!(on_voice_state_update, event_store) {
-                let context = context(None, conn, data);
+            // The user's voice state from before the update, if the guild is
+            // cached. Without voice states being cached there is nothing to
+            // compare against, so no voice events are derived.
+            #[cfg(feature="cache")]
+            let before = {
+                let cache = CACHE.read().unwrap();
+
+                if cache.config.voice_states {
+                    guild_id.and_then(|guild_id| cache.guild(guild_id)).map(|guild| {
+                        guild.read()
+                            .unwrap()
+                            .voice_states
+                            .get(&event.voice_state.user_id)
+                            .cloned()
+                    })
+                } else {
+                    None
+                }
+            };
+
+            update!(shared update_with_voice_state_update, event);
+
+            #[cfg(feature="cache")]
+            let after = event.voice_state.clone();
+
+            if let Some(handlers) = handler!(on_voice_state_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.voice_state));
+                call!(pool, key, handlers,
+                      on_voice_state_update(context, guild_id, event.voice_state));
+            }
+
+            #[cfg(feature="cache")]
+            {
+                if let (Some(guild_id), Some(before)) = (guild_id, before) {
+                    let context = context(None, conn, data, &raw);
+
+                    dispatch_voice_changes(guild_id, before, &after, &context, event_store, pool, key);
+                }
             }
         },
         Event::WebhookUpdate(event) => {
-            if let Some(handler) = handler!(on_webhook_update, event_store) {
-                let context = context(None, conn, data);
+            if let Some(handlers) = handler!(on_webhook_update, event_store) {
+                let context = context(None, conn, data, &raw);
 
-                thread::spawn(move || (handler)(context, event.guild_id, event.channel_id));
+                call!(pool, key, handlers,
+                      on_webhook_update(context, event.guild_id, event.channel_id));
             }
         },
     }
@@ -1,6 +1,6 @@
 use hyper::status::StatusCode;
 use ::constants::ErrorCode;
-use ::model::{ChannelType, Permissions};
+use ::model::{ChannelType, Permissions, RoleId, UserId};
 
 /// An error returned from the [`Client`] or the [`Context`], or model instance.
 ///
@@ -52,6 +52,18 @@ pub enum Error {
     /// When attempting to delete below or above the minimum and maximum allowed
     /// number of messages.
     BulkDeleteAmount,
+    /// When attempting to give or remove a [`Role`] which is not below the
+    /// highest role of the [current user] in the role hierarchy.
+    ///
+    /// [`Role`]: ../model/struct.Role.html
+    /// [current user]: ../model/struct.CurrentUser.html
+    CannotManageRole(RoleId),
+    /// When attempting to moderate - such as kick, ban or nickname - a member
+    /// which is the owner of the guild, or whose highest role is not below the
+    /// highest role of the [current user].
+    ///
+    /// [current user]: ../model/struct.CurrentUser.html
+    CannotModerate(UserId),
     /// When attempting to delete a number of days' worth of messages that is
     /// not allowed.
     DeleteMessageDaysAmount(u8),
@@ -0,0 +1,444 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use super::context::Context;
+use ::model::event::*;
+use ::model::*;
+
+#[cfg(feature="cache")]
+use std::sync::{Arc, RwLock};
+
+/// A trait for handling events received over the gateway, as an alternative to
+/// attaching closures via the [`Client`]'s `on_*` methods.
+///
+/// Every method has a default implementation which does nothing, so only the
+/// events of interest need to be implemented. The methods mirror the
+/// signatures of the closures accepted by their equivalent [`Client`] methods,
+/// including values provided by the cache when the `cache` feature is
+/// enabled.
+///
+/// Implementations are attached via [`Client::add_handler`], and are called in
+/// the order in which they were attached - alongside any closures - on a
+/// thread separate from the shard.
+///
+/// # Examples
+///
+/// Count the number of messages received, keeping the state in the handler
+/// rather than cloning it into a closure:
+///
+/// ```rust,no_run
+/// use serenity::client::{Context, EventHandler};
+/// use serenity::model::Message;
+/// use serenity::Client;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// struct MessageCounter {
+///     count: AtomicUsize,
+/// }
+///
+/// impl EventHandler for MessageCounter {
+///     fn on_message(&self, _: Context, message: Message) {
+///         let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+///
+///         println!("Message #{}: {}", count, message.content);
+///     }
+/// }
+///
+/// let mut client = Client::login("bot token here");
+/// client.add_handler(MessageCounter {
+///     count: AtomicUsize::new(0),
+/// });
+///
+/// let _ = client.start();
+/// ```
+///
+/// [`Client`]: struct.Client.html
+/// [`Client::add_handler`]: struct.Client.html#method.add_handler
+pub trait EventHandler: Send + Sync {
+    /// Called once every guild from the READY of every shard has been
+    /// received, or has timed out, with the guilds which stayed unavailable.
+    fn on_cache_ready(&self, _: Context, _: Vec<GuildId>) {}
+
+    /// Called when a [`ChannelCreate`] is received.
+    ///
+    /// [`ChannelCreate`]: ../model/event/enum.Event.html#variant.ChannelCreate
+    fn on_channel_create(&self, _: Context, _: Channel) {}
+
+    /// Called when a [`ChannelDelete`] is received.
+    ///
+    /// [`ChannelDelete`]: ../model/event/enum.Event.html#variant.ChannelDelete
+    fn on_channel_delete(&self, _: Context, _: Channel) {}
+
+    /// Called when a [`ChannelPinsUpdate`] is received.
+    ///
+    /// [`ChannelPinsUpdate`]: ../model/event/enum.Event.html#variant.ChannelPinsUpdate
+    fn on_channel_pins_update(&self, _: Context, _: ChannelPinsUpdateEvent) {}
+
+    /// Called when a guild channel's name changes, derived from a
+    /// [`ChannelUpdate`] and the channel in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
+    #[cfg(feature="cache")]
+    fn on_channel_rename(&self, _: Context, _: ChannelId, _: Change<String>) {}
+
+    /// Called when a [`ChannelUpdate`] is received.
+    ///
+    /// Optionally provides the version of the channel before the update.
+    ///
+    /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
+    #[cfg(feature="cache")]
+    fn on_channel_update(&self, _: Context, _: Option<Channel>, _: Channel) {}
+
+    /// Called when a [`ChannelUpdate`] is received.
+    ///
+    /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_channel_update(&self, _: Context, _: Channel) {}
+
+    /// Called when a [`GuildCreate`] is received.
+    ///
+    /// [`GuildCreate`]: ../model/event/enum.Event.html#variant.GuildCreate
+    fn on_guild_create(&self, _: Context, _: Guild) {}
+
+    /// Called when a [`GuildDelete`] is received.
+    ///
+    /// Returns a partial guild as well as - optionally - the full guild, with
+    /// data like [`Role`]s. This can be `None` in the event that it was not in
+    /// the [`Cache`].
+    ///
+    /// **Note**: The relevant guild is _removed_ from the Cache when this event
+    /// is received. If you need to keep it, you can either re-insert it
+    /// yourself back into the Cache or manage it in another way.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildDelete`]: ../model/event/enum.Event.html#variant.GuildDelete
+    /// [`Role`]: ../model/struct.Role.html
+    #[cfg(feature="cache")]
+    fn on_guild_delete(&self, _: Context, _: PartialGuild, _: Option<Arc<RwLock<Guild>>>) {}
+
+    /// Called when a [`GuildDelete`] is received.
+    ///
+    /// [`GuildDelete`]: ../model/event/enum.Event.html#variant.GuildDelete
+    #[cfg(not(feature="cache"))]
+    fn on_guild_delete(&self, _: Context, _: PartialGuild) {}
+
+    /// Called when a [`GuildEmojisUpdate`] is received.
+    ///
+    /// [`GuildEmojisUpdate`]: ../model/event/enum.Event.html#variant.GuildEmojisUpdate
+    fn on_guild_emojis_update(&self, _: Context, _: GuildId, _: HashMap<EmojiId, Emoji>) {}
+
+    /// Called when a [`GuildIntegrationsUpdate`] is received.
+    ///
+    /// [`GuildIntegrationsUpdate`]: ../model/event/enum.Event.html#variant.GuildIntegrationsUpdate
+    fn on_guild_integrations_update(&self, _: Context, _: GuildId) {}
+
+    /// Called when a [`GuildMemberAdd`] is received.
+    ///
+    /// [`GuildMemberAdd`]: ../model/event/enum.Event.html#variant.GuildMemberAdd
+    fn on_guild_member_add(&self, _: Context, _: GuildId, _: Member) {}
+
+    /// Called when a member's nickname changes, derived from a
+    /// [`GuildMemberUpdate`] and the member in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_member_nickname_update(&self, _: Context, _: GuildId, _: Member, _: Change<Option<String>>) {}
+
+    /// Called when a [`GuildMemberRemove`] is received.
+    ///
+    /// Returns the user's associated `Member` object, _if_ it existed in the
+    /// cache.
+    ///
+    /// [`GuildMemberRemove`]: ../model/event/enum.Event.html#variant.GuildMemberRemove
+    #[cfg(feature="cache")]
+    fn on_guild_member_remove(&self, _: Context, _: GuildId, _: User, _: Option<Member>) {}
+
+    /// Called when a [`GuildMemberRemove`] is received.
+    ///
+    /// [`GuildMemberRemove`]: ../model/event/enum.Event.html#variant.GuildMemberRemove
+    #[cfg(not(feature="cache"))]
+    fn on_guild_member_remove(&self, _: Context, _: GuildId, _: User) {}
+
+    /// Called for each role given to a member, derived from a
+    /// [`GuildMemberUpdate`] and the member in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_member_role_add(&self, _: Context, _: GuildId, _: Member, _: RoleId) {}
+
+    /// Called for each role taken from a member, derived from a
+    /// [`GuildMemberUpdate`] and the member in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_member_role_remove(&self, _: Context, _: GuildId, _: Member, _: RoleId) {}
+
+    /// Called when a [`GuildMemberUpdate`] is received.
+    ///
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_member_update(&self, _: Context, _: Option<Member>, _: Member) {}
+
+    /// Called when a [`GuildMemberUpdate`] is received.
+    ///
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_guild_member_update(&self, _: Context, _: GuildMemberUpdateEvent) {}
+
+    /// Called when a [`GuildMembersChunk`] is received.
+    ///
+    /// [`GuildMembersChunk`]: ../model/event/enum.Event.html#variant.GuildMembersChunk
+    fn on_guild_members_chunk(&self, _: Context, _: GuildId, _: HashMap<UserId, Member>) {}
+
+    /// Called when a [`GuildRoleCreate`] is received.
+    ///
+    /// [`GuildRoleCreate`]: ../model/event/enum.Event.html#variant.GuildRoleCreate
+    fn on_guild_role_create(&self, _: Context, _: GuildId, _: Role) {}
+
+    /// Called when a [`GuildRoleDelete`] is received.
+    ///
+    /// [`GuildRoleDelete`]: ../model/event/enum.Event.html#variant.GuildRoleDelete
+    #[cfg(feature="cache")]
+    fn on_guild_role_delete(&self, _: Context, _: GuildId, _: RoleId, _: Option<Role>) {}
+
+    /// Called when a [`GuildRoleDelete`] is received.
+    ///
+    /// [`GuildRoleDelete`]: ../model/event/enum.Event.html#variant.GuildRoleDelete
+    #[cfg(not(feature="cache"))]
+    fn on_guild_role_delete(&self, _: Context, _: GuildId, _: RoleId) {}
+
+    /// Called when a role's name changes, derived from a [`GuildRoleUpdate`]
+    /// and the role in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_role_rename(&self, _: Context, _: GuildId, _: Role, _: Change<String>) {}
+
+    /// Called when a [`GuildRoleUpdate`] is received.
+    ///
+    /// The optional `Role` is the role prior to updating. This can be `None` if
+    /// it did not exist in the [`Cache`] before the update.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_role_update(&self, _: Context, _: GuildId, _: Option<Role>, _: Role) {}
+
+    /// Called when a [`GuildRoleUpdate`] is received.
+    ///
+    /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_guild_role_update(&self, _: Context, _: GuildId, _: Role) {}
+
+    /// Called when a [`GuildUnavailable`] is received.
+    ///
+    /// [`GuildUnavailable`]: ../model/event/enum.Event.html#variant.GuildUnavailable
+    fn on_guild_unavailable(&self, _: Context, _: GuildId) {}
+
+    /// Called when a [`GuildUpdate`] is received.
+    ///
+    /// [`GuildUpdate`]: ../model/event/enum.Event.html#variant.GuildUpdate
+    #[cfg(feature="cache")]
+    fn on_guild_update(&self, _: Context, _: Option<Arc<RwLock<Guild>>>, _: PartialGuild) {}
+
+    /// Called when a [`GuildUpdate`] is received.
+    ///
+    /// [`GuildUpdate`]: ../model/event/enum.Event.html#variant.GuildUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_guild_update(&self, _: Context, _: PartialGuild) {}
+
+    /// Called when a [`GuildBan`] is received.
+    ///
+    /// [`GuildBan`]: ../model/event/enum.Event.html#variant.GuildBan
+    fn on_member_ban(&self, _: Context, _: GuildId, _: User) {}
+
+    /// Called when a [`GuildUnban`] is received.
+    ///
+    /// [`GuildUnban`]: ../model/event/enum.Event.html#variant.GuildUnban
+    fn on_member_unban(&self, _: Context, _: GuildId, _: User) {}
+
+    /// Called when a [`MessageCreate`] is received.
+    ///
+    /// [`MessageCreate`]: ../model/event/enum.Event.html#variant.MessageCreate
+    fn on_message(&self, _: Context, _: Message) {}
+
+    /// Called when a [`MessageDelete`] is received, with the deleted message if
+    /// it was in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
+    #[cfg(feature="cache")]
+    fn on_message_delete(&self, _: Context, _: ChannelId, _: MessageId, _: Option<Message>) {}
+
+    /// Called when a [`MessageDelete`] is received.
+    ///
+    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
+    #[cfg(not(feature="cache"))]
+    fn on_message_delete(&self, _: Context, _: ChannelId, _: MessageId) {}
+
+    /// Called when a [`MessageDeleteBulk`] is received, with those of the
+    /// deleted messages which were in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageDeleteBulk`]: ../model/event/enum.Event.html#variant.MessageDeleteBulk
+    #[cfg(feature="cache")]
+    fn on_message_delete_bulk(&self, _: Context, _: ChannelId, _: Vec<MessageId>, _: Vec<Message>) {}
+
+    /// Called when a [`MessageDeleteBulk`] is received.
+    ///
+    /// [`MessageDeleteBulk`]: ../model/event/enum.Event.html#variant.MessageDeleteBulk
+    #[cfg(not(feature="cache"))]
+    fn on_message_delete_bulk(&self, _: Context, _: ChannelId, _: Vec<MessageId>) {}
+
+    /// Called when a [`MessageUpdate`] is received, with the message from
+    /// before the update if it was in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
+    #[cfg(feature="cache")]
+    fn on_message_update(&self, _: Context, _: Option<Message>, _: MessageUpdateEvent) {}
+
+    /// Called when a [`MessageUpdate`] is received.
+    ///
+    /// [`MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_message_update(&self, _: Context, _: MessageUpdateEvent) {}
+
+    /// Called when a [`PresencesReplace`] is received.
+    ///
+    /// [`PresencesReplace`]: ../model/event/enum.Event.html#variant.PresencesReplace
+    fn on_presence_replace(&self, _: Context, _: Vec<Presence>) {}
+
+    /// Called when a [`PresenceUpdate`] is received.
+    ///
+    /// [`PresenceUpdate`]: ../model/event/enum.Event.html#variant.PresenceUpdate
+    fn on_presence_update(&self, _: Context, _: PresenceUpdateEvent) {}
+
+    /// Called for every dispatch received over the gateway, with the event's
+    /// name, sequence number, and raw payload. This is called before the
+    /// payload is decoded.
+    fn on_raw_event(&self, _: Context, _: String, _: u64, _: &Value) {}
+
+    /// Called when a [`ReactionAdd`] is received.
+    ///
+    /// [`ReactionAdd`]: ../model/event/enum.Event.html#variant.ReactionAdd
+    fn on_reaction_add(&self, _: Context, _: Reaction) {}
+
+    /// Called when a [`ReactionRemove`] is received.
+    ///
+    /// [`ReactionRemove`]: ../model/event/enum.Event.html#variant.ReactionRemove
+    fn on_reaction_remove(&self, _: Context, _: Reaction) {}
+
+    /// Called when a [`ReactionRemoveAll`] is received.
+    ///
+    /// [`ReactionRemoveAll`]: ../model/event/enum.Event.html#variant.ReactionRemoveAll
+    fn on_reaction_remove_all(&self, _: Context, _: ChannelId, _: MessageId) {}
+
+    /// Called when a [`Ready`] is received.
+    ///
+    /// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+    fn on_ready(&self, _: Context, _: Ready) {}
+
+    /// Called when a [`ChannelRecipientAdd`] is received.
+    ///
+    /// [`ChannelRecipientAdd`]: ../model/event/enum.Event.html#variant.ChannelRecipientAdd
+    fn on_recipient_add(&self, _: Context, _: ChannelId, _: User) {}
+
+    /// Called when a [`ChannelRecipientRemove`] is received.
+    ///
+    /// [`ChannelRecipientRemove`]: ../model/event/enum.Event.html#variant.ChannelRecipientRemove
+    fn on_recipient_remove(&self, _: Context, _: ChannelId, _: User) {}
+
+    /// Called when a [`Resumed`] is received.
+    ///
+    /// [`Resumed`]: ../model/event/enum.Event.html#variant.Resumed
+    fn on_resume(&self, _: Context, _: ResumedEvent) {}
+
+    /// Called once every guild from a shard's READY has been received, or has
+    /// timed out, with the guilds which stayed unavailable.
+    fn on_shard_cache_ready(&self, _: Context, _: Vec<GuildId>) {}
+
+    /// Called when a [`TypingStart`] is received.
+    ///
+    /// [`TypingStart`]: ../model/event/enum.Event.html#variant.TypingStart
+    fn on_typing_start(&self, _: Context, _: TypingStartEvent) {}
+
+    /// Called when an [`Unknown`] is received.
+    ///
+    /// [`Unknown`]: ../model/event/enum.Event.html#variant.Unknown
+    fn on_unknown(&self, _: Context, _: String, _: Value) {}
+
+    /// Called when a [`UserUpdate`] is received.
+    ///
+    /// The old current user will be provided as well.
+    ///
+    /// [`UserUpdate`]: ../model/event/enum.Event.html#variant.UserUpdate
+    #[cfg(feature="cache")]
+    fn on_user_update(&self, _: Context, _: CurrentUser, _: CurrentUser) {}
+
+    /// Called when a [`UserUpdate`] is received.
+    ///
+    /// [`UserUpdate`]: ../model/event/enum.Event.html#variant.UserUpdate
+    #[cfg(not(feature="cache"))]
+    fn on_user_update(&self, _: Context, _: CurrentUser) {}
+
+    /// Called when a user joins a voice channel, derived from a
+    /// [`VoiceStateUpdate`] and the user's voice state in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    #[cfg(feature="cache")]
+    fn on_voice_channel_join(&self, _: Context, _: GuildId, _: UserId, _: ChannelId) {}
+
+    /// Called when a user leaves a voice channel, derived from a
+    /// [`VoiceStateUpdate`] and the user's voice state in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    #[cfg(feature="cache")]
+    fn on_voice_channel_leave(&self, _: Context, _: GuildId, _: UserId, _: ChannelId) {}
+
+    /// Called when a user moves from one voice channel to another, derived
+    /// from a [`VoiceStateUpdate`] and the user's voice state in the
+    /// [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    #[cfg(feature="cache")]
+    fn on_voice_channel_move(&self, _: Context, _: GuildId, _: UserId, _: Change<ChannelId>) {}
+
+    /// Called when a user is server deafened or undeafened, derived from a
+    /// [`VoiceStateUpdate`] and the user's voice state in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    #[cfg(feature="cache")]
+    fn on_voice_server_deaf_update(&self, _: Context, _: GuildId, _: UserId, _: bool) {}
+
+    /// Called when a user is server muted or unmuted, derived from a
+    /// [`VoiceStateUpdate`] and the user's voice state in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    #[cfg(feature="cache")]
+    fn on_voice_server_mute_update(&self, _: Context, _: GuildId, _: UserId, _: bool) {}
+
+    /// Called when a [`VoiceServerUpdate`] is received.
+    ///
+    /// [`VoiceServerUpdate`]: ../model/event/enum.Event.html#variant.VoiceServerUpdate
+    fn on_voice_server_update(&self, _: Context, _: VoiceServerUpdateEvent) {}
+
+    /// Called when a [`VoiceStateUpdate`] is received.
+    ///
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    fn on_voice_state_update(&self, _: Context, _: Option<GuildId>, _: VoiceState) {}
+
+    /// Called when a [`WebhookUpdate`] is received.
+    ///
+    /// [`WebhookUpdate`]: ../model/event/enum.Event.html#variant.WebhookUpdate
+    fn on_webhook_update(&self, _: Context, _: GuildId, _: ChannelId) {}
+}
@@ -1,13 +1,136 @@
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{ATOMIC_USIZE_INIT, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use super::context::Context;
+use super::event_handler::EventHandler;
+use super::events::EventsSender;
+use super::gateway::Shard;
+use super::ready;
 use ::model::event::*;
 use ::model::*;
 
 #[cfg(feature="cache")]
 use std::sync::RwLock;
 
+static NEXT_HANDLER_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// An identifier for an event handler registered on a [`Client`], returned by
+/// each of its `on_*` methods.
+///
+/// This can be passed to [`Client::remove_handler`] to unregister the handler.
+///
+/// [`Client`]: struct.Client.html
+/// [`Client::remove_handler`]: struct.Client.html#method.remove_handler
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct HandlerId(pub u64);
+
+/// The handlers registered for a single event, in the order in which they were
+/// registered.
+#[doc(hidden)]
+pub struct Handlers<F: ?Sized> {
+    handlers: Vec<(HandlerId, Arc<F>)>,
+}
+
+impl<F: ?Sized> Handlers<F> {
+    /// Appends a handler, returning its newly allocated Id.
+    pub fn push(&mut self, handler: Arc<F>) -> HandlerId {
+        let id = next_id();
+        self.handlers.push((id, handler));
+
+        id
+    }
+
+    /// Removes the handler with the given Id, returning whether it was
+    /// registered for this event.
+    pub fn remove(&mut self, id: HandlerId) -> bool {
+        let len = self.handlers.len();
+        self.handlers.retain(|&(handler_id, _)| handler_id != id);
+
+        self.handlers.len() != len
+    }
+
+    /// Whether there are no handlers.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Clones the handlers, in registration order, so that they can be called
+    /// without holding a lock on the store. Returns `None` if there are no
+    /// handlers.
+    pub fn get(&self) -> Option<Vec<Arc<F>>> {
+        if self.handlers.is_empty() {
+            None
+        } else {
+            Some(self.handlers.iter().map(|&(_, ref handler)| handler.clone()).collect())
+        }
+    }
+
+    /// Clones the handlers along with the [`EventHandler`]s, merged in
+    /// registration order. Returns `None` if there are neither.
+    ///
+    /// [`EventHandler`]: trait.EventHandler.html
+    pub fn callbacks(&self, event_handlers: &Handlers<EventHandler>) -> Option<Vec<Callback<F>>> {
+        if self.handlers.is_empty() && event_handlers.is_empty() {
+            return None;
+        }
+
+        let mut callbacks = Vec::with_capacity(self.handlers.len() + event_handlers.handlers.len());
+        let mut closures = self.handlers.iter().peekable();
+        let mut handlers = event_handlers.handlers.iter().peekable();
+
+        loop {
+            let closure_first = match (closures.peek(), handlers.peek()) {
+                (Some(&&(a, _)), Some(&&(b, _))) => a < b,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+
+            if closure_first {
+                if let Some(&(_, ref closure)) = closures.next() {
+                    callbacks.push(Callback::Closure(closure.clone()));
+                }
+            } else if let Some(&(_, ref handler)) = handlers.next() {
+                callbacks.push(Callback::Handler(handler.clone()));
+            }
+        }
+
+        Some(callbacks)
+    }
+}
+
+/// A handler to call for an event: either a closure registered for just that
+/// event, or an [`EventHandler`] whose respective method is called.
+///
+/// [`EventHandler`]: trait.EventHandler.html
+#[doc(hidden)]
+pub enum Callback<F: ?Sized> {
+    Closure(Arc<F>),
+    Handler(Arc<EventHandler>),
+}
+
+impl<F: ?Sized> Clone for Callback<F> {
+    fn clone(&self) -> Callback<F> {
+        match *self {
+            Callback::Closure(ref closure) => Callback::Closure(closure.clone()),
+            Callback::Handler(ref handler) => Callback::Handler(handler.clone()),
+        }
+    }
+}
+
+fn next_id() -> HandlerId {
+    HandlerId(NEXT_HANDLER_ID.fetch_add(1, Ordering::Relaxed) as u64)
+}
+
+impl<F: ?Sized> Default for Handlers<F> {
+    fn default() -> Handlers<F> {
+        Handlers {
+            handlers: vec![],
+        }
+    }
+}
+
 // This should use type macros when stable receives the type macro
 // stabilization patch.
 //
@@ -16,7 +139,7 @@ use std::sync::RwLock;
 // ```rust,ignore
 // macro_rules! efn {
 //     ($def:ty) => {
-//         Option<Arc<Box<$def> + Send + Sync + 'static>>
+//         Handlers<$def + Send + Sync + 'static>
 //     }
 // }
 // ```
@@ -29,66 +152,184 @@ use std::sync::RwLock;
 #[allow(type_complexity)]
 #[derive(Default)]
 pub struct EventStore {
-    pub on_channel_create: Option<Arc<Fn(Context, Channel) + Send + Sync + 'static>>,
-    pub on_channel_delete: Option<Arc<Fn(Context, Channel) + Send + Sync + 'static>>,
-    pub on_channel_pins_update: Option<Arc<Fn(Context, ChannelPinsUpdateEvent) + Send + Sync + 'static>>,
-    pub on_channel_recipient_addition: Option<Arc<Fn(Context, ChannelId, User) + Send + Sync + 'static>>,
-    pub on_channel_recipient_removal: Option<Arc<Fn(Context, ChannelId, User) + Send + Sync + 'static>>,
+    pub events: Vec<EventsSender>,
+    pub handlers: Handlers<EventHandler>,
+    pub ready: Mutex<ready::State>,
+    pub middleware: Handlers<Fn(&Arc<Mutex<Shard>>, Event) -> Option<Event> + Send + Sync + 'static>,
+    pub on_cache_ready: Handlers<Fn(Context, Vec<GuildId>) + Send + Sync + 'static>,
+    pub on_channel_create: Handlers<Fn(Context, Channel) + Send + Sync + 'static>,
+    pub on_channel_delete: Handlers<Fn(Context, Channel) + Send + Sync + 'static>,
+    pub on_channel_pins_update: Handlers<Fn(Context, ChannelPinsUpdateEvent) + Send + Sync + 'static>,
+    pub on_channel_recipient_addition: Handlers<Fn(Context, ChannelId, User) + Send + Sync + 'static>,
+    pub on_channel_recipient_removal: Handlers<Fn(Context, ChannelId, User) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_channel_update: Option<Arc<Fn(Context, Option<Channel>, Channel) + Send + Sync + 'static>>,
+    pub on_channel_rename: Handlers<Fn(Context, ChannelId, Change<String>) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_channel_update: Handlers<Fn(Context, Option<Channel>, Channel) + Send + Sync + 'static>,
+    #[cfg(not(feature="cache"))]
+    pub on_channel_update: Handlers<Fn(Context, Channel) + Send + Sync + 'static>,
+    pub on_guild_ban_addition: Handlers<Fn(Context, GuildId, User) + Send + Sync + 'static>,
+    pub on_guild_ban_removal: Handlers<Fn(Context, GuildId, User) + Send + Sync + 'static>,
+    pub on_guild_create: Handlers<Fn(Context, Guild) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_delete: Handlers<Fn(Context, PartialGuild, Option<Arc<RwLock<Guild>>>) + Send + Sync + 'static>,
+    #[cfg(not(feature="cache"))]
+    pub on_guild_delete: Handlers<Fn(Context, PartialGuild) + Send + Sync + 'static>,
+    pub on_guild_emojis_update: Handlers<Fn(Context, GuildId, HashMap<EmojiId, Emoji>) + Send + Sync + 'static>,
+    pub on_guild_integrations_update: Handlers<Fn(Context, GuildId) + Send + Sync + 'static>,
+    pub on_guild_member_addition: Handlers<Fn(Context, GuildId, Member) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_member_removal: Handlers<Fn(Context, GuildId, User, Option<Member>) + Send + Sync + 'static>,
+    #[cfg(not(feature="cache"))]
+    pub on_guild_member_removal: Handlers<Fn(Context, GuildId, User) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_member_nickname_update: Handlers<Fn(Context, GuildId, Member, Change<Option<String>>) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_member_role_add: Handlers<Fn(Context, GuildId, Member, RoleId) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_member_role_remove: Handlers<Fn(Context, GuildId, Member, RoleId) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_member_update: Handlers<Fn(Context, Option<Member>, Member) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_channel_update: Option<Arc<Fn(Context, Channel) + Send + Sync + 'static>>,
-    pub on_guild_ban_addition: Option<Arc<Fn(Context, GuildId, User) + Send + Sync + 'static>>,
-    pub on_guild_ban_removal: Option<Arc<Fn(Context, GuildId, User) + Send + Sync + 'static>>,
-    pub on_guild_create: Option<Arc<Fn(Context, Guild) + Send + Sync + 'static>>,
+    pub on_guild_member_update: Handlers<Fn(Context, GuildMemberUpdateEvent) + Send + Sync + 'static>,
+    pub on_guild_members_chunk: Handlers<Fn(Context, GuildId, HashMap<UserId, Member>) + Send + Sync + 'static>,
+    pub on_guild_role_create: Handlers<Fn(Context, GuildId, Role) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_delete: Option<Arc<Fn(Context, PartialGuild, Option<Arc<RwLock<Guild>>>) + Send + Sync + 'static>>,
+    pub on_guild_role_delete: Handlers<Fn(Context, GuildId, RoleId, Option<Role>) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_delete: Option<Arc<Fn(Context, PartialGuild) + Send + Sync + 'static>>,
-    pub on_guild_emojis_update: Option<Arc<Fn(Context, GuildId, HashMap<EmojiId, Emoji>) + Send + Sync + 'static>>,
-    pub on_guild_integrations_update: Option<Arc<Fn(Context, GuildId) + Send + Sync + 'static>>,
-    pub on_guild_member_addition: Option<Arc<Fn(Context, GuildId, Member) + Send + Sync + 'static>>,
+    pub on_guild_role_delete: Handlers<Fn(Context, GuildId, RoleId) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_guild_role_rename: Handlers<Fn(Context, GuildId, Role, Change<String>) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_member_removal: Option<Arc<Fn(Context, GuildId, User, Option<Member>) + Send + Sync + 'static>>,
+    pub on_guild_role_update: Handlers<Fn(Context, GuildId, Option<Role>, Role) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_member_removal: Option<Arc<Fn(Context, GuildId, User) + Send + Sync + 'static>>,
+    pub on_guild_role_update: Handlers<Fn(Context, GuildId, Role) + Send + Sync + 'static>,
+    pub on_guild_unavailable: Handlers<Fn(Context, GuildId) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_member_update: Option<Arc<Fn(Context, Option<Member>, Member) + Send + Sync + 'static>>,
+    pub on_guild_update: Handlers<Fn(Context, Option<Arc<RwLock<Guild>>>, PartialGuild) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_member_update: Option<Arc<Fn(Context, GuildMemberUpdateEvent) + Send + Sync + 'static>>,
-    pub on_guild_members_chunk: Option<Arc<Fn(Context, GuildId, HashMap<UserId, Member>) + Send + Sync + 'static>>,
-    pub on_guild_role_create: Option<Arc<Fn(Context, GuildId, Role) + Send + Sync + 'static>>,
+    pub on_guild_update: Handlers<Fn(Context, PartialGuild) + Send + Sync + 'static>,
+    pub on_message: Handlers<Fn(Context, Message) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_role_delete: Option<Arc<Fn(Context, GuildId, RoleId, Option<Role>) + Send + Sync + 'static>>,
+    pub on_message_delete: Handlers<Fn(Context, ChannelId, MessageId, Option<Message>) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_role_delete: Option<Arc<Fn(Context, GuildId, RoleId) + Send + Sync + 'static>>,
+    pub on_message_delete: Handlers<Fn(Context, ChannelId, MessageId) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_role_update: Option<Arc<Fn(Context, GuildId, Option<Role>, Role) + Send + Sync + 'static>>,
+    pub on_message_delete_bulk: Handlers<Fn(Context, ChannelId, Vec<MessageId>, Vec<Message>) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_role_update: Option<Arc<Fn(Context, GuildId, Role) + Send + Sync + 'static>>,
-    pub on_guild_unavailable: Option<Arc<Fn(Context, GuildId) + Send + Sync + 'static>>,
+    pub on_message_delete_bulk: Handlers<Fn(Context, ChannelId, Vec<MessageId>) + Send + Sync + 'static>,
+    pub on_reaction_add: Handlers<Fn(Context, Reaction) + Send + Sync + 'static>,
+    pub on_reaction_remove: Handlers<Fn(Context, Reaction) + Send + Sync + 'static>,
+    pub on_reaction_remove_all: Handlers<Fn(Context, ChannelId, MessageId) + Send + Sync + 'static>,
     #[cfg(feature="cache")]
-    pub on_guild_update: Option<Arc<Fn(Context, Option<Arc<RwLock<Guild>>>, PartialGuild) + Send + Sync + 'static>>,
+    pub on_message_update: Handlers<Fn(Context, Option<Message>, MessageUpdateEvent) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_guild_update: Option<Arc<Fn(Context, PartialGuild) + Send + Sync + 'static>>,
-    pub on_message: Option<Arc<Fn(Context, Message) + Send + Sync + 'static>>,
-    pub on_message_delete: Option<Arc<Fn(Context, ChannelId, MessageId) + Send + Sync + 'static>>,
-    pub on_message_delete_bulk: Option<Arc<Fn(Context, ChannelId, Vec<MessageId>) + Send + Sync + 'static>>,
-    pub on_reaction_add: Option<Arc<Fn(Context, Reaction) + Send + Sync + 'static>>,
-    pub on_reaction_remove: Option<Arc<Fn(Context, Reaction) + Send + Sync + 'static>>,
-    pub on_reaction_remove_all: Option<Arc<Fn(Context, ChannelId, MessageId) + Send + Sync + 'static>>,
-    pub on_message_update: Option<Arc<Fn(Context, MessageUpdateEvent) + Send + Sync + 'static>>,
-    pub on_presence_replace: Option<Arc<Fn(Context, Vec<Presence>) + Send + Sync + 'static>>,
-    pub on_presence_update: Option<Arc<Fn(Context, PresenceUpdateEvent) + Send + Sync + 'static>>,
-    pub on_ready: Option<Arc<Fn(Context, Ready) + Send + Sync + 'static>>,
-    pub on_resume: Option<Arc<Fn(Context, ResumedEvent) + Send + Sync + 'static>>,
-    pub on_typing_start: Option<Arc<Fn(Context, TypingStartEvent) + Send + Sync + 'static>>,
-    pub on_unknown: Option<Arc<Fn(Context, String, Value) + Send + Sync + 'static>>,
-    #[cfg(feature="cache")]
-    pub on_user_update: Option<Arc<Fn(Context, CurrentUser, CurrentUser) + Send + Sync + 'static>>,
+    pub on_message_update: Handlers<Fn(Context, MessageUpdateEvent) + Send + Sync + 'static>,
+    pub on_presence_replace: Handlers<Fn(Context, Vec<Presence>) + Send + Sync + 'static>,
+    pub on_presence_update: Handlers<Fn(Context, PresenceUpdateEvent) + Send + Sync + 'static>,
+    pub on_ready: Handlers<Fn(Context, Ready) + Send + Sync + 'static>,
+    pub on_resume: Handlers<Fn(Context, ResumedEvent) + Send + Sync + 'static>,
+    pub on_shard_cache_ready: Handlers<Fn(Context, Vec<GuildId>) + Send + Sync + 'static>,
+    pub on_raw_event: Handlers<Fn(Context, String, u64, &Value) + Send + Sync + 'static>,
+    pub on_typing_start: Handlers<Fn(Context, TypingStartEvent) + Send + Sync + 'static>,
+    pub on_unknown: Handlers<Fn(Context, String, Value) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_user_update: Handlers<Fn(Context, CurrentUser, CurrentUser) + Send + Sync + 'static>,
     #[cfg(not(feature="cache"))]
-    pub on_user_update: Option<Arc<Fn(Context, CurrentUser) + Send + Sync + 'static>>,
-    pub on_voice_server_update: Option<Arc<Fn(Context, VoiceServerUpdateEvent) + Send + Sync + 'static>>,
-    pub on_voice_state_update: Option<Arc<Fn(Context, Option<GuildId>, VoiceState) + Send + Sync + 'static>>,
-    pub on_webhook_update: Option<Arc<Fn(Context, GuildId, ChannelId) + Send + Sync + 'static>>,
+    pub on_user_update: Handlers<Fn(Context, CurrentUser) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_voice_channel_join: Handlers<Fn(Context, GuildId, UserId, ChannelId) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_voice_channel_leave: Handlers<Fn(Context, GuildId, UserId, ChannelId) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_voice_channel_move: Handlers<Fn(Context, GuildId, UserId, Change<ChannelId>) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_voice_server_deaf_update: Handlers<Fn(Context, GuildId, UserId, bool) + Send + Sync + 'static>,
+    #[cfg(feature="cache")]
+    pub on_voice_server_mute_update: Handlers<Fn(Context, GuildId, UserId, bool) + Send + Sync + 'static>,
+    pub on_voice_server_update: Handlers<Fn(Context, VoiceServerUpdateEvent) + Send + Sync + 'static>,
+    pub on_voice_state_update: Handlers<Fn(Context, Option<GuildId>, VoiceState) + Send + Sync + 'static>,
+    pub on_webhook_update: Handlers<Fn(Context, GuildId, ChannelId) + Send + Sync + 'static>,
+}
+
+impl EventStore {
+    /// Attaches an [`EventHandler`], whose methods are called for their
+    /// respective events.
+    ///
+    /// [`EventHandler`]: trait.EventHandler.html
+    pub fn add_handler<H: EventHandler + 'static>(&mut self, handler: Arc<H>) -> HandlerId {
+        self.handlers.push(handler)
+    }
+
+    /// Removes the handler with the given Id, regardless of which event it was
+    /// registered for.
+    pub fn remove(&mut self, id: HandlerId) -> bool {
+        macro_rules! remove {
+            ($store:ident, $($field:ident),*) => {
+                $($store.$field.remove(id))|*
+            }
+        }
+
+        let removed = remove!(self,
+            handlers,
+            middleware,
+            on_cache_ready,
+            on_channel_create,
+            on_channel_delete,
+            on_channel_pins_update,
+            on_channel_recipient_addition,
+            on_channel_recipient_removal,
+            on_channel_update,
+            on_guild_ban_addition,
+            on_guild_ban_removal,
+            on_guild_create,
+            on_guild_delete,
+            on_guild_emojis_update,
+            on_guild_integrations_update,
+            on_guild_member_addition,
+            on_guild_member_removal,
+            on_guild_member_update,
+            on_guild_members_chunk,
+            on_guild_role_create,
+            on_guild_role_delete,
+            on_guild_role_update,
+            on_guild_unavailable,
+            on_guild_update,
+            on_message,
+            on_message_delete,
+            on_message_delete_bulk,
+            on_reaction_add,
+            on_reaction_remove,
+            on_reaction_remove_all,
+            on_message_update,
+            on_presence_replace,
+            on_presence_update,
+            on_ready,
+            on_resume,
+            on_shard_cache_ready,
+            on_raw_event,
+            on_typing_start,
+            on_unknown,
+            on_user_update,
+            on_voice_server_update,
+            on_voice_state_update,
+            on_webhook_update
+        );
+
+        #[cfg(feature="cache")]
+        let removed = removed | remove!(self,
+            on_channel_rename,
+            on_guild_member_nickname_update,
+            on_guild_member_role_add,
+            on_guild_member_role_remove,
+            on_guild_role_rename,
+            on_voice_channel_join,
+            on_voice_channel_leave,
+            on_voice_channel_move,
+            on_voice_server_deaf_update,
+            on_voice_server_mute_update
+        );
+
+        removed
+    }
 }
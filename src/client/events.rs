@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TryRecvError, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use super::Backpressure;
+use ::model::event::Event;
+
+/// An event, along with the information of the shard which received it.
+pub type ShardEvent = (Option<[u64; 2]>, Event);
+
+/// Configuration for an [`Events`] iterator, created via
+/// [`Client::events_with`].
+///
+/// [`Client::events_with`]: struct.Client.html#method.events_with
+/// [`Events`]: struct.Events.html
+pub struct EventsConfig {
+    backpressure: Backpressure,
+    buffer: usize,
+}
+
+impl EventsConfig {
+    /// What to do when the buffer is full.
+    ///
+    /// With [`Backpressure::Drop`], the event is dropped for the iterator and
+    /// counted by [`Events::dropped`]. With [`Backpressure::Block`], the shard
+    /// waits until the event can be buffered, receiving no further events -
+    /// and calling no further handlers - until then. This suits folding events
+    /// into state, where a missed event leaves the state wrong.
+    ///
+    /// Defaults to [`Backpressure::Drop`].
+    ///
+    /// [`Backpressure::Block`]: enum.Backpressure.html#variant.Block
+    /// [`Backpressure::Drop`]: enum.Backpressure.html#variant.Drop
+    /// [`Events::dropped`]: struct.Events.html#method.dropped
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+
+        self
+    }
+
+    /// The number of events which can be waiting to be received.
+    ///
+    /// Defaults to `1000`.
+    pub fn buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer;
+
+        self
+    }
+}
+
+impl Default for EventsConfig {
+    fn default() -> EventsConfig {
+        EventsConfig {
+            backpressure: Backpressure::Drop,
+            buffer: 1000,
+        }
+    }
+}
+
+/// The sending half of an [`Events`], held by the client.
+///
+/// [`Events`]: struct.Events.html
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct EventsSender {
+    backpressure: Backpressure,
+    dropped: Arc<AtomicUsize>,
+    sender: SyncSender<ShardEvent>,
+}
+
+impl EventsSender {
+    /// Sends an event, returning whether the [`Events`] still exists.
+    ///
+    /// [`Events`]: struct.Events.html
+    pub fn send(&self, event: ShardEvent) -> bool {
+        match self.backpressure {
+            Backpressure::Block => self.sender.send(event).is_ok(),
+            Backpressure::Drop => match self.sender.try_send(event) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    warn!("Events buffer full, dropping event");
+
+                    self.dropped.fetch_add(1, Ordering::SeqCst);
+
+                    true
+                },
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+        }
+    }
+
+    /// Whether both senders are of the same [`Events`].
+    ///
+    /// [`Events`]: struct.Events.html
+    pub fn same(&self, other: &EventsSender) -> bool {
+        Arc::ptr_eq(&self.dropped, &other.dropped)
+    }
+}
+
+/// A blocking iterator over the events received by a [`Client`]'s shards,
+/// created via [`Client::events`] or [`Client::events_with`].
+///
+/// Events are yielded after they have passed through any middleware and have
+/// been applied to the [`Cache`], in the order in which each shard received
+/// them.
+///
+/// The iterator ends once the client - and all of its shards - have been
+/// dropped.
+///
+/// **Note**: By default, up to 1000 events are buffered. Once the buffer is
+/// full, further events are dropped - with a warning logged, and counted by
+/// [`dropped`] - until events are received from it. Refer to [`EventsConfig`]
+/// to change the size of the buffer, or to instead have the shards wait.
+///
+/// [`Cache`]: ../ext/cache/struct.Cache.html
+/// [`Client`]: struct.Client.html
+/// [`Client::events`]: struct.Client.html#method.events
+/// [`Client::events_with`]: struct.Client.html#method.events_with
+/// [`EventsConfig`]: struct.EventsConfig.html
+/// [`dropped`]: #method.dropped
+pub struct Events {
+    dropped: Arc<AtomicUsize>,
+    receiver: Receiver<ShardEvent>,
+}
+
+impl Events {
+    #[doc(hidden)]
+    pub fn new(config: EventsConfig) -> (EventsSender, Events) {
+        let (tx, rx) = mpsc::sync_channel(config.buffer);
+        let dropped = Arc::new(AtomicUsize::new(0));
+
+        let sender = EventsSender {
+            backpressure: config.backpressure,
+            dropped: dropped.clone(),
+            sender: tx,
+        };
+
+        (sender, Events {
+            dropped: dropped,
+            receiver: rx,
+        })
+    }
+
+    /// The number of events which were dropped because the buffer was full.
+    ///
+    /// If this has increased, then events were missed - and any state built
+    /// from them should be rebuilt, such as from the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    /// Receives the next event if one is available, without blocking.
+    pub fn try_next(&self) -> Option<ShardEvent> {
+        match self.receiver.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+
+    /// Blocks until the next event is received or the timeout expires.
+    ///
+    /// Returns `None` if the timeout expired, or if the client has been
+    /// dropped.
+    pub fn next_timeout(&self, timeout: Duration) -> Option<ShardEvent> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(event) => Some(event),
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = ShardEvent;
+
+    fn next(&mut self) -> Option<ShardEvent> {
+        self.receiver.recv().ok()
+    }
+}
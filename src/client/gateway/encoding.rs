@@ -0,0 +1,72 @@
+use serde_json;
+use super::etf;
+use ::internal::prelude::*;
+
+/// The format that payloads are encoded in over a [`Shard`]'s WebSocket
+/// connection.
+///
+/// Regardless of the encoding used, received payloads are decoded into the
+/// same [`GatewayEvent`]s and [`Event`]s.
+///
+/// [`Event`]: ../../model/event/enum.Event.html
+/// [`GatewayEvent`]: ../../model/event/enum.GatewayEvent.html
+/// [`Shard`]: struct.Shard.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Encoding {
+    /// Payloads are sent as JSON text. This is the default.
+    Json,
+    /// Payloads are sent in the binary Erlang [External Term Format].
+    ///
+    /// This results in smaller payloads over the wire, at the cost of
+    /// snowflakes being sent as integers rather than strings.
+    ///
+    /// [External Term Format]: http://erlang.org/doc/apps/erts/erl_ext_dist.html
+    Etf,
+}
+
+impl Encoding {
+    /// The name of the encoding, as passed in the `encoding` query parameter
+    /// of the gateway URL.
+    pub fn name(&self) -> &str {
+        match *self {
+            Encoding::Json => "json",
+            Encoding::Etf => "etf",
+        }
+    }
+
+    /// Decodes a raw, uncompressed payload into a `Value`.
+    ///
+    /// # Examples
+    ///
+    /// Decode an ETF payload of the map `#{<<"op">> => 11}`, a heartbeat
+    /// acknowledgement:
+    ///
+    /// ```rust
+    /// use serenity::client::gateway::GatewayEncoding;
+    ///
+    /// let bytes = [131, 116, 0, 0, 0, 1, 109, 0, 0, 0, 2, 111, 112, 97, 11];
+    /// let value = GatewayEncoding::Etf.decode(&bytes).unwrap();
+    ///
+    /// assert_eq!(value["op"], 11);
+    /// ```
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        match *self {
+            Encoding::Json => serde_json::from_slice(bytes).map_err(From::from),
+            Encoding::Etf => etf::decode(bytes),
+        }
+    }
+
+    /// Encodes a `Value` into a payload to be sent over the gateway.
+    pub fn encode(&self, value: &Value) -> Result<Vec<u8>> {
+        match *self {
+            Encoding::Json => serde_json::to_vec(value).map_err(From::from),
+            Encoding::Etf => etf::encode(value),
+        }
+    }
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::Json
+    }
+}
@@ -12,6 +12,9 @@ pub enum Error {
     ExpectedHello,
     /// Expected a Ready or an InvalidateSession
     InvalidHandshake,
+    /// A payload could not be decoded from or encoded to the Erlang External
+    /// Term Format.
+    InvalidEtf(&'static str),
     /// When a session Id was expected (for resuming), but was not present.
     NoSessionId,
     /// Failed to reconnect after a number of attempts.
@@ -26,6 +29,7 @@ impl Display for Error {
             },
             Error::ExpectedHello => f.write_str("Expected Hello during handshake"),
             Error::InvalidHandshake => f.write_str("Expected Ready or InvalidateSession"),
+            Error::InvalidEtf(reason) => f.write_str(&format!("Invalid ETF payload: {}", reason)),
             Error::NoSessionId => f.write_str("No Session Id present"),
             Error::ReconnectFailure => f.write_str("Failed to Reconnect"),
         }
@@ -0,0 +1,347 @@
+//! A minimal implementation of the Erlang [External Term Format], covering the
+//! subset of terms that Discord sends and accepts over the gateway.
+//!
+//! Terms are translated to and from `serde_json::Value`s, so that the rest of
+//! the library can decode events without caring about the wire format:
+//!
+//! - the atoms `nil` and `null` map to `Value::Null`;
+//! - the atoms `true` and `false` map to `Value::Bool`;
+//! - all other atoms and binaries map to `Value::String`;
+//! - integers - including small and large bignums which fit in 64 bits - and
+//!   floats map to `Value::Number`;
+//! - lists, strings, and tuples map to `Value::Array`;
+//! - maps map to `Value::Object`.
+//!
+//! [External Term Format]: http://erlang.org/doc/apps/erts/erl_ext_dist.html
+
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+use flate2::read::ZlibDecoder;
+use std::io::{Cursor, Read};
+use super::GatewayError;
+use ::internal::prelude::*;
+
+const FORMAT_VERSION: u8 = 131;
+
+const NEW_FLOAT_EXT: u8 = 70;
+const COMPRESSED: u8 = 80;
+const SMALL_INTEGER_EXT: u8 = 97;
+const INTEGER_EXT: u8 = 98;
+const FLOAT_EXT: u8 = 99;
+const ATOM_EXT: u8 = 100;
+const SMALL_TUPLE_EXT: u8 = 104;
+const LARGE_TUPLE_EXT: u8 = 105;
+const NIL_EXT: u8 = 106;
+const STRING_EXT: u8 = 107;
+const LIST_EXT: u8 = 108;
+const BINARY_EXT: u8 = 109;
+const SMALL_BIG_EXT: u8 = 110;
+const LARGE_BIG_EXT: u8 = 111;
+const SMALL_ATOM_EXT: u8 = 115;
+const MAP_EXT: u8 = 116;
+const ATOM_UTF8_EXT: u8 = 118;
+const SMALL_ATOM_UTF8_EXT: u8 = 119;
+
+/// Decodes a full ETF payload - including the leading version byte - into a
+/// `Value`.
+pub fn decode(bytes: &[u8]) -> Result<Value> {
+    let mut cursor = Cursor::new(bytes);
+
+    if cursor.read_u8()? != FORMAT_VERSION {
+        return Err(invalid("unsupported format version"));
+    }
+
+    let value = decode_term(&mut cursor)?;
+
+    if cursor.position() as usize != bytes.len() {
+        return Err(invalid("trailing bytes after term"));
+    }
+
+    Ok(value)
+}
+
+/// Encodes a `Value` into a full ETF payload, including the leading version
+/// byte.
+pub fn encode(value: &Value) -> Result<Vec<u8>> {
+    let mut buf = vec![FORMAT_VERSION];
+
+    encode_term(&mut buf, value)?;
+
+    Ok(buf)
+}
+
+fn invalid(reason: &'static str) -> Error {
+    Error::Gateway(GatewayError::InvalidEtf(reason))
+}
+
+fn decode_term<R: Read>(reader: &mut R) -> Result<Value> {
+    match reader.read_u8()? {
+        SMALL_INTEGER_EXT => Ok(Value::from(reader.read_u8()?)),
+        INTEGER_EXT => Ok(Value::from(reader.read_i32::<BigEndian>()?)),
+        NEW_FLOAT_EXT => {
+            let float = reader.read_f64::<BigEndian>()?;
+
+            Number::from_f64(float)
+                .map(Value::Number)
+                .ok_or_else(|| invalid("non-finite float"))
+        },
+        FLOAT_EXT => {
+            let mut buf = [0; 31];
+            reader.read_exact(&mut buf)?;
+
+            let s = String::from_utf8_lossy(&buf);
+
+            s.trim_right_matches('\0')
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| invalid("invalid float string"))
+        },
+        ATOM_EXT | ATOM_UTF8_EXT => {
+            let len = reader.read_u16::<BigEndian>()? as usize;
+
+            decode_atom(reader, len)
+        },
+        SMALL_ATOM_EXT | SMALL_ATOM_UTF8_EXT => {
+            let len = reader.read_u8()? as usize;
+
+            decode_atom(reader, len)
+        },
+        SMALL_TUPLE_EXT => {
+            let arity = reader.read_u8()? as usize;
+
+            decode_elements(reader, arity)
+        },
+        LARGE_TUPLE_EXT => {
+            let arity = reader.read_u32::<BigEndian>()? as usize;
+
+            decode_elements(reader, arity)
+        },
+        NIL_EXT => Ok(Value::Array(vec![])),
+        STRING_EXT => {
+            let len = reader.read_u16::<BigEndian>()? as usize;
+            let bytes = read_bytes(reader, len)?;
+
+            Ok(Value::Array(bytes.into_iter().map(Value::from).collect()))
+        },
+        LIST_EXT => {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            let elements = decode_elements(reader, len)?;
+
+            // Proper lists are terminated by an empty list. Improper lists are
+            // not used by Discord, so their tail is rejected.
+            match decode_term(reader)? {
+                Value::Array(ref tail) if tail.is_empty() => Ok(elements),
+                _ => Err(invalid("improper list")),
+            }
+        },
+        BINARY_EXT => {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+            let bytes = read_bytes(reader, len)?;
+
+            String::from_utf8(bytes)
+                .map(Value::String)
+                .map_err(|_| invalid("binary is not valid UTF-8"))
+        },
+        SMALL_BIG_EXT => {
+            let len = reader.read_u8()? as usize;
+
+            decode_big(reader, len)
+        },
+        LARGE_BIG_EXT => {
+            let len = reader.read_u32::<BigEndian>()? as usize;
+
+            decode_big(reader, len)
+        },
+        MAP_EXT => {
+            let arity = reader.read_u32::<BigEndian>()? as usize;
+            let mut map = Map::new();
+
+            for _ in 0..arity {
+                let key = match decode_term(reader)? {
+                    Value::String(key) => key,
+                    Value::Number(key) => key.to_string(),
+                    Value::Bool(key) => key.to_string(),
+                    Value::Null => "null".to_owned(),
+                    _ => return Err(invalid("unsupported map key")),
+                };
+                let value = decode_term(reader)?;
+
+                map.insert(key, value);
+            }
+
+            Ok(Value::Object(map))
+        },
+        COMPRESSED => {
+            let size = reader.read_u32::<BigEndian>()? as usize;
+            let mut inflated = Vec::with_capacity(size);
+
+            ZlibDecoder::new(reader).read_to_end(&mut inflated)?;
+
+            if inflated.len() != size {
+                return Err(invalid("compressed term size mismatch"));
+            }
+
+            decode_term(&mut Cursor::new(inflated))
+        },
+        _ => Err(invalid("unsupported term tag")),
+    }
+}
+
+fn decode_atom<R: Read>(reader: &mut R, len: usize) -> Result<Value> {
+    let bytes = read_bytes(reader, len)?;
+    let atom = String::from_utf8(bytes).map_err(|_| invalid("atom is not valid UTF-8"))?;
+
+    Ok(match &atom[..] {
+        "nil" | "null" => Value::Null,
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::String(atom),
+    })
+}
+
+fn decode_big<R: Read>(reader: &mut R, len: usize) -> Result<Value> {
+    let negative = reader.read_u8()? != 0;
+    let digits = read_bytes(reader, len)?;
+
+    // Discord only sends bignums for snowflakes, which always fit in a u64.
+    if digits.iter().skip(8).any(|&digit| digit != 0) {
+        return Err(invalid("integer does not fit in 64 bits"));
+    }
+
+    let magnitude = digits.iter()
+        .take(8)
+        .enumerate()
+        .fold(0u64, |acc, (i, &digit)| acc | ((digit as u64) << (8 * i)));
+
+    if !negative {
+        Ok(Value::from(magnitude))
+    } else if magnitude <= i64::max_value() as u64 + 1 {
+        Ok(Value::from((magnitude as i64).wrapping_neg()))
+    } else {
+        Err(invalid("integer does not fit in 64 bits"))
+    }
+}
+
+fn decode_elements<R: Read>(reader: &mut R, len: usize) -> Result<Value> {
+    let mut elements = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        elements.push(decode_term(reader)?);
+    }
+
+    Ok(Value::Array(elements))
+}
+
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(len);
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len {
+        return Err(invalid("unexpected end of payload"));
+    }
+
+    Ok(bytes)
+}
+
+fn encode_term(buf: &mut Vec<u8>, value: &Value) -> Result<()> {
+    match *value {
+        Value::Null => encode_atom(buf, "nil"),
+        Value::Bool(true) => encode_atom(buf, "true"),
+        Value::Bool(false) => encode_atom(buf, "false"),
+        Value::Number(ref number) => {
+            if let Some(int) = number.as_u64() {
+                encode_unsigned(buf, int, false)
+            } else if let Some(int) = number.as_i64() {
+                if int >= i32::min_value() as i64 {
+                    buf.push(INTEGER_EXT);
+                    buf.write_i32::<BigEndian>(int as i32)?;
+                } else {
+                    encode_unsigned(buf, (int as u64).wrapping_neg(), true)?;
+                }
+
+                Ok(())
+            } else if let Some(float) = number.as_f64() {
+                buf.push(NEW_FLOAT_EXT);
+                buf.write_f64::<BigEndian>(float)?;
+
+                Ok(())
+            } else {
+                Err(invalid("unrepresentable number"))
+            }
+        },
+        Value::String(ref s) => {
+            encode_binary(buf, s);
+
+            Ok(())
+        },
+        Value::Array(ref elements) => {
+            if elements.is_empty() {
+                buf.push(NIL_EXT);
+
+                return Ok(());
+            }
+
+            buf.push(LIST_EXT);
+            buf.write_u32::<BigEndian>(elements.len() as u32)?;
+
+            for element in elements {
+                encode_term(buf, element)?;
+            }
+
+            buf.push(NIL_EXT);
+
+            Ok(())
+        },
+        Value::Object(ref map) => {
+            buf.push(MAP_EXT);
+            buf.write_u32::<BigEndian>(map.len() as u32)?;
+
+            for (key, value) in map {
+                encode_binary(buf, key);
+                encode_term(buf, value)?;
+            }
+
+            Ok(())
+        },
+    }
+}
+
+fn encode_atom(buf: &mut Vec<u8>, atom: &str) -> Result<()> {
+    buf.push(SMALL_ATOM_UTF8_EXT);
+    buf.push(atom.len() as u8);
+    buf.extend_from_slice(atom.as_bytes());
+
+    Ok(())
+}
+
+fn encode_binary(buf: &mut Vec<u8>, s: &str) {
+    buf.push(BINARY_EXT);
+    let _ = buf.write_u32::<BigEndian>(s.len() as u32);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn encode_unsigned(buf: &mut Vec<u8>, int: u64, negative: bool) -> Result<()> {
+    if !negative && int <= u8::max_value() as u64 {
+        buf.push(SMALL_INTEGER_EXT);
+        buf.push(int as u8);
+    } else if !negative && int <= i32::max_value() as u64 {
+        buf.push(INTEGER_EXT);
+        buf.write_i32::<BigEndian>(int as i32)?;
+    } else {
+        let mut digits = vec![];
+        buf.push(SMALL_BIG_EXT);
+        digits.write_u64::<LittleEndian>(int)?;
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        buf.push(digits.len() as u8);
+        buf.push(negative as u8);
+        buf.extend_from_slice(&digits);
+    }
+
+    Ok(())
+}
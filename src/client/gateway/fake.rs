@@ -0,0 +1,442 @@
+use serde::Deserialize;
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::Builder as ThreadBuilder;
+use std::time::{Duration, Instant};
+use super::GatewayEncoding;
+use websocket::client::{Receiver, Sender};
+use websocket::message::Message as WsMessage;
+use websocket::server::Server;
+use websocket::stream::WebSocketStream;
+use websocket::ws::sender::Sender as WsSender;
+use ::constants::OpCode;
+use ::internal::prelude::*;
+use ::internal::ws_impl::{ReceiverExt, SenderExt};
+
+/// The heartbeat interval sent in the fake gateway's HELLO by default, in
+/// milliseconds.
+const HEARTBEAT_INTERVAL: u64 = 41250;
+
+/// An in-process, fake version of Discord's gateway, for testing code which
+/// uses a [`Shard`] or [`Client`] without connecting to Discord.
+///
+/// The fake gateway listens for WebSocket connections on a local port, and
+/// speaks enough of the gateway protocol for shards to connect, stay
+/// connected, and resume:
+///
+/// - a HELLO is sent upon connecting;
+/// - an IDENTIFY is responded to with a READY, starting a new session;
+/// - a heartbeat is responded to with a heartbeat acknowledgement;
+/// - a RESUME of the current session is responded to with a RESUMED, while a
+/// RESUME of any other session is responded to with an invalid session.
+///
+/// Every payload sent by the connected shard is kept, and can be inspected
+/// via [`received`] or waited on via [`wait_for`]. Dispatches can be sent to
+/// the connected shard via [`dispatch`], and the connection can be closed
+/// with a code via [`close`] or dropped without one via [`drop_connection`].
+///
+/// Payloads are sent in the encoding that the shard requested when
+/// connecting.
+///
+/// Connect a [`Client`] to the fake gateway via [`Client::with_gateway_url`],
+/// or a [`Shard`] by passing the [`url`] to [`Shard::new`].
+///
+/// # Examples
+///
+/// Connect a shard to the fake gateway and send it a dispatch:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate serde_json;
+/// # extern crate serenity;
+/// #
+/// # fn main() {
+/// use serenity::client::gateway::{FakeGateway, Shard};
+///
+/// let gateway = FakeGateway::start().unwrap();
+///
+/// let (shard, ready, receiver) = Shard::new(&gateway.url(), "fake token", None).unwrap();
+///
+/// assert_eq!(ready.ready.session_id, "fake-session-1");
+///
+/// gateway.dispatch("TYPING_START", json!({
+///     "channel_id": "81384788765712384",
+///     "timestamp": 1492552800,
+///     "user_id": "114941315417899012",
+/// }));
+/// # }
+/// ```
+///
+/// [`Client`]: ../struct.Client.html
+/// [`Client::with_gateway_url`]: ../struct.Client.html#method.with_gateway_url
+/// [`Shard`]: struct.Shard.html
+/// [`Shard::new`]: struct.Shard.html#method.new
+/// [`close`]: #method.close
+/// [`dispatch`]: #method.dispatch
+/// [`drop_connection`]: #method.drop_connection
+/// [`received`]: #method.received
+/// [`url`]: #method.url
+/// [`wait_for`]: #method.wait_for
+pub struct FakeGateway {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    condvar: Condvar,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    closed: bool,
+    connection: Option<Connection>,
+    connections: usize,
+    heartbeat_interval: Option<u64>,
+    ready: Option<Value>,
+    received: Vec<Value>,
+    seq: u64,
+    session_id: Option<String>,
+    sessions: usize,
+}
+
+struct Connection {
+    encoding: GatewayEncoding,
+    id: usize,
+    sender: Sender<WebSocketStream>,
+}
+
+impl FakeGateway {
+    /// Starts a fake gateway listening on a random local port.
+    pub fn start() -> Result<FakeGateway> {
+        let mut server = Server::bind("127.0.0.1:0")?;
+        let addr = server.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let accepting = shared.clone();
+
+        ThreadBuilder::new()
+            .name("serenity fake gateway".to_owned())
+            .spawn(move || {
+                while let Ok(connection) = server.accept() {
+                    if accepting.state.lock().unwrap().closed {
+                        break;
+                    }
+
+                    if let Err(why) = accept(connection, &accepting) {
+                        warn!("[fake gateway] Error accepting connection: {:?}", why);
+                    }
+                }
+            })?;
+
+        Ok(FakeGateway {
+            addr: addr,
+            shared: shared,
+        })
+    }
+
+    /// The URL to connect to the fake gateway with.
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    /// Sets the heartbeat interval to send in the HELLO of new connections,
+    /// in milliseconds.
+    ///
+    /// **Note**: Defaults to `41250`.
+    pub fn set_heartbeat_interval(&self, interval: u64) {
+        self.shared.state.lock().unwrap().heartbeat_interval = Some(interval);
+    }
+
+    /// Sets the data of the READY sent in response to an IDENTIFY.
+    ///
+    /// The `session_id` and `shard` fields are always overwritten. By default,
+    /// a READY is sent for a bot user with an Id of `1` which is in no guilds.
+    pub fn set_ready(&self, ready: Value) {
+        self.shared.state.lock().unwrap().ready = Some(ready);
+    }
+
+    /// The number of connections that have been accepted so far.
+    pub fn connections(&self) -> usize {
+        self.shared.state.lock().unwrap().connections
+    }
+
+    /// Every payload that has been received from shards so far, in the order
+    /// they were received.
+    pub fn received(&self) -> Vec<Value> {
+        self.shared.state.lock().unwrap().received.clone()
+    }
+
+    /// Blocks the current thread until a payload matching the filter has
+    /// been received, or until the timeout elapses.
+    ///
+    /// Payloads that were received before calling this are also checked, so
+    /// it does not matter whether the payload arrives before or after the
+    /// call.
+    ///
+    /// # Examples
+    ///
+    /// Wait for a shard to resume:
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// let resume = gateway.wait_for(Duration::from_secs(5), |p| p["op"] == 6);
+    ///
+    /// assert!(resume.is_some());
+    /// ```
+    pub fn wait_for<F>(&self, timeout: Duration, f: F) -> Option<Value>
+        where F: Fn(&Value) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock().unwrap();
+
+        loop {
+            if let Some(payload) = state.received.iter().find(|payload| f(payload)) {
+                return Some(payload.clone());
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return None;
+            }
+
+            state = self.shared.condvar.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    /// Sends a dispatch with the given event name - such as
+    /// `"MESSAGE_CREATE"` - and data to the connected shard, with the next
+    /// sequence number of the session.
+    ///
+    /// Returns whether a shard was connected to send the dispatch to.
+    pub fn dispatch(&self, name: &str, data: Value) -> bool {
+        let mut state = self.shared.state.lock().unwrap();
+
+        send_dispatch(&mut state, name, data)
+    }
+
+    /// Sends a raw payload to the connected shard, such as a reconnect
+    /// request or an invalid session.
+    ///
+    /// Returns whether a shard was connected to send the payload to.
+    pub fn send(&self, payload: Value) -> bool {
+        let mut state = self.shared.state.lock().unwrap();
+
+        send(&mut state, &payload)
+    }
+
+    /// Closes the connection to the connected shard with the given close code
+    /// and reason.
+    ///
+    /// Returns whether a shard was connected.
+    pub fn close(&self, code: u16, reason: &str) -> bool {
+        let connection = self.shared.state.lock().unwrap().connection.take();
+
+        match connection {
+            Some(mut connection) => {
+                let message = WsMessage::close_because(code, reason.to_owned());
+                let _ = connection.sender.send_message(&message);
+                let _ = connection.sender.shutdown_all();
+
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Drops the connection to the connected shard without sending a close
+    /// code, as if the network connection had been lost.
+    ///
+    /// Returns whether a shard was connected.
+    pub fn drop_connection(&self) -> bool {
+        let connection = self.shared.state.lock().unwrap().connection.take();
+
+        match connection {
+            Some(mut connection) => {
+                let _ = connection.sender.shutdown_all();
+
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Drop for FakeGateway {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().closed = true;
+        self.drop_connection();
+
+        // Wake up the accepting thread so that it can exit.
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+fn accept(connection: ::websocket::server::Connection<WebSocketStream, WebSocketStream>,
+          shared: &Arc<Shared>)
+          -> Result<()> {
+    let request = connection.read_request()?;
+    let encoding = if request.url.to_string().contains("encoding=etf") {
+        GatewayEncoding::Etf
+    } else {
+        GatewayEncoding::Json
+    };
+
+    let (sender, receiver) = request.accept().send()?.split();
+
+    let id = {
+        let mut state = shared.state.lock().unwrap();
+        state.connections += 1;
+
+        if let Some(mut old) = state.connection.take() {
+            let _ = old.sender.shutdown_all();
+        }
+
+        state.connection = Some(Connection {
+            encoding: encoding,
+            id: state.connections,
+            sender: sender,
+        });
+
+        let hello = json!({
+            "op": OpCode::Hello.num(),
+            "d": {
+                "heartbeat_interval": state.heartbeat_interval.unwrap_or(HEARTBEAT_INTERVAL),
+                "_trace": ["fake-gateway"],
+            },
+        });
+        send(&mut state, &hello);
+
+        state.connections
+    };
+
+    let shared = shared.clone();
+
+    ThreadBuilder::new()
+        .name(format!("serenity fake gateway [connection {}]", id))
+        .spawn(move || handle_connection(id, encoding, receiver, &shared))?;
+
+    Ok(())
+}
+
+fn handle_connection(id: usize,
+                     encoding: GatewayEncoding,
+                     mut receiver: Receiver<WebSocketStream>,
+                     shared: &Shared) {
+    loop {
+        let payload = match receiver.recv_encoded(encoding, Ok) {
+            Ok(payload) => payload,
+            Err(why) => {
+                debug!("[fake gateway] Connection {} ended: {:?}", id, why);
+
+                break;
+            },
+        };
+
+        let mut state = shared.state.lock().unwrap();
+
+        // Ignore anything still being received over a replaced connection.
+        if state.connection.as_ref().map(|c| c.id) != Some(id) {
+            break;
+        }
+
+        state.received.push(payload.clone());
+        shared.condvar.notify_all();
+
+        let op = payload.get("op").cloned().and_then(|op| OpCode::deserialize(op).ok());
+
+        match op {
+            Some(OpCode::Heartbeat) => {
+                send(&mut state, &json!({
+                    "op": OpCode::HeartbeatAck.num(),
+                }));
+            },
+            Some(OpCode::Identify) => {
+                state.sessions += 1;
+                state.seq = 0;
+
+                let session_id = format!("fake-session-{}", state.sessions);
+                let mut ready = state.ready.clone().unwrap_or_else(default_ready);
+
+                if let Some(map) = ready.as_object_mut() {
+                    map.insert("session_id".to_owned(), Value::String(session_id.clone()));
+                    map.insert("shard".to_owned(), payload["d"]["shard"].clone());
+                }
+
+                state.session_id = Some(session_id);
+                send_dispatch(&mut state, "READY", ready);
+            },
+            Some(OpCode::Resume) => {
+                let session_id = payload["d"]["session_id"].as_str().map(|s| s.to_owned());
+
+                if session_id.is_some() && session_id == state.session_id {
+                    send_dispatch(&mut state, "RESUMED", json!({
+                        "_trace": ["fake-gateway"],
+                    }));
+                } else {
+                    send(&mut state, &json!({
+                        "op": OpCode::InvalidSession.num(),
+                        "d": false,
+                    }));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    let mut state = shared.state.lock().unwrap();
+
+    if state.connection.as_ref().map(|c| c.id) == Some(id) {
+        state.connection = None;
+    }
+}
+
+fn send(state: &mut State, payload: &Value) -> bool {
+    let sent = match state.connection {
+        Some(ref mut connection) => {
+            connection.sender.send_encoded(connection.encoding, payload).is_ok()
+        },
+        None => false,
+    };
+
+    if !sent {
+        debug!("[fake gateway] No connection to send to: {:?}", payload);
+    }
+
+    sent
+}
+
+fn send_dispatch(state: &mut State, name: &str, data: Value) -> bool {
+    state.seq += 1;
+
+    let payload = json!({
+        "op": OpCode::Event.num(),
+        "s": state.seq,
+        "t": name,
+        "d": data,
+    });
+
+    send(state, &payload)
+}
+
+fn default_ready() -> Value {
+    json!({
+        "guilds": [],
+        "presences": [],
+        "private_channels": [],
+        "session_id": "",
+        "shard": null,
+        "user": {
+            "avatar": null,
+            "bot": true,
+            "discriminator": "0001",
+            "email": null,
+            "id": "1",
+            "mfa_enabled": false,
+            "username": "fake",
+            "verified": true,
+        },
+        "v": ::constants::GATEWAY_VERSION,
+        "_trace": ["fake-gateway"],
+    })
+}
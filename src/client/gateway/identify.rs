@@ -0,0 +1,158 @@
+use serde_json::Value;
+use std::cmp;
+use std::default::Default;
+use std::env;
+use super::prep;
+use ::constants::LARGE_THRESHOLD;
+use ::model::{Game, OnlineStatus};
+
+// The lowest large threshold that Discord accepts.
+const MIN_LARGE_THRESHOLD: u8 = 50;
+
+/// The settings sent to Discord when a [`Shard`] identifies, including the
+/// presence that the current user comes online with.
+///
+/// The same settings are used by every shard started by a [`Client`], and
+/// whenever a shard re-identifies after its session has been invalidated. If
+/// the presence has since been changed - such as via [`Context::set_game`] -
+/// then the shard will re-identify with the updated presence instead.
+///
+/// # Examples
+///
+/// Come online as "do not disturb", playing a game, and only receive online
+/// members for guilds with more than 100 members:
+///
+/// ```rust,no_run
+/// use serenity::model::{Game, OnlineStatus};
+/// use serenity::Client;
+/// use std::env;
+///
+/// let mut client = Client::login(&env::var("DISCORD_BOT_TOKEN").unwrap());
+///
+/// client.with_identify(|i| i
+///     .game(Game::playing("with fire"))
+///     .status(OnlineStatus::DoNotDisturb)
+///     .large_threshold(100));
+/// ```
+///
+/// [`Client`]: ../struct.Client.html
+/// [`Context::set_game`]: ../struct.Context.html#method.set_game
+/// [`Shard`]: struct.Shard.html
+#[derive(Clone, Debug)]
+pub struct Identify {
+    pub(crate) afk: bool,
+    pub(crate) browser: String,
+    pub(crate) device: String,
+    pub(crate) game: Option<Game>,
+    pub(crate) large_threshold: u8,
+    pub(crate) os: String,
+    pub(crate) status: OnlineStatus,
+}
+
+impl Identify {
+    /// Whether the current user should be marked as AFK.
+    ///
+    /// **Note**: Defaults to `false`.
+    pub fn afk(mut self, afk: bool) -> Self {
+        self.afk = afk;
+
+        self
+    }
+
+    /// The value of the `$browser` identify property.
+    ///
+    /// **Note**: Defaults to `"serenity"`.
+    pub fn browser<S: Into<String>>(mut self, browser: S) -> Self {
+        self.browser = browser.into();
+
+        self
+    }
+
+    /// The value of the `$device` identify property.
+    ///
+    /// **Note**: Defaults to `"serenity"`.
+    pub fn device<S: Into<String>>(mut self, device: S) -> Self {
+        self.device = device.into();
+
+        self
+    }
+
+    /// The game that the current user should initially be playing.
+    ///
+    /// **Note**: Defaults to no game.
+    pub fn game(mut self, game: Game) -> Self {
+        self.game = Some(game);
+
+        self
+    }
+
+    /// The number of members a guild must have for Discord to stop sending
+    /// offline members in its [`GuildCreate`] event.
+    ///
+    /// Discord accepts values between 50 and 250, and so values outside of
+    /// this range are clamped to it.
+    ///
+    /// **Note**: Defaults to [`LARGE_THRESHOLD`].
+    ///
+    /// [`GuildCreate`]: ../../model/event/enum.Event.html#variant.GuildCreate
+    /// [`LARGE_THRESHOLD`]: ../../constants/constant.LARGE_THRESHOLD.html
+    pub fn large_threshold(mut self, large_threshold: u8) -> Self {
+        let large_threshold = cmp::min(large_threshold, LARGE_THRESHOLD);
+        self.large_threshold = cmp::max(large_threshold, MIN_LARGE_THRESHOLD);
+
+        self
+    }
+
+    /// The value of the `$os` identify property.
+    ///
+    /// **Note**: Defaults to the operating system the library was compiled
+    /// for.
+    pub fn os<S: Into<String>>(mut self, os: S) -> Self {
+        self.os = os.into();
+
+        self
+    }
+
+    /// Builds the identify payload sent to the gateway by a shard.
+    #[doc(hidden)]
+    pub fn payload(&self, token: &str, shard_info: Option<[u64; 2]>) -> Value {
+        prep::identify(token, shard_info, self)
+    }
+
+    /// The online status that the current user should initially have.
+    ///
+    /// **Note**: Defaults to [`OnlineStatus::Online`].
+    ///
+    /// [`OnlineStatus::Online`]: ../../model/enum.OnlineStatus.html#variant.Online
+    pub fn status(mut self, status: OnlineStatus) -> Self {
+        self.status = status;
+
+        self
+    }
+}
+
+impl Default for Identify {
+    /// Builds a default identify configuration, with the following settings:
+    ///
+    /// - **afk**: false
+    /// - **browser**: `"serenity"`
+    /// - **device**: `"serenity"`
+    /// - **game**: None
+    /// - **large_threshold**: [`LARGE_THRESHOLD`]
+    /// - **os**: the compilation target's operating system
+    /// - **status**: [`OnlineStatus::Online`]
+    ///
+    /// [`LARGE_THRESHOLD`]: ../../constants/constant.LARGE_THRESHOLD.html
+    /// [`OnlineStatus::Online`]: ../../model/enum.OnlineStatus.html#variant.Online
+    fn default() -> Identify {
+        Identify {
+            afk: false,
+            browser: "serenity".to_owned(),
+            device: "serenity".to_owned(),
+            game: None,
+            large_threshold: LARGE_THRESHOLD,
+            os: env::consts::OS.to_owned(),
+            status: OnlineStatus::Online,
+        }
+    }
+}
@@ -49,11 +49,23 @@
 //! [`Client::start_shards`]: ../struct.Client.html#method.start_shards
 //! [docs]: https://discordapp.com/developers/docs/topics/gateway#sharding
 
+mod encoding;
 mod error;
+mod etf;
+#[cfg(feature="fake-gateway")]
+mod fake;
+mod identify;
 mod prep;
+mod recording;
 mod shard;
 mod status;
 
+pub use self::encoding::Encoding as GatewayEncoding;
 pub use self::error::Error as GatewayError;
-pub use self::shard::Shard;
+pub use self::identify::Identify;
+pub use self::recording::{RecordedEvent, Recorder, Recording};
+pub use self::shard::{Shard, ShardConfig};
 pub use self::status::Status as GatewayStatus;
+
+#[cfg(feature="fake-gateway")]
+pub use self::fake::FakeGateway;
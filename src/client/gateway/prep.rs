@@ -6,25 +6,27 @@ use std::sync::mpsc::{
 };
 use std::sync::{Arc, Mutex};
 use std::time::{Duration as StdDuration, Instant};
-use std::{env, thread};
+use std::thread;
 use super::super::ClientError;
-use super::{GatewayError, GatewayStatus};
+use super::{GatewayEncoding, GatewayError, GatewayStatus, Identify, Recorder};
 use time::{self, Duration};
 use websocket::client::request::Url as RequestUrl;
 use websocket::client::{Receiver, Sender};
 use websocket::result::WebSocketError as WsError;
 use websocket::stream::WebSocketStream;
-use ::constants::{self, LARGE_THRESHOLD, OpCode};
+use ::constants::{self, OpCode};
 use ::error::{Error, Result};
 use ::internal::ws_impl::{ReceiverExt, SenderExt};
 use ::model::event::{Event, GatewayEvent, ReadyEvent};
+use ::model::Game;
 
 #[inline]
-pub fn parse_ready(event: GatewayEvent,
-                   tx: &MpscSender<GatewayStatus>,
-                   receiver: &mut Receiver<WebSocketStream>,
-                   identification: Value)
-                   -> Result<(ReadyEvent, u64)> {
+pub fn parse_ready<F>(event: GatewayEvent,
+                      tx: &MpscSender<GatewayStatus>,
+                      identification: Value,
+                      next: F)
+                      -> Result<(ReadyEvent, u64)>
+                      where F: FnOnce() -> Result<GatewayEvent> {
     match event {
         GatewayEvent::Dispatch(seq, Event::Ready(event)) => {
             Ok((event, seq))
@@ -34,7 +36,7 @@ pub fn parse_ready(event: GatewayEvent,
 
             let _ = tx.send(GatewayStatus::SendMessage(identification));
 
-            match receiver.recv_json(GatewayEvent::decode)? {
+            match next()? {
                 GatewayEvent::Dispatch(seq, Event::Ready(event)) => {
                     Ok((event, seq))
                 },
@@ -53,30 +55,88 @@ pub fn parse_ready(event: GatewayEvent,
     }
 }
 
-pub fn identify(token: &str, shard_info: Option<[u64; 2]>) -> Value {
+/// Receives the next payload, recording it before it is decoded if a recorder
+/// is in use.
+pub fn recv_event(receiver: &mut Receiver<WebSocketStream>,
+                  encoding: GatewayEncoding,
+                  recorder: Option<&Recorder>,
+                  shard_info: Option<[u64; 2]>)
+                  -> Result<GatewayEvent> {
+    receiver.recv_encoded(encoding, |value| {
+        if let Some(recorder) = recorder {
+            recorder.record(shard_info, &value);
+        }
+
+        GatewayEvent::decode(value)
+    })
+}
+
+/// Receives the next payload like [`recv_event`], keeping a copy of it if it
+/// is a dispatch.
+///
+/// [`recv_event`]: fn.recv_event.html
+pub fn recv_dispatch(receiver: &mut Receiver<WebSocketStream>,
+                     encoding: GatewayEncoding,
+                     recorder: Option<&Recorder>,
+                     shard_info: Option<[u64; 2]>,
+                     payload: &mut Option<Value>)
+                     -> Result<GatewayEvent> {
+    receiver.recv_encoded(encoding, |value| {
+        if let Some(recorder) = recorder {
+            recorder.record(shard_info, &value);
+        }
+
+        if value.get("op").and_then(Value::as_u64) == Some(OpCode::Event.num()) {
+            *payload = Some(value.clone());
+        }
+
+        GatewayEvent::decode(value)
+    })
+}
+
+pub fn identify(token: &str, shard_info: Option<[u64; 2]>, identify: &Identify) -> Value {
     json!({
         "op": OpCode::Identify.num(),
         "d": {
             "compression": !cfg!(feature="debug"),
-            "large_threshold": LARGE_THRESHOLD,
+            "large_threshold": identify.large_threshold,
             "shard": shard_info.unwrap_or([0, 1]),
             "token": token,
             "v": constants::GATEWAY_VERSION,
+            "presence": {
+                "afk": identify.afk,
+                "since": 0,
+                "status": identify.status.name(),
+                "game": identify.game.as_ref().map(game),
+            },
             "properties": {
-                "$browser": "serenity",
-                "$device": "serenity",
-                "$os": env::consts::OS,
+                "$browser": identify.browser,
+                "$device": identify.device,
+                "$os": identify.os,
             },
         },
     })
 }
 
-pub fn build_gateway_url(base: &str) -> Result<RequestUrl> {
-    RequestUrl::parse(&format!("{}?v={}", base, constants::GATEWAY_VERSION))
+/// Serializes a game for an identify or presence update payload.
+pub fn game(game: &Game) -> Value {
+    json!({
+        "name": game.name,
+        "type": game.kind,
+        "url": game.url,
+    })
+}
+
+pub fn build_gateway_url(base: &str, encoding: GatewayEncoding) -> Result<RequestUrl> {
+    RequestUrl::parse(&format!("{}?v={}&encoding={}",
+                               base,
+                               constants::GATEWAY_VERSION,
+                               encoding.name()))
         .map_err(|_| Error::Client(ClientError::Gateway))
 }
 
 pub fn keepalive(interval: u64,
+                 encoding: GatewayEncoding,
                  heartbeat_sent: Arc<Mutex<Instant>>,
                  mut sender: Sender<WebSocketStream>,
                  channel: &MpscReceiver<GatewayStatus>) {
@@ -98,7 +158,7 @@ pub fn keepalive(interval: u64,
                     sender = new_sender;
                 },
                 Ok(GatewayStatus::SendMessage(val)) => {
-                    if let Err(why) = sender.send_json(&val) {
+                    if let Err(why) = sender.send_encoded(encoding, &val) {
                         warn!("Error sending message: {:?}", why);
                     }
                 },
@@ -120,7 +180,7 @@ pub fn keepalive(interval: u64,
 
             trace!("Sending heartbeat d: {}", last_sequence);
 
-            match sender.send_json(&map) {
+            match sender.send_encoded(encoding, &map) {
                 Ok(_) => {
                     let now = Instant::now();
 
@@ -0,0 +1,156 @@
+use serde_json;
+use std::fs::File;
+use std::io::{BufRead, BufReader, LineWriter, Lines, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use ::internal::prelude::*;
+
+/// Records every payload received over the gateway to a file, so that the
+/// session can later be replayed via [`Client::replay`].
+///
+/// Each payload is written as a line of JSON - regardless of the
+/// [`GatewayEncoding`] it was received in - alongside the time it was received
+/// and the information of the shard which received it. The file is flushed
+/// after every line, so a recording remains usable if the process crashes.
+///
+/// Use [`Client::record_to`] to record all shards started by a client.
+///
+/// **Note**: Recordings contain everything that the current user can see, and
+/// should be treated with the same care as the bot's token.
+///
+/// [`Client::record_to`]: ../struct.Client.html#method.record_to
+/// [`Client::replay`]: ../struct.Client.html#method.replay
+/// [`GatewayEncoding`]: enum.GatewayEncoding.html
+#[derive(Clone)]
+pub struct Recorder {
+    writer: Arc<Mutex<LineWriter<File>>>,
+}
+
+impl Recorder {
+    /// Creates a recorder which writes to the file at the given path,
+    /// truncating it if it already exists.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Recorder> {
+        let file = File::create(path)?;
+
+        Ok(Recorder {
+            writer: Arc::new(Mutex::new(LineWriter::new(file))),
+        })
+    }
+
+    /// Records a received payload.
+    ///
+    /// Failing to write to the file is logged, rather than interrupting the
+    /// shard.
+    pub fn record(&self, shard_info: Option<[u64; 2]>, payload: &Value) {
+        let line = json!({
+            "payload": payload,
+            "shard": shard_info,
+            "time": now_millis(),
+        });
+
+        let mut writer = self.writer.lock().unwrap();
+
+        if let Err(why) = writeln!(writer, "{}", line) {
+            warn!("Error recording payload: {:?}", why);
+        }
+    }
+}
+
+/// A payload which was recorded by a [`Recorder`].
+///
+/// [`Recorder`]: struct.Recorder.html
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    /// The payload, as it would be passed to [`GatewayEvent::decode`].
+    ///
+    /// [`GatewayEvent::decode`]: ../../model/event/enum.GatewayEvent.html#method.decode
+    pub payload: Value,
+    /// The information of the shard which received the payload.
+    pub shard_info: Option<[u64; 2]>,
+    /// The time the payload was received, in milliseconds since the Unix
+    /// epoch.
+    pub time: u64,
+}
+
+/// An iterator over the payloads in a file written by a [`Recorder`].
+///
+/// # Examples
+///
+/// Count the number of `MESSAGE_CREATE` dispatches in a recording:
+///
+/// ```rust,no_run
+/// use serenity::client::gateway::Recording;
+///
+/// let recording = Recording::open("session.jsonl").unwrap();
+/// let count = recording
+///     .filter_map(|event| event.ok())
+///     .filter(|event| event.payload["t"] == "MESSAGE_CREATE")
+///     .count();
+///
+/// println!("{} messages were received", count);
+/// ```
+///
+/// [`Recorder`]: struct.Recorder.html
+pub struct Recording {
+    lines: Lines<BufReader<File>>,
+}
+
+impl Recording {
+    /// Opens the recording at the given path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Recording> {
+        let file = File::open(path)?;
+
+        Ok(Recording {
+            lines: BufReader::new(file).lines(),
+        })
+    }
+}
+
+impl Iterator for Recording {
+    type Item = Result<RecordedEvent>;
+
+    fn next(&mut self) -> Option<Result<RecordedEvent>> {
+        loop {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(why)) => return Some(Err(From::from(why))),
+                None => return None,
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            return Some(decode_line(&line));
+        }
+    }
+}
+
+fn decode_line(line: &str) -> Result<RecordedEvent> {
+    let mut map: JsonMap = serde_json::from_str(line)?;
+
+    let payload = map.remove("payload")
+        .ok_or_else(|| Error::Decode("expected recorded payload", Value::Object(map.clone())))?;
+    let shard_info = match map.remove("shard") {
+        Some(v) => serde_json::from_value::<Option<[u64; 2]>>(v)?,
+        None => None,
+    };
+    let time = map.get("time")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| Error::Decode("expected recorded time", Value::Object(map.clone())))?;
+
+    Ok(RecordedEvent {
+        payload: payload,
+        shard_info: shard_info,
+        time: time,
+    })
+}
+
+fn now_millis() -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    now.as_secs() * 1000 + u64::from(now.subsec_nanos() / 1_000_000)
+}
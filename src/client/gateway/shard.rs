@@ -5,8 +5,8 @@ use std::sync::{Arc, Mutex};
 use std::thread::{self, Builder as ThreadBuilder};
 use std::time::{Duration as StdDuration, Instant};
 use std::mem;
-use super::super::rest;
-use super::{GatewayError, GatewayStatus, prep};
+use super::super::{MemberChunks, rest};
+use super::{GatewayEncoding, GatewayError, GatewayStatus, Identify, Recorder, prep};
 use time;
 use websocket::client::{Client as WsClient, Sender, Receiver};
 use websocket::message::Message as WsMessage;
@@ -18,11 +18,14 @@ use ::internal::prelude::*;
 use ::internal::ws_impl::{ReceiverExt, SenderExt};
 use ::model::event::{Event, GatewayEvent, ReadyEvent};
 use ::model::{Game, GuildId, OnlineStatus};
+use ::utils::builder::RequestGuildMembers;
 
 #[cfg(feature="cache")]
 use ::client::CACHE;
 #[cfg(feature="voice")]
 use ::ext::voice::Manager as VoiceManager;
+#[cfg(feature="voice")]
+use ::model::UserId;
 #[cfg(feature="cache")]
 use ::utils;
 
@@ -64,6 +67,7 @@ type CurrentPresence = (Option<Game>, OnlineStatus, bool);
 /// [module docs]: index.html#sharding
 pub struct Shard {
     current_presence: CurrentPresence,
+    encoding: GatewayEncoding,
     /// A tuple of the last instant that a heartbeat was sent, and the last that
     /// an acknowledgement was received.
     ///
@@ -71,7 +75,15 @@ pub struct Shard {
     ///
     /// [`latency`]: fn.latency.html
     heartbeat_instants: (Arc<Mutex<Instant>>, Option<Instant>),
+    identify: Identify,
     keepalive_channel: MpscSender<GatewayStatus>,
+    /// The payload of the READY - or RESUMED - received when the shard last
+    /// connected, until it is taken by the client.
+    ready_payload: Option<Value>,
+    recorder: Option<Recorder>,
+    /// Whether the gateway URL is retrieved from the REST API again when
+    /// reconnecting, which is not done when a custom URL is in use.
+    refresh_gateway_url: bool,
     seq: u64,
     session_id: Option<String>,
     shard_info: Option<[u64; 2]>,
@@ -111,17 +123,63 @@ impl Shard {
                token: &str,
                shard_info: Option<[u64; 2]>)
                -> Result<(Shard, ReadyEvent, Receiver<WebSocketStream>)> {
-        let url = prep::build_gateway_url(base_url)?;
+        Shard::connect(base_url, token, shard_info, ShardConfig::default())
+    }
+
+    /// Instantiates a new instance of a Shard like [`new`], configuring how it
+    /// connects via a [`ShardConfig`].
+    ///
+    /// Refer to [`ShardConfig`] for the defaults.
+    ///
+    /// **Note**: You should likely never need to do this yourself.
+    ///
+    /// # Examples
+    ///
+    /// Instantiating a new Shard which uses ETF and comes online as idle:
+    ///
+    /// ```rust,ignore
+    /// use serenity::client::gateway::{GatewayEncoding, Identify, Shard};
+    /// use serenity::client::rest;
+    /// use serenity::model::OnlineStatus;
+    /// use std::env;
+    ///
+    /// let token = env::var("DISCORD_BOT_TOKEN").expect("Token in environment");
+    /// let gateway = rest::get_gateway().expect("Valid gateway response").url;
+    /// let shard = Shard::new_with(&gateway, &token, None, |s| s
+    ///     .encoding(GatewayEncoding::Etf)
+    ///     .identify(Identify::default().status(OnlineStatus::Idle)))
+    ///     .expect("Working shard");
+    /// ```
+    ///
+    /// [`ShardConfig`]: struct.ShardConfig.html
+    /// [`new`]: #method.new
+    pub fn new_with<F>(base_url: &str, token: &str, shard_info: Option<[u64; 2]>, f: F)
+        -> Result<(Shard, ReadyEvent, Receiver<WebSocketStream>)>
+        where F: FnOnce(ShardConfig) -> ShardConfig {
+        Shard::connect(base_url, token, shard_info, f(ShardConfig::default()))
+    }
+
+    fn connect(base_url: &str,
+               token: &str,
+               shard_info: Option<[u64; 2]>,
+               config: ShardConfig)
+               -> Result<(Shard, ReadyEvent, Receiver<WebSocketStream>)> {
+        let ShardConfig { encoding, identify, recorder } = config;
+
+        let url = prep::build_gateway_url(base_url, encoding)?;
 
         let response = WsClient::connect(url)?.send()?;
         response.validate()?;
 
         let (mut sender, mut receiver) = response.begin().split();
 
-        let identification = prep::identify(token, shard_info);
-        sender.send_json(&identification)?;
+        let identification = prep::identify(token, shard_info, &identify);
+        sender.send_encoded(encoding, &identification)?;
 
-        let heartbeat_interval = match receiver.recv_json(GatewayEvent::decode)? {
+        let heartbeat_interval = match prep::recv_event(&mut receiver,
+                                                        encoding,
+                                                        recorder.as_ref(),
+                                                        shard_info)? {
             GatewayEvent::Hello(interval) => interval,
             other => {
                 debug!("Unexpected event during shard start: {:?}", other);
@@ -144,21 +202,34 @@ impl Shard {
         ThreadBuilder::new()
             .name(thread_name)
             .spawn(move || {
-                prep::keepalive(heartbeat_interval, heartbeat_clone, sender, &rx)
+                prep::keepalive(heartbeat_interval, encoding, heartbeat_clone, sender, &rx)
             })?;
 
-        // Parse READY
-        let event = receiver.recv_json(GatewayEvent::decode)?;
-        let (ready, sequence) = prep::parse_ready(event,
-                                                  &tx,
-                                                  &mut receiver,
-                                                  identification)?;
+        // Parse READY, keeping its payload for the client's raw event handlers.
+        let mut ready_payload = None;
+        let event = prep::recv_dispatch(&mut receiver,
+                                        encoding,
+                                        recorder.as_ref(),
+                                        shard_info,
+                                        &mut ready_payload)?;
+        let (ready, sequence) = prep::parse_ready(event, &tx, identification, || {
+            prep::recv_dispatch(&mut receiver,
+                                encoding,
+                                recorder.as_ref(),
+                                shard_info,
+                                &mut ready_payload)
+        })?;
 
         Ok((feature_voice! {{
             Shard {
-                current_presence: (None, OnlineStatus::Online, false),
+                current_presence: (identify.game.clone(), identify.status, identify.afk),
+                encoding: encoding,
                 heartbeat_instants: (heartbeat_sent, None),
+                identify: identify,
                 keepalive_channel: tx.clone(),
+                ready_payload: ready_payload,
+                recorder: recorder,
+                refresh_gateway_url: true,
                 seq: sequence,
                 token: token.to_owned(),
                 session_id: Some(ready.ready.session_id.clone()),
@@ -168,9 +239,14 @@ impl Shard {
             }
         } else {
             Shard {
-                current_presence: (None, OnlineStatus::Online, false),
+                current_presence: (identify.game.clone(), identify.status, identify.afk),
+                encoding: encoding,
                 heartbeat_instants: (heartbeat_sent, None),
+                identify: identify,
                 keepalive_channel: tx.clone(),
+                ready_payload: ready_payload,
+                recorder: recorder,
+                refresh_gateway_url: true,
                 seq: sequence,
                 token: token.to_owned(),
                 session_id: Some(ready.ready.session_id.clone()),
@@ -180,6 +256,51 @@ impl Shard {
         }}, ready, receiver))
     }
 
+    /// Creates a shard which is not connected to the gateway, for dispatching
+    /// events from a recording.
+    ///
+    /// Messages sent over the shard - such as presence updates - are
+    /// discarded.
+    #[doc(hidden)]
+    pub fn offline(shard_info: Option<[u64; 2]>) -> Shard {
+        let (tx, _) = mpsc::channel();
+
+        feature_voice! {{
+            Shard {
+                current_presence: (None, OnlineStatus::Online, false),
+                encoding: GatewayEncoding::default(),
+                heartbeat_instants: (Arc::new(Mutex::new(Instant::now())), None),
+                identify: Identify::default(),
+                keepalive_channel: tx.clone(),
+                ready_payload: None,
+                recorder: None,
+                refresh_gateway_url: false,
+                seq: 0,
+                token: String::new(),
+                session_id: None,
+                shard_info: shard_info,
+                ws_url: String::new(),
+                manager: VoiceManager::new(tx, UserId(0)),
+            }
+        } else {
+            Shard {
+                current_presence: (None, OnlineStatus::Online, false),
+                encoding: GatewayEncoding::default(),
+                heartbeat_instants: (Arc::new(Mutex::new(Instant::now())), None),
+                identify: Identify::default(),
+                keepalive_channel: tx,
+                ready_payload: None,
+                recorder: None,
+                refresh_gateway_url: false,
+                seq: 0,
+                token: String::new(),
+                session_id: None,
+                shard_info: shard_info,
+                ws_url: String::new(),
+            }
+        }}
+    }
+
     /// Retrieves a copy of the current shard information.
     ///
     /// The first element is the _current_ shard - 0-indexed - while the second
@@ -191,6 +312,26 @@ impl Shard {
         self.shard_info
     }
 
+    /// Sets whether the gateway URL is retrieved from the REST API again when
+    /// the shard reconnects. This defaults to `true`.
+    #[doc(hidden)]
+    pub fn set_refresh_gateway_url(&mut self, refresh_gateway_url: bool) {
+        self.refresh_gateway_url = refresh_gateway_url;
+    }
+
+    /// Takes the payload of the READY - or RESUMED - that was received when
+    /// the shard last connected, if it has not already been taken.
+    #[doc(hidden)]
+    pub fn take_ready_payload(&mut self) -> Option<Value> {
+        self.ready_payload.take()
+    }
+
+    /// Retrieves the encoding that payloads are sent and received in over the
+    /// shard's connection.
+    pub fn encoding(&self) -> GatewayEncoding {
+        self.encoding
+    }
+
     /// Sets whether the current user is afk. This helps Discord determine where
     /// to send notifications.
     ///
@@ -336,7 +477,9 @@ impl Shard {
                 self.seq = 0;
                 self.session_id = None;
 
-                let identification = prep::identify(&self.token, self.shard_info);
+                let identification = prep::identify(&self.token,
+                                                    self.shard_info,
+                                                    &self.current_identify());
                 let status = GatewayStatus::SendMessage(identification);
                 let _ = self.keepalive_channel.send(status);
 
@@ -509,6 +652,57 @@ impl Shard {
         let _ = self.keepalive_channel.send(GatewayStatus::SendMessage(msg));
     }
 
+    /// Requests members of one or multiple [`Guild`]s, returning a handle
+    /// which completes once every chunk of members has been received.
+    ///
+    /// Unlike [`chunk_guilds`], this can request specific members by their
+    /// user Ids. Refer to the documentation for [`RequestGuildMembers`] for
+    /// more information.
+    ///
+    /// If the `cache` feature is enabled, the members will have been inserted
+    /// into the [`Cache`] by the time that the handle completes.
+    ///
+    /// # Examples
+    ///
+    /// Request two members of a guild, waiting until they have been received:
+    ///
+    /// ```rust,ignore
+    /// use serenity::model::{GuildId, UserId};
+    ///
+    /// let user_ids = [UserId(114941315417899012), UserId(77812253511913472)];
+    ///
+    /// let chunks = shard.request_guild_members(&[GuildId(81384788765712384)],
+    ///                                          |r| r.user_ids(&user_ids));
+    /// chunks.wait();
+    /// ```
+    ///
+    /// [`Cache`]: ../../ext/cache/struct.Cache.html
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`RequestGuildMembers`]: ../../utils/builder/struct.RequestGuildMembers.html
+    /// [`chunk_guilds`]: #method.chunk_guilds
+    pub fn request_guild_members<F>(&self, guild_ids: &[GuildId], f: F) -> MemberChunks
+        where F: FnOnce(RequestGuildMembers) -> RequestGuildMembers {
+        let chunks = MemberChunks::new(guild_ids);
+        let mut map = f(RequestGuildMembers::default()).0;
+
+        if !map.contains_key("user_ids") {
+            map.entry("query".to_owned()).or_insert_with(|| Value::String(String::new()));
+        }
+
+        map.entry("limit".to_owned()).or_insert_with(|| Value::Number(Number::from(0)));
+        map.insert("guild_id".to_owned(), json!(guild_ids.iter().map(|x| x.0).collect::<Vec<u64>>()));
+        map.insert("nonce".to_owned(), Value::String(chunks.nonce().to_owned()));
+
+        let msg = json!({
+            "op": OpCode::GetGuildMembers.num(),
+            "d": map,
+        });
+
+        let _ = self.keepalive_channel.send(GatewayStatus::SendMessage(msg));
+
+        chunks
+    }
+
     /// Calculates the number of guilds that the shard is responsible for.
     ///
     /// If sharding is not being used (i.e. 1 shard), then the total number of
@@ -553,23 +747,48 @@ impl Shard {
         }
     }
 
+    /// The identify settings to use when re-identifying, carrying over the
+    /// presence that is currently set rather than the initial one.
+    fn current_identify(&self) -> Identify {
+        let (ref game, status, afk) = self.current_presence;
+
+        Identify {
+            afk: afk,
+            game: game.clone(),
+            status: status,
+            ..self.identify.clone()
+        }
+    }
+
     fn reconnect(&mut self, mut receiver: &mut Receiver<WebSocketStream>)
         -> Result<(Event, Receiver<WebSocketStream>)> {
         info!("Attempting to reconnect");
 
         // Take a few attempts at reconnecting.
         for i in 1u64..11u64 {
-            let gateway_url = rest::get_gateway()?.url;
-
-            let shard = Shard::new(&gateway_url,
-                                   &self.token,
-                                   self.shard_info);
+            let gateway_url = if self.refresh_gateway_url {
+                rest::get_gateway()?.url
+            } else {
+                self.ws_url.clone()
+            };
+
+            let shard = Shard::connect(&gateway_url,
+                                       &self.token,
+                                       self.shard_info,
+                                       ShardConfig {
+                                           encoding: self.encoding,
+                                           identify: self.current_identify(),
+                                           recorder: self.recorder.clone(),
+                                       });
 
             if let Ok((shard, ready, receiver_new)) = shard {
                 let _ = Shard::shutdown(&mut receiver);
 
+                let refresh_gateway_url = self.refresh_gateway_url;
+
                 mem::replace(self, shard);
                 self.session_id = Some(ready.ready.session_id.clone());
+                self.refresh_gateway_url = refresh_gateway_url;
 
                 return Ok((Event::Ready(ready), receiver_new));
             }
@@ -595,14 +814,14 @@ impl Shard {
         };
 
         let _ = receiver.shutdown_all();
-        let url = prep::build_gateway_url(&self.ws_url)?;
+        let url = prep::build_gateway_url(&self.ws_url, self.encoding)?;
 
         let response = WsClient::connect(url)?.send()?;
         response.validate()?;
 
         let (mut sender, mut receiver) = response.begin().split();
 
-        sender.send_json(&json!({
+        sender.send_encoded(self.encoding, &json!({
             "op": OpCode::Resume.num(),
             "d": {
                 "session_id": session_id,
@@ -615,8 +834,14 @@ impl Shard {
         // https://github.com/rust-lang/rfcs/issues/961
         let ev;
 
+        // Keep the payload of the dispatch received, for the client's raw event
+        // handlers.
         loop {
-            match receiver.recv_json(GatewayEvent::decode)? {
+            match prep::recv_dispatch(&mut receiver,
+                                      self.encoding,
+                                      self.recorder.as_ref(),
+                                      self.shard_info,
+                                      &mut self.ready_payload)? {
                 GatewayEvent::Dispatch(seq, event) => {
                     match event {
                         Event::Ready(ref ready) => {
@@ -635,7 +860,11 @@ impl Shard {
                     let _ = self.keepalive_channel.send(GatewayStatus::Interval(i));
                 }
                 GatewayEvent::InvalidateSession => {
-                    sender.send_json(&prep::identify(&self.token, self.shard_info))?;
+                    let identification = prep::identify(&self.token,
+                                                        self.shard_info,
+                                                        &self.current_identify());
+
+                    sender.send_encoded(self.encoding, &identification)?;
                 },
                 other => {
                     debug!("Unexpected event: {:?}", other);
@@ -660,9 +889,7 @@ impl Shard {
                 "afk": afk,
                 "since": now,
                 "status": status.name(),
-                "game": game.as_ref().map(|x| json!({
-                    "name": x.name,
-                })),
+                "game": game.as_ref().map(prep::game),
             },
         });
 
@@ -680,3 +907,58 @@ impl Shard {
         }
     }
 }
+
+/// The settings of how a [`Shard`] connects to the gateway, passed to
+/// [`Shard::new_with`].
+///
+/// [`Shard`]: struct.Shard.html
+/// [`Shard::new_with`]: struct.Shard.html#method.new_with
+#[derive(Clone)]
+pub struct ShardConfig {
+    encoding: GatewayEncoding,
+    identify: Identify,
+    recorder: Option<Recorder>,
+}
+
+impl ShardConfig {
+    /// The encoding that payloads are sent and received in.
+    ///
+    /// Defaults to [`GatewayEncoding::Json`].
+    ///
+    /// [`GatewayEncoding::Json`]: enum.GatewayEncoding.html#variant.Json
+    pub fn encoding(mut self, encoding: GatewayEncoding) -> Self {
+        self.encoding = encoding;
+
+        self
+    }
+
+    /// The settings that the shard identifies - and comes online - with.
+    ///
+    /// Defaults to [`Identify::default`].
+    ///
+    /// [`Identify::default`]: struct.Identify.html#method.default
+    pub fn identify(mut self, identify: Identify) -> Self {
+        self.identify = identify;
+
+        self
+    }
+
+    /// A recorder which every payload that the shard receives is recorded to.
+    ///
+    /// Defaults to recording nothing.
+    pub fn recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+
+        self
+    }
+}
+
+impl Default for ShardConfig {
+    fn default() -> ShardConfig {
+        ShardConfig {
+            encoding: GatewayEncoding::Json,
+            identify: Identify::default(),
+            recorder: None,
+        }
+    }
+}
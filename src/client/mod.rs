@@ -23,25 +23,42 @@
 pub mod gateway;
 pub mod rest;
 
+mod chunk;
+mod collector;
 mod context;
 mod dispatch;
 mod error;
+mod event_handler;
 mod event_store;
+mod events;
+mod pool;
+mod ready;
 
+pub use self::chunk::MemberChunks;
 pub use self::context::Context;
 pub use self::error::Error as ClientError;
+pub use self::event_handler::EventHandler;
+pub use self::event_store::HandlerId;
+pub use self::events::{Events, EventsConfig, ShardEvent};
+pub use self::pool::{Backpressure, DispatchOrdering, PoolConfig, WorkerPool};
 
-use self::dispatch::dispatch;
-use self::event_store::EventStore;
-use self::gateway::Shard;
+#[doc(hidden)]
+pub use self::collector::{await_reactions, message_received, reaction_added};
+
+use self::dispatch::{dispatch, shard_failed};
+use self::event_store::{Callback, EventStore};
+use self::gateway::{GatewayEncoding, Identify, Recorder, Recording, Shard, ShardConfig};
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex, RwLock};
-use std::time::Duration;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use std::{mem, thread};
 use typemap::ShareMap;
 use websocket::client::Receiver;
 use websocket::result::WebSocketError;
 use websocket::stream::WebSocketStream;
+use ::constants::OpCode;
 use ::internal::prelude::*;
 use ::internal::ws_impl::ReceiverExt;
 use ::model::event::*;
@@ -51,7 +68,7 @@ use ::model::*;
 use ::ext::framework::Framework;
 
 #[cfg(feature="cache")]
-use ::ext::cache::Cache;
+use ::ext::cache::{self, Cache, CacheConfig, CacheSink, MessageCacheConfig};
 
 #[cfg(feature="cache")]
 lazy_static! {
@@ -106,6 +123,14 @@ lazy_static! {
 /// Note that you do not need to manually handle events, as they are handled
 /// internally and then dispatched to your event handlers.
 ///
+/// Any number of handlers may be attached to the same event, and they are
+/// called in the order in which they were attached. Each `on_*` method returns
+/// a [`HandlerId`], which can be passed to [`remove_handler`] to detach the
+/// handler again.
+///
+/// Alternatively, handlers can be implemented as a struct via the
+/// [`EventHandler`] trait, and attached with [`add_handler`].
+///
 /// # Examples
 ///
 /// Creating a Client instance and adding a handler on every message
@@ -126,7 +151,11 @@ lazy_static! {
 /// ```
 ///
 /// [`Shard`]: gateway/struct.Shard.html
+/// [`EventHandler`]: trait.EventHandler.html
+/// [`HandlerId`]: struct.HandlerId.html
+/// [`add_handler`]: #method.add_handler
 /// [`on_message`]: #method.on_message
+/// [`remove_handler`]: #method.remove_handler
 /// [`Event::MessageCreate`]: ../model/event/enum.Event.html#variant.MessageCreate
 /// [sharding docs]: gateway/index.html#sharding
 pub struct Client {
@@ -154,6 +183,13 @@ pub struct Client {
     /// [`Event::Ready`]: ../model/event/enum.Event.html#variant.Ready
     /// [`on_ready`]: #method.on_ready
     event_store: Arc<RwLock<EventStore>>,
+    encoding: GatewayEncoding,
+    identify: Identify,
+    gateway_url: Option<String>,
+    keep_raw_events: bool,
+    pool: Option<WorkerPool>,
+    pool_config: PoolConfig,
+    recorder: Option<Recorder>,
     #[cfg(feature="framework")]
     framework: Arc<Mutex<Framework>>,
     token: String,
@@ -199,6 +235,293 @@ impl Client {
         self.framework = Arc::new(Mutex::new(f(Framework::default())));
     }
 
+    /// Configures the pool of worker threads which event handlers and framework
+    /// commands are run on.
+    ///
+    /// Rather than spawning a thread per event, events are queued to a fixed
+    /// number of workers. The size of the pool, the length of each worker's
+    /// queue, and what to do when a queue is full can all be configured, as
+    /// well as whether events for the same channel or guild must be handled in
+    /// the order in which they were received.
+    ///
+    /// Refer to [`PoolConfig`] for the defaults.
+    ///
+    /// **Note**: A handler which blocks - such as by sleeping - occupies its
+    /// worker for the duration. When ordering is enabled, this also delays all
+    /// events assigned to the same worker.
+    ///
+    /// # Examples
+    ///
+    /// Use 4 workers, dropping events when they can not keep up, and keeping
+    /// the events of each channel in order:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::{Backpressure, DispatchOrdering};
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// client.with_pool(|p| p
+    ///     .size(4)
+    ///     .queue_len(1000)
+    ///     .backpressure(Backpressure::Drop)
+    ///     .ordering(DispatchOrdering::PerChannel));
+    /// ```
+    ///
+    /// [`PoolConfig`]: struct.PoolConfig.html
+    pub fn with_pool<F>(&mut self, f: F)
+        where F: FnOnce(PoolConfig) -> PoolConfig {
+        self.pool_config = f(PoolConfig::default());
+        self.pool = None;
+    }
+
+    /// Sets the encoding that the client's shards will use for payloads sent
+    /// and received over the gateway. This defaults to [`GatewayEncoding::Json`].
+    ///
+    /// Events are decoded into the same [`Event`]s regardless of the encoding.
+    ///
+    /// # Examples
+    ///
+    /// Use the Erlang External Term Format, which results in smaller payloads:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::gateway::GatewayEncoding;
+    /// use serenity::Client;
+    /// use std::env;
+    ///
+    /// let token = env::var("DISCORD_BOT_TOKEN").unwrap();
+    /// let mut client = Client::login(&token);
+    ///
+    /// client.with_encoding(GatewayEncoding::Etf);
+    /// ```
+    ///
+    /// [`Event`]: ../model/event/enum.Event.html
+    /// [`GatewayEncoding::Json`]: gateway/enum.GatewayEncoding.html#variant.Json
+    pub fn with_encoding(&mut self, encoding: GatewayEncoding) {
+        self.encoding = encoding;
+    }
+
+    /// Configures the settings that the client's shards identify with, such
+    /// as the presence that the current user initially comes online with.
+    ///
+    /// These settings apply to every shard, and are re-used whenever a shard
+    /// needs to re-identify.
+    ///
+    /// Refer to the documentation for [`Identify`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Come online playing a game, rather than setting it once the client has
+    /// started:
+    ///
+    /// ```rust,no_run
+    /// use serenity::model::Game;
+    /// use serenity::Client;
+    /// use std::env;
+    ///
+    /// let token = env::var("DISCORD_BOT_TOKEN").unwrap();
+    /// let mut client = Client::login(&token);
+    ///
+    /// client.with_identify(|i| i.game(Game::playing("~help")));
+    /// ```
+    ///
+    /// [`Identify`]: gateway/struct.Identify.html
+    pub fn with_identify<F>(&mut self, f: F)
+        where F: FnOnce(Identify) -> Identify {
+        self.identify = f(Identify::default());
+    }
+
+    /// Sets the URL of the gateway that the client's shards connect to,
+    /// rather than retrieving it from the REST API.
+    ///
+    /// This is primarily useful for connecting to a `FakeGateway` - enabled
+    /// by the `fake-gateway` feature - in tests. The URL will not be refreshed
+    /// from the REST API when shards fail to connect or reconnect.
+    ///
+    /// **Note**: [`start_autosharded`] still retrieves the recommended number
+    /// of shards from the REST API.
+    ///
+    /// # Examples
+    ///
+    /// Connect to a gateway running locally:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("fake token");
+    /// client.with_gateway_url("ws://127.0.0.1:8080");
+    /// ```
+    ///
+    /// [`start_autosharded`]: #method.start_autosharded
+    pub fn with_gateway_url(&mut self, url: &str) {
+        self.gateway_url = Some(url.to_owned());
+    }
+
+    /// Sets whether the raw payload of each dispatch should be kept alongside
+    /// the event decoded from it. This defaults to `false`.
+    ///
+    /// When enabled, the payload is available to event handlers via
+    /// [`Context::raw_event`], which allows using fields that the models do
+    /// not yet support.
+    ///
+    /// # Examples
+    ///
+    /// Read a field from a message's payload that is not part of [`Message`]:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    /// use std::env;
+    ///
+    /// let token = env::var("DISCORD_BOT_TOKEN").unwrap();
+    /// let mut client = Client::login(&token);
+    ///
+    /// client.keep_raw_events(true);
+    ///
+    /// client.on_message(|context, _message| {
+    ///     if let Some(ref raw) = context.raw_event {
+    ///         println!("Message flags: {:?}", raw["d"]["flags"]);
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Context::raw_event`]: struct.Context.html#structfield.raw_event
+    /// [`Message`]: ../model/struct.Message.html
+    pub fn keep_raw_events(&mut self, keep_raw_events: bool) {
+        self.keep_raw_events = keep_raw_events;
+    }
+
+    /// Records every payload received by the client's shards to the file at
+    /// the given path, truncating it if it already exists.
+    ///
+    /// The recording can later be fed back through the client's event
+    /// handlers, cache, and framework with [`replay`].
+    ///
+    /// Refer to the documentation for [`Recorder`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Record a session to attach to a bug report:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    /// use std::env;
+    ///
+    /// let token = env::var("DISCORD_BOT_TOKEN").unwrap();
+    /// let mut client = Client::login(&token);
+    ///
+    /// client.record_to("session.jsonl").expect("Error creating recording");
+    /// ```
+    ///
+    /// [`Recorder`]: gateway/struct.Recorder.html
+    /// [`replay`]: #method.replay
+    pub fn record_to<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.recorder = Some(Recorder::create(path)?);
+
+        Ok(())
+    }
+
+    /// Replays a recording made via [`record_to`], without connecting to the
+    /// gateway.
+    ///
+    /// Each recorded dispatch is decoded and passed through the same path as
+    /// events received live: the [`Cache`] is updated, and the registered
+    /// event handlers and framework are called. Events are dispatched to an
+    /// offline shard for each shard in the recording; messages sent over these
+    /// shards are discarded.
+    ///
+    /// If a `speed` is given, then the original timing of the events is
+    /// preserved, divided by the speed: `1.0` replays in real time, while
+    /// `10.0` replays ten times faster. Otherwise, events are dispatched as
+    /// quickly as possible.
+    ///
+    /// This returns once every event has been dispatched. As event handlers
+    /// are run on the client's [`WorkerPool`], they may still be queued or
+    /// running at this point.
+    ///
+    /// # Examples
+    ///
+    /// Replay a recording at five times the original speed:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("");
+    ///
+    /// client.on_message(|_context, message| {
+    ///     println!("{}: {}", message.author.name, message.content);
+    /// });
+    ///
+    /// client.replay("session.jsonl", Some(5.0)).expect("Error replaying");
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`record_to`]: #method.record_to
+    /// [`WorkerPool`]: struct.WorkerPool.html
+    pub fn replay<P: AsRef<Path>>(&mut self, path: P, speed: Option<f64>) -> Result<()> {
+        let mut shards = HashMap::new();
+        let mut start: Option<(u64, Instant)> = None;
+        let pool = self.pool();
+
+        for recorded in Recording::open(path)? {
+            let recorded = recorded?;
+
+            if let Some(speed) = speed {
+                match start {
+                    Some((first, started)) => {
+                        let offset = recorded.time.saturating_sub(first) as f64 / speed;
+                        let target = started + Duration::from_millis(offset as u64);
+                        let now = Instant::now();
+
+                        if target > now {
+                            thread::sleep(target - now);
+                        }
+                    },
+                    None => start = Some((recorded.time, Instant::now())),
+                }
+            }
+
+            let shard_info = recorded.shard_info;
+            let shard = shards.entry(shard_info)
+                .or_insert_with(|| Arc::new(Mutex::new(Shard::offline(shard_info))))
+                .clone();
+
+            let (event, raw) = decode_event(recorded.payload,
+                                            &shard,
+                                            &self.data,
+                                            &self.event_store,
+                                            self.keep_raw_events);
+
+            let event = match event {
+                Ok(GatewayEvent::Dispatch(_, event)) => event,
+                Ok(_) => continue,
+                Err(why) => {
+                    warn!("Failed to decode recorded payload: {:?}", why);
+
+                    continue;
+                },
+            };
+
+            feature_framework! {{
+                dispatch(event,
+                         &shard,
+                         &self.framework,
+                         &self.data,
+                         &self.event_store,
+                         &pool,
+                         raw);
+            } else {
+                dispatch(event,
+                         &shard,
+                         &self.data,
+                         &self.event_store,
+                         &pool,
+                         raw);
+            }}
+        }
+
+        Ok(())
+    }
+
     /// Establish the connection and start listening for events.
     ///
     /// This will start receiving events in a loop and start dispatching the
@@ -213,7 +536,9 @@ impl Client {
     ///
     /// [gateway docs]: gateway/index.html#sharding
     pub fn start(&mut self) -> Result<()> {
-        self.start_connection(None, rest::get_gateway()?.url)
+        let url = self.gateway_url()?;
+
+        self.start_connection(None, url)
     }
 
     /// Establish the connection(s) and start listening for events.
@@ -234,7 +559,10 @@ impl Client {
 
         let x = res.shards as u64 - 1;
         let y = res.shards as u64;
-        let url = mem::replace(&mut res.url, String::default());
+        let url = match self.gateway_url {
+            Some(ref url) => url.clone(),
+            None => mem::replace(&mut res.url, String::default()),
+        };
 
         drop(res);
 
@@ -255,7 +583,9 @@ impl Client {
     ///
     /// [gateway docs]: gateway/index.html#sharding
     pub fn start_shard(&mut self, shard: u64, shards: u64) -> Result<()> {
-        self.start_connection(Some([shard, shard, shards]), rest::get_gateway()?.url)
+        let url = self.gateway_url()?;
+
+        self.start_connection(Some([shard, shard, shards]), url)
     }
 
     /// Establish sharded connections and start listening for events.
@@ -274,7 +604,9 @@ impl Client {
     /// [`start_shard_range`]: #method.start_shards
     /// [Gateway docs]: gateway/index.html#sharding
     pub fn start_shards(&mut self, total_shards: u64) -> Result<()> {
-        self.start_connection(Some([0, total_shards - 1, total_shards]), rest::get_gateway()?.url)
+        let url = self.gateway_url()?;
+
+        self.start_connection(Some([0, total_shards - 1, total_shards]), url)
     }
 
     /// Establish a range of sharded connections and start listening for events.
@@ -308,47 +640,91 @@ impl Client {
     /// [`start_shards`]: #method.start_shards
     /// [Gateway docs]: gateway/index.html#sharding
     pub fn start_shard_range(&mut self, range: [u64; 2], total_shards: u64) -> Result<()> {
-        self.start_connection(Some([range[0], range[1], total_shards]), rest::get_gateway()?.url)
+        let url = self.gateway_url()?;
+
+        self.start_connection(Some([range[0], range[1], total_shards]), url)
+    }
+
+    /// Attaches a handler for when the guilds of every shard started by the
+    /// client have streamed in after their [`Ready`], meaning that the
+    /// [`Cache`] - if enabled - contains every guild the bot is in.
+    ///
+    /// This is dispatched once each shard has become ready, as with
+    /// [`on_shard_cache_ready`], and is given the guilds of every shard which
+    /// stayed unavailable. It is dispatched again if a shard receives a new
+    /// [`Ready`] after re-identifying.
+    ///
+    /// # Examples
+    ///
+    /// Count the guilds in the cache once they have all been received:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::Client;
+    /// #
+    /// # let mut client = Client::login("");
+    /// #
+    /// use serenity::client::CACHE;
+    ///
+    /// client.on_cache_ready(|_context, unavailable| {
+    ///     let guilds = CACHE.read().unwrap().guilds.len();
+    ///
+    ///     println!("In {} guilds, {} unavailable", guilds, unavailable.len());
+    /// });
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+    /// [`on_shard_cache_ready`]: #method.on_shard_cache_ready
+    pub fn on_cache_ready<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, Vec<GuildId>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_cache_ready
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`ChannelCreate`] is received.
     ///
     /// [`ChannelCreate`]: ../model/event/enum.Event.html#variant.ChannelCreate
-    pub fn on_channel_create<F>(&mut self, handler: F)
+    pub fn on_channel_create<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Channel) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_create = Some(Arc::new(handler));
+            .on_channel_create
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`ChannelDelete`] is received.
     ///
     /// [`ChannelDelete`]: ../model/event/enum.Event.html#variant.ChannelDelete
-    pub fn on_channel_delete<F>(&mut self, handler: F)
+    pub fn on_channel_delete<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Channel) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_delete = Some(Arc::new(handler));
+            .on_channel_delete
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`ChannelPinsUpdate`] is received.
     ///
     /// [`ChannelPinsUpdate`]: ../model/event/enum.Event.html#variant.ChannelPinsUpdate
-    pub fn on_channel_pins_update<F>(&mut self, handler: F)
+    pub fn on_channel_pins_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, ChannelPinsUpdateEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_pins_update = Some(Arc::new(handler));
+            .on_channel_pins_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildCreate`] is received.
     ///
     /// [`GuildCreate`]: ../model/event/enum.Event.html#variant.GuildCreate
-    pub fn on_guild_create<F>(&mut self, handler: F)
+    pub fn on_guild_create<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Guild) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_create = Some(Arc::new(handler));
+            .on_guild_create
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildEmojisUpdate`] is received.
@@ -356,81 +732,89 @@ impl Client {
     /// The `HashMap` of emojis is the new full list of emojis.
     ///
     /// [`GuildEmojisUpdate`]: ../model/event/enum.Event.html#variant.GuildEmojisUpdate
-    pub fn on_guild_emojis_update<F>(&mut self, handler: F)
+    pub fn on_guild_emojis_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, HashMap<EmojiId, Emoji>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_emojis_update = Some(Arc::new(handler));
+            .on_guild_emojis_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildIntegrationsUpdate`] is received.
     ///
     /// [`GuildIntegrationsUpdate`]: ../model/event/enum.Event.html#variant.GuildIntegrationsUpdate
-    pub fn on_guild_integrations_update<F>(&mut self, handler: F)
+    pub fn on_guild_integrations_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_integrations_update = Some(Arc::new(handler));
+            .on_guild_integrations_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMemberAdd`] is received.
     ///
     /// [`GuildMemberAdd`]: ../model/event/enum.Event.html#variant.GuildMemberAdd
-    pub fn on_guild_member_add<F>(&mut self, handler: F)
+    pub fn on_guild_member_add<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, Member) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_member_addition = Some(Arc::new(handler));
+            .on_guild_member_addition
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMembersChunk`] is received.
     ///
     /// [`GuildMembersChunk`]: ../model/event/enum.Event.html#variant.GuildMembersChunk
-    pub fn on_guild_members_chunk<F>(&mut self, handler: F)
+    pub fn on_guild_members_chunk<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, HashMap<UserId, Member>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_members_chunk = Some(Arc::new(handler));
+            .on_guild_members_chunk
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildRoleCreate`] is received.
     ///
     /// [`GuildRoleCreate`]: ../model/event/enum.Event.html#variant.GuildRoleCreate
-    pub fn on_guild_role_create<F>(&mut self, handler: F)
+    pub fn on_guild_role_create<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, Role) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_role_create = Some(Arc::new(handler));
+            .on_guild_role_create
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildUnavailable`] is received.
     ///
     /// [`GuildUnavailable`]: ../model/event/enum.Event.html#variant.GuildUnavailable
-    pub fn on_guild_unavailable<F>(&mut self, handler: F)
+    pub fn on_guild_unavailable<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_unavailable = Some(Arc::new(handler));
+            .on_guild_unavailable
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildBan`] is received.
     ///
     /// [`GuildBan`]: ../model/event/enum.Event.html#variant.GuildBan
-    pub fn on_member_ban<F>(&mut self, handler: F)
+    pub fn on_member_ban<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, User) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_ban_addition = Some(Arc::new(handler));
+            .on_guild_ban_addition
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildUnban`] is received.
     ///
     /// [`GuildUnban`]: ../model/event/enum.Event.html#variant.GuildUnban
-    pub fn on_member_unban<F>(&mut self, handler: F)
+    pub fn on_member_unban<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, User) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_ban_removal = Some(Arc::new(handler));
+            .on_guild_ban_removal
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`MessageCreate`] is received.
@@ -452,92 +836,68 @@ impl Client {
     /// ```
     ///
     /// [`MessageCreate`]: ../model/event/enum.Event.html#variant.MessageCreate
-    pub fn on_message<F>(&mut self, handler: F)
+    pub fn on_message<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Message) + Send + Sync + 'static {
 
         self.event_store.write()
             .unwrap()
-            .on_message = Some(Arc::new(handler));
-    }
-
-    /// Attaches a handler for when a [`MessageDelete`] is received.
-    ///
-    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
-    pub fn on_message_delete<F>(&mut self, handler: F)
-        where F: Fn(Context, ChannelId, MessageId) + Send + Sync + 'static {
-        self.event_store.write()
-            .unwrap()
-            .on_message_delete = Some(Arc::new(handler));
-    }
-
-    /// Attaches a handler for when a [`MessageDeleteBulk`] is received.
-    ///
-    /// [`MessageDeleteBulk`]: ../model/event/enum.Event.html#variant.MessageDeleteBulk
-    pub fn on_message_delete_bulk<F>(&mut self, handler: F)
-        where F: Fn(Context, ChannelId, Vec<MessageId>) + Send + Sync + 'static {
-        self.event_store.write()
-            .unwrap()
-            .on_message_delete_bulk = Some(Arc::new(handler));
-    }
-
-    /// Attaches a handler for when a [`MessageUpdate`] is received.
-    ///
-    /// [`MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
-    pub fn on_message_update<F>(&mut self, handler: F)
-        where F: Fn(Context, MessageUpdateEvent) + Send + Sync + 'static {
-        self.event_store.write()
-            .unwrap()
-            .on_message_update = Some(Arc::new(handler));
+            .on_message
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`PresencesReplace`] is received.
     ///
     /// [`PresencesReplace`]: ../model/event/enum.Event.html#variant.PresencesReplace
-    pub fn on_presence_replace<F>(&mut self, handler: F)
+    pub fn on_presence_replace<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Vec<Presence>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_presence_replace = Some(Arc::new(handler));
+            .on_presence_replace
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`PresenceUpdate`] is received.
     ///
     /// [`PresenceUpdate`]: ../model/event/enum.Event.html#variant.PresenceUpdate
-    pub fn on_presence_update<F>(&mut self, handler: F)
+    pub fn on_presence_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, PresenceUpdateEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_presence_update = Some(Arc::new(handler));
+            .on_presence_update
+            .push(Arc::new(handler))
     }
 
     /// Attached a handler for when a [`ReactionAdd`] is received.
     ///
     /// [`ReactionAdd`]: ../model/event/enum.Event.html#variant.ReactionAdd
-    pub fn on_reaction_add<F>(&mut self, handler: F)
+    pub fn on_reaction_add<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Reaction) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_reaction_add = Some(Arc::new(handler));
+            .on_reaction_add
+            .push(Arc::new(handler))
     }
 
     /// Attached a handler for when a [`ReactionRemove`] is received.
     ///
     /// [`ReactionRemove`]: ../model/event/enum.Event.html#variant.ReactionRemove
-    pub fn on_reaction_remove<F>(&mut self, handler: F)
+    pub fn on_reaction_remove<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Reaction) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_reaction_remove = Some(Arc::new(handler));
+            .on_reaction_remove
+            .push(Arc::new(handler))
     }
 
     /// Attached a handler for when a [`ReactionRemoveAll`] is received.
     ///
     /// [`ReactionRemoveAll`]: ../model/event/enum.Event.html#variant.ReactionRemoveAll
-    pub fn on_reaction_remove_all<F>(&mut self, handler: F)
+    pub fn on_reaction_remove_all<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, ChannelId, MessageId) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_reaction_remove_all = Some(Arc::new(handler));
+            .on_reaction_remove_all
+            .push(Arc::new(handler))
     }
 
     /// Register an event to be called whenever a Ready event is received.
@@ -566,157 +926,448 @@ impl Client {
     ///
     /// [`CurrentUser`]: ../model/struct.CurrentUser.html
     /// [`Shard`]: gateway/struct.Shard.html
-    pub fn on_ready<F>(&mut self, handler: F)
+    pub fn on_ready<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Ready) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_ready = Some(Arc::new(handler));
+            .on_ready
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`ChannelRecipientAdd`] is received.
     ///
     /// [`ChannelRecipientAdd`]: ../model/event/enum.Event.html#variant.ChannelRecipientAdd
-    pub fn on_recipient_add<F>(&mut self, handler: F)
+    pub fn on_recipient_add<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, ChannelId, User) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_recipient_addition = Some(Arc::new(handler));
+            .on_channel_recipient_addition
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`ChannelRecipientRemove`] is received.
     ///
     /// [`ChannelRecipientRemove`]: ../model/event/enum.Event.html#variant.ChannelRecipientRemove
-    pub fn on_recipient_remove<F>(&mut self, handler: F)
+    pub fn on_recipient_remove<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, ChannelId, User) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_recipient_removal = Some(Arc::new(handler));
+            .on_channel_recipient_removal
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a shard has received every guild which was
+    /// unavailable in its [`Ready`], as [`GuildCreate`]s.
+    ///
+    /// Guilds which are instead received as [`GuildUnavailable`]s, or which
+    /// have not been received 30 seconds after the [`Ready`], are given as
+    /// having stayed unavailable.
+    ///
+    /// [`GuildCreate`]: ../model/event/enum.Event.html#variant.GuildCreate
+    /// [`GuildUnavailable`]: ../model/event/enum.Event.html#variant.GuildUnavailable
+    /// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+    pub fn on_shard_cache_ready<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, Vec<GuildId>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_shard_cache_ready
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`Resumed`] is received.
     ///
     /// [`Resumed`]: ../model/event/enum.Event.html#variant.Resumed
-    pub fn on_resume<F>(&mut self, handler: F)
+    pub fn on_resume<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, ResumedEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_resume = Some(Arc::new(handler));
+            .on_resume
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler which is called for every dispatch received over
+    /// the gateway, before it is decoded into an [`Event`].
+    ///
+    /// The handler receives the name of the dispatch - such as
+    /// `"MESSAGE_CREATE"` - its sequence number, and the full payload. This is
+    /// called even if the payload can not be decoded, and in addition to the
+    /// handler for the decoded event.
+    ///
+    /// The handler is called on the shard's thread, before any handlers of
+    /// the decoded event are called, and so it should return quickly. The
+    /// [`Ready`] received while a shard connects is decoded by the shard
+    /// itself, and so is passed to the handler afterwards, but still before
+    /// its handlers are called.
+    ///
+    /// [`Event`]: ../model/event/enum.Event.html
+    /// [`Ready`]: ../model/event/enum.Event.html#variant.Ready
+    pub fn on_raw_event<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, String, u64, &Value) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_raw_event
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`TypingStart`] is received.
     ///
     /// [`TypingStart`]: ../model/event/enum.Event.html#variant.TypingStart
-    pub fn on_typing_start<F>(&mut self, handler: F)
+    pub fn on_typing_start<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, TypingStartEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_typing_start = Some(Arc::new(handler));
+            .on_typing_start
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when an [`Unknown`] is received.
     ///
     /// [`Unknown`]: ../model/event/enum.Event.html#variant.Unknown
-    pub fn on_unknown<F>(&mut self, handler: F)
+    pub fn on_unknown<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, String, Value) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_unknown = Some(Arc::new(handler));
+            .on_unknown
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`VoiceServerUpdate`] is received.
     ///
     /// [`VoiceServerUpdate`]: ../model/event/enum.Event.html#variant.VoiceServerUpdate
-    pub fn on_voice_server_update<F>(&mut self, handler: F)
+    pub fn on_voice_server_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, VoiceServerUpdateEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_voice_server_update = Some(Arc::new(handler));
+            .on_voice_server_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`VoiceStateUpdate`] is received.
     ///
     /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
-    pub fn on_voice_state_update<F>(&mut self, handler: F)
+    pub fn on_voice_state_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Option<GuildId>, VoiceState) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_voice_state_update = Some(Arc::new(handler));
+            .on_voice_state_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`WebhookUpdate`] is received.
     ///
     /// [`WebhookUpdate`]: ../model/event/enum.Event.html#variant.WebhookUpdate
-    pub fn on_webhook_update<F>(&mut self, handler: F)
+    pub fn on_webhook_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, ChannelId) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_webhook_update = Some(Arc::new(handler));
+            .on_webhook_update
+            .push(Arc::new(handler))
     }
 
-    // Shard data layout is:
-    // 0: first shard number to initialize
-    // 1: shard number to initialize up to and including
-    // 2: total number of shards the bot is sharding for
-    //
-    // Not all shards need to be initialized in this process.
-    fn start_connection(&mut self, shard_data: Option<[u64; 3]>, url: String) -> Result<()> {
-        // Update the framework's current user if the feature is enabled.
-        //
-        // This also acts as a form of check to ensure the token is correct.
-        #[cfg(feature="framework")]
-        {
-            let user = rest::get_current_user()?;
-
-            self.framework.lock()
-                .unwrap()
-                .update_current_user(user.id, user.bot);
-        }
+    /// Attaches an implementation of [`EventHandler`], whose methods are called
+    /// as their respective events are received.
+    ///
+    /// Any number of implementations may be attached. The returned Id applies
+    /// to every method of the implementation, and can be passed to
+    /// [`remove_handler`] to detach all of them at once.
+    ///
+    /// Refer to the documentation for [`EventHandler`] for an example.
+    ///
+    /// [`EventHandler`]: trait.EventHandler.html
+    /// [`remove_handler`]: #method.remove_handler
+    pub fn add_handler<H: EventHandler + 'static>(&mut self, handler: H) -> HandlerId {
+        self.event_store.write().unwrap().add_handler(Arc::new(handler))
+    }
 
-        let gateway_url = Arc::new(Mutex::new(url));
+    /// Creates an iterator over the events received by the client's shards, as
+    /// an alternative to attaching event handlers.
+    ///
+    /// Shards are still started via one of the `start` methods, which continue
+    /// to handle reconnecting and updating the [`Cache`], so the client should
+    /// be started on another thread. Any number of iterators may be created,
+    /// and each receives every event. Handlers, middleware, and the
+    /// [`Framework`] continue to work alongside them.
+    ///
+    /// Refer to [`Events`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Count the messages received by each shard:
+    ///
+    /// ```rust,no_run
+    /// use serenity::model::event::Event;
+    /// use serenity::Client;
+    /// use std::collections::HashMap;
+    /// use std::thread;
+    ///
+    /// let mut client = Client::login("bot token here");
+    /// let events = client.events();
+    ///
+    /// thread::spawn(move || client.start_autosharded());
+    ///
+    /// let mut counts = HashMap::new();
+    ///
+    /// for (shard_info, event) in events {
+    ///     if let Event::MessageCreate(_) = event {
+    ///         *counts.entry(shard_info).or_insert(0) += 1;
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`Events`]: struct.Events.html
+    /// [`Framework`]: ../ext/framework/struct.Framework.html
+    pub fn events(&mut self) -> Events {
+        self.events_with(|e| e)
+    }
 
-        let shards_index = shard_data.map_or(0, |x| x[0]);
-        let shards_total = shard_data.map_or(1, |x| x[1] + 1);
+    /// Creates an iterator over the events received by the client's shards,
+    /// as with [`events`], configuring its buffer.
+    ///
+    /// # Examples
+    ///
+    /// Buffer up to 10000 events, waiting for them to be received rather than
+    /// dropping any:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::Backpressure;
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("bot token here");
+    /// let events = client.events_with(|e| e
+    ///     .backpressure(Backpressure::Block)
+    ///     .buffer(10000));
+    /// ```
+    ///
+    /// [`events`]: #method.events
+    pub fn events_with<F>(&mut self, f: F) -> Events
+        where F: FnOnce(EventsConfig) -> EventsConfig {
+        let (sender, events) = Events::new(f(EventsConfig::default()));
 
-        for shard_number in shards_index..shards_total {
-            let shard_info = shard_data.map(|s| [shard_number, s[2]]);
+        self.event_store.write()
+            .unwrap()
+            .events
+            .push(sender);
 
-            let boot = boot_shard(&BootInfo {
-                gateway_url: gateway_url.clone(),
-                shard_info: shard_info,
-                token: self.token.clone(),
-            });
+        events
+    }
 
-            match boot {
-                Ok((shard, ready, receiver)) => {
-                    #[cfg(feature="cache")]
-                    {
+    /// Adds a middleware which every received [`Event`] is passed through
+    /// before it is dispatched.
+    ///
+    /// A middleware is given the event along with the shard that received it.
+    /// It can pass the event on by returning it, replace it by returning a
+    /// different event, or drop it by returning `None`.
+    ///
+    /// Middleware are run in the order in which they were added, on the thread
+    /// of the shard which received the event, so events from a shard are seen
+    /// in the order they were received. Events are passed through the
+    /// middleware before they update the [`Cache`] and before they reach any
+    /// handlers, collectors, or the [`Framework`]; a dropped event has none of
+    /// these effects.
+    ///
+    /// **Note**: As middleware block the shard, they should be quick. Long
+    /// running work belongs in an event handler.
+    ///
+    /// The returned Id can be passed to [`remove_handler`] to remove the
+    /// middleware.
+    ///
+    /// # Examples
+    ///
+    /// Ignore all events for a blocked guild, and drop half of all presence
+    /// updates:
+    ///
+    /// ```rust,no_run
+    /// use serenity::model::event::Event;
+    /// use serenity::model::GuildId;
+    /// use serenity::Client;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// let blocked = GuildId(81384788765712384);
+    ///
+    /// client.add_middleware(move |_shard, event| {
+    ///     match event {
+    ///         Event::GuildCreate(ref e) if e.guild.id == blocked => None,
+    ///         Event::GuildMemberAdd(ref e) if e.guild_id == blocked => None,
+    ///         Event::PresenceUpdate(ref e) if e.guild_id == Some(blocked) => None,
+    ///         event => Some(event),
+    ///     }
+    /// });
+    ///
+    /// let presences = AtomicUsize::new(0);
+    ///
+    /// client.add_middleware(move |_shard, event| {
+    ///     match event {
+    ///         Event::PresenceUpdate(_) if presences.fetch_add(1, Ordering::Relaxed) % 2 == 0 => None,
+    ///         event => Some(event),
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`Event`]: ../model/event/enum.Event.html
+    /// [`Framework`]: ../ext/framework/struct.Framework.html
+    /// [`remove_handler`]: #method.remove_handler
+    pub fn add_middleware<F>(&mut self, middleware: F) -> HandlerId
+        where F: Fn(&Arc<Mutex<Shard>>, Event) -> Option<Event> + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .middleware
+            .push(Arc::new(middleware))
+    }
+
+    /// Removes an event handler or middleware which was previously added,
+    /// regardless of which event it was attached to.
+    ///
+    /// Returns whether a handler with the given Id was attached.
+    ///
+    /// # Examples
+    ///
+    /// Stop logging messages once the client has been told to be quiet:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// let logger = client.on_message(|_context, message| {
+    ///     println!("{}", message.content);
+    /// });
+    ///
+    /// // Later on:
+    /// client.remove_handler(logger);
+    /// ```
+    pub fn remove_handler(&mut self, id: HandlerId) -> bool {
+        self.event_store.write().unwrap().remove(id)
+    }
+
+    fn pool(&mut self) -> WorkerPool {
+        if self.pool.is_none() {
+            self.pool = Some(WorkerPool::new(self.pool_config.clone()));
+        }
+
+        self.pool.clone().unwrap()
+    }
+
+    fn gateway_url(&self) -> Result<String> {
+        match self.gateway_url {
+            Some(ref url) => Ok(url.clone()),
+            None => Ok(rest::get_gateway()?.url),
+        }
+    }
+
+    // Shard data layout is:
+    // 0: first shard number to initialize
+    // 1: shard number to initialize up to and including
+    // 2: total number of shards the bot is sharding for
+    //
+    // Not all shards need to be initialized in this process.
+    fn start_connection(&mut self, shard_data: Option<[u64; 3]>, url: String) -> Result<()> {
+        let pool = self.pool();
+
+        // Update the framework's current user if the feature is enabled.
+        //
+        // This also acts as a form of check to ensure the token is correct.
+        // When connecting to a custom gateway, the current user is instead
+        // taken from the first READY received.
+        #[cfg(feature="framework")]
+        {
+            if self.gateway_url.is_none() {
+                let user = rest::get_current_user()?;
+
+                self.framework.lock()
+                    .unwrap()
+                    .update_current_user(user.id, user.bot);
+            }
+        }
+
+        let gateway_url = Arc::new(Mutex::new(url));
+
+        let shards_index = shard_data.map_or(0, |x| x[0]);
+        let shards_total = shard_data.map_or(1, |x| x[1] + 1);
+
+        self.event_store.read()
+            .unwrap()
+            .ready
+            .lock()
+            .unwrap()
+            .expect_shards((shards_total - shards_index) as usize);
+
+        // A shard which started, for the context of the client's cache ready
+        // event if a later shard fails to start.
+        let mut started = None;
+
+        for shard_number in shards_index..shards_total {
+            let shard_info = shard_data.map(|s| [shard_number, s[2]]);
+
+            let boot = boot_shard(&BootInfo {
+                encoding: self.encoding,
+                gateway_url: gateway_url.clone(),
+                identify: self.identify.clone(),
+                recorder: self.recorder.clone(),
+                refresh_gateway_url: self.gateway_url.is_none(),
+                shard_info: shard_info,
+                token: self.token.clone(),
+            });
+
+            match boot {
+                Ok((shard, ready, receiver)) => {
+                    #[cfg(feature="cache")]
+                    {
                         CACHE.write()
                             .unwrap()
                             .update_with_ready(&ready);
                     }
 
+                    #[cfg(feature="framework")]
+                    {
+                        let user = &ready.ready.user;
+
+                        self.framework.lock()
+                            .unwrap()
+                            .update_current_user(user.id, user.bot);
+                    }
+
                     let shard = Arc::new(Mutex::new(shard));
+                    started = Some(shard.clone());
+
+                    let raw = dispatch_ready_raw(&shard,
+                                                 &self.data,
+                                                 &self.event_store,
+                                                 self.keep_raw_events);
 
                     feature_framework! {{
                         dispatch(Event::Ready(ready),
                                  &shard,
                                  &self.framework,
                                  &self.data,
-                                 &self.event_store);
+                                 &self.event_store,
+                                 &pool,
+                                 raw);
                     } else {
                         dispatch(Event::Ready(ready),
                                  &shard,
                                  &self.data,
-                                 &self.event_store);
+                                 &self.event_store,
+                                 &pool,
+                                 raw);
                     }}
 
                     let monitor_info = feature_framework! {{
                         MonitorInfo {
                             data: self.data.clone(),
+                            encoding: self.encoding,
                             event_store: self.event_store.clone(),
                             framework: self.framework.clone(),
                             gateway_url: gateway_url.clone(),
+                            identify: self.identify.clone(),
+                            keep_raw_events: self.keep_raw_events,
+                            pool: pool.clone(),
                             receiver: receiver,
+                            recorder: self.recorder.clone(),
+                            refresh_gateway_url: self.gateway_url.is_none(),
                             shard: shard,
                             shard_info: shard_info,
                             token: self.token.clone(),
@@ -724,9 +1375,15 @@ impl Client {
                     } else {
                         MonitorInfo {
                             data: self.data.clone(),
+                            encoding: self.encoding,
                             event_store: self.event_store.clone(),
                             gateway_url: gateway_url.clone(),
+                            identify: self.identify.clone(),
+                            keep_raw_events: self.keep_raw_events,
+                            pool: pool.clone(),
                             receiver: receiver,
+                            recorder: self.recorder.clone(),
+                            refresh_gateway_url: self.gateway_url.is_none(),
                             shard: shard,
                             shard_info: shard_info,
                             token: self.token.clone(),
@@ -737,7 +1394,11 @@ impl Client {
                         monitor_shard(monitor_info);
                     });
                 },
-                Err(why) => warn!("Error starting shard {:?}: {:?}", shard_info, why),
+                Err(why) => {
+                    warn!("Error starting shard {:?}: {:?}", shard_info, why);
+
+                    shard_failed(started.as_ref(), &self.data, &self.event_store, &pool);
+                },
             }
 
             // Wait 5 seconds between shard boots.
@@ -759,11 +1420,29 @@ impl Client {
     /// Optionally provides the version of the channel before the update.
     ///
     /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
-    pub fn on_channel_update<F>(&mut self, handler: F)
+    pub fn on_channel_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Option<Channel>, Channel) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_update = Some(Arc::new(handler));
+            .on_channel_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a guild channel's name changes.
+    ///
+    /// This is derived from a [`ChannelUpdate`], by comparing the channel's name
+    /// with the one in the [`Cache`]. The names before and after the update are
+    /// given as a [`Change`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`Change`]: ../model/struct.Change.html
+    /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
+    pub fn on_channel_rename<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, ChannelId, Change<String>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_channel_rename
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildDelete`] is received.
@@ -779,11 +1458,12 @@ impl Client {
     /// [`GuildDelete`]: ../model/event/enum.Event.html#variant.GuildDelete
     /// [`Role`]: ../model/struct.Role.html
     /// [`Cache`]: ../ext/cache/struct.Cache.html
-    pub fn on_guild_delete<F>(&mut self, handler: F)
+    pub fn on_guild_delete<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, PartialGuild, Option<Arc<RwLock<Guild>>>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_delete = Some(Arc::new(handler));
+            .on_guild_delete
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMemberRemove`] is received.
@@ -792,31 +1472,98 @@ impl Client {
     /// cache.
     ///
     /// [`GuildMemberRemove`]: ../model/event/enum.Event.html#variant.GuildMemberRemove
-    pub fn on_guild_member_remove<F>(&mut self, handler: F)
+    pub fn on_guild_member_remove<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, User, Option<Member>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_member_removal = Some(Arc::new(handler));
+            .on_guild_member_removal
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a member's nickname changes.
+    ///
+    /// This is derived from a [`GuildMemberUpdate`], by comparing the member with
+    /// the one in the [`Cache`], and is not dispatched if the member was not
+    /// cached before the update.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    pub fn on_guild_member_nickname_update<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, Member, Change<Option<String>>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_guild_member_nickname_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a role is given to a member.
+    ///
+    /// This is derived from a [`GuildMemberUpdate`], and is dispatched once for
+    /// each role which was not in the member's roles in the [`Cache`]. The
+    /// member is given as it is after the update.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    pub fn on_guild_member_role_add<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, Member, RoleId) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_guild_member_role_add
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a role is taken from a member.
+    ///
+    /// This is derived from a [`GuildMemberUpdate`], and is dispatched once for
+    /// each of the member's roles in the [`Cache`] which is no longer present.
+    /// The member is given as it is after the update.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
+    pub fn on_guild_member_role_remove<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, Member, RoleId) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_guild_member_role_remove
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMemberUpdate`] is received.
     ///
     /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
-    pub fn on_guild_member_update<F>(&mut self, handler: F)
+    pub fn on_guild_member_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Option<Member>, Member) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_member_update = Some(Arc::new(handler));
+            .on_guild_member_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildRoleDelete`] is received.
     ///
     /// [`GuildRoleDelete`]: ../model/event/enum.Event.html#variant.GuildRoleDelete
-    pub fn on_guild_role_delete<F>(&mut self, handler: F)
+    pub fn on_guild_role_delete<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, RoleId, Option<Role>) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_role_delete = Some(Arc::new(handler));
+            .on_guild_role_delete
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a role's name changes.
+    ///
+    /// This is derived from a [`GuildRoleUpdate`], by comparing the role's name
+    /// with the one in the [`Cache`]. The role is given as it is after the
+    /// update.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
+    pub fn on_guild_role_rename<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, Role, Change<String>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_guild_role_rename
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildRoleUpdate`] is received.
@@ -826,21 +1573,74 @@ impl Client {
     ///
     /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
     /// [`Cache`]: ../ext/cache/struct.Cache.html
-    pub fn on_guild_role_update<F>(&mut self, handler: F)
+    pub fn on_guild_role_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, Option<Role>, Role) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_role_update = Some(Arc::new(handler));
+            .on_guild_role_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildUpdate`] is received.
     ///
     /// [`GuildUpdate`]: ../model/event/enum.Event.html#variant.GuildUpdate
-    pub fn on_guild_update<F>(&mut self, handler: F)
+    pub fn on_guild_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Option<Arc<RwLock<Guild>>>, PartialGuild) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_update = Some(Arc::new(handler));
+            .on_guild_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageDelete`] is received.
+    ///
+    /// Provides the deleted message if it was in the [`Cache`]'s message
+    /// cache, which is disabled by default. Refer to [`with_message_cache`]
+    /// to enable it.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
+    /// [`with_message_cache`]: #method.with_message_cache
+    pub fn on_message_delete<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, ChannelId, MessageId, Option<Message>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_delete
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageDeleteBulk`] is received.
+    ///
+    /// Provides those of the deleted messages which were in the [`Cache`]'s
+    /// message cache, which is disabled by default. Refer to
+    /// [`with_message_cache`] to enable it.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageDeleteBulk`]: ../model/event/enum.Event.html#variant.MessageDeleteBulk
+    /// [`with_message_cache`]: #method.with_message_cache
+    pub fn on_message_delete_bulk<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, ChannelId, Vec<MessageId>, Vec<Message>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_delete_bulk
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageUpdate`] is received.
+    ///
+    /// Provides the message from before the update if it was in the
+    /// [`Cache`]'s message cache, which is disabled by default. Refer to
+    /// [`with_message_cache`] to enable it.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
+    /// [`with_message_cache`]: #method.with_message_cache
+    pub fn on_message_update<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, Option<Message>, MessageUpdateEvent) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`UserUpdate`] is received.
@@ -848,11 +1648,228 @@ impl Client {
     /// The old current user will be provided as well.
     ///
     /// [`UserUpdate`]: ../model/event/enum.Event.html#variant.UserUpdate
-    pub fn on_user_update<F>(&mut self, handler: F)
+    pub fn on_user_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, CurrentUser, CurrentUser) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_user_update = Some(Arc::new(handler));
+            .on_user_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a user joins a voice channel in a guild,
+    /// having not been in one before.
+    ///
+    /// This is derived from a [`VoiceStateUpdate`], by comparing the user's voice
+    /// state with the one in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    pub fn on_voice_channel_join<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, UserId, ChannelId) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_voice_channel_join
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a user leaves a voice channel in a guild,
+    /// without joining another. The channel which was left is given.
+    ///
+    /// This is derived from a [`VoiceStateUpdate`], by comparing the user's voice
+    /// state with the one in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    pub fn on_voice_channel_leave<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, UserId, ChannelId) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_voice_channel_leave
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a user moves from one voice channel in a
+    /// guild to another.
+    ///
+    /// This is derived from a [`VoiceStateUpdate`], by comparing the user's voice
+    /// state with the one in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    pub fn on_voice_channel_move<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, UserId, Change<ChannelId>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_voice_channel_move
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a user is server deafened or undeafened. The
+    /// `bool` is whether the user is now deafened.
+    ///
+    /// This is derived from a [`VoiceStateUpdate`], by comparing the user's voice
+    /// state with the one in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    pub fn on_voice_server_deaf_update<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, UserId, bool) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_voice_server_deaf_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a user is server muted or unmuted. The `bool`
+    /// is whether the user is now muted.
+    ///
+    /// This is derived from a [`VoiceStateUpdate`], by comparing the user's voice
+    /// state with the one in the [`Cache`].
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`VoiceStateUpdate`]: ../model/event/enum.Event.html#variant.VoiceStateUpdate
+    pub fn on_voice_server_mute_update<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, GuildId, UserId, bool) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_voice_server_mute_update
+            .push(Arc::new(handler))
+    }
+
+    /// Configures which data the [`Cache`] stores, to reduce its memory usage.
+    ///
+    /// Presences, voice states and the members of large guilds can each be
+    /// disabled, the number of members and presences stored per guild can be
+    /// capped, and users which are no longer referenced can be removed.
+    /// Refer to [`CacheConfig`] for the defaults, which store everything.
+    ///
+    /// # Examples
+    ///
+    /// Store neither presences nor the members of large guilds, and remove
+    /// users when they are no longer referenced:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// client.with_cache_config(|c| c
+    ///     .presences(false)
+    ///     .large_guild_members(false)
+    ///     .prune_users(true));
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`CacheConfig`]: ../ext/cache/struct.CacheConfig.html
+    pub fn with_cache_config<F>(&mut self, f: F)
+        where F: FnOnce(CacheConfig) -> CacheConfig {
+        CACHE.write().unwrap().config = f(CacheConfig::default());
+    }
+
+    /// Sets a [`CacheSink`] which received events are mirrored to, such as one
+    /// shared by the processes of a sharded bot.
+    ///
+    /// The sink is only written to. The global [`Cache`] is still updated, and
+    /// is what the library's own lookups - such as those of model methods and
+    /// the event handlers - read from; use [`with_cache_config`] to reduce what
+    /// it stores.
+    ///
+    /// # Examples
+    ///
+    /// Mirror events to a separate instance of the in-memory cache:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    /// use serenity::ext::cache::Cache;
+    /// use std::sync::RwLock;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// client.with_cache_sink(RwLock::new(Cache::default()));
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`CacheSink`]: ../ext/cache/trait.CacheSink.html
+    /// [`with_cache_config`]: #method.with_cache_config
+    pub fn with_cache_sink<S: CacheSink + 'static>(&mut self, sink: S) {
+        cache::set_sink(Arc::new(sink));
+    }
+
+    /// Enables and configures the [`Cache`]'s storage of messages, which the
+    /// message delete and update handlers are provided the previous versions
+    /// of messages from.
+    ///
+    /// The most recent messages of each channel are stored, up to the
+    /// configured capacity. Refer to [`MessageCacheConfig`] for the defaults.
+    ///
+    /// # Examples
+    ///
+    /// Store up to 100 messages per channel, for at most a day, and log
+    /// deleted messages:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// client.with_message_cache(|c| c
+    ///     .capacity(100)
+    ///     .max_age(Duration::from_secs(60 * 60 * 24)));
+    ///
+    /// client.on_message_delete(|_context, _channel_id, _message_id, message| {
+    ///     if let Some(message) = message {
+    ///         println!("{} deleted: {}", message.author.name, message.content);
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`MessageCacheConfig`]: ../ext/cache/struct.MessageCacheConfig.html
+    pub fn with_message_cache<F>(&mut self, f: F)
+        where F: FnOnce(MessageCacheConfig) -> MessageCacheConfig {
+        CACHE.write().unwrap().message_config = f(MessageCacheConfig::default());
+    }
+
+    /// Loads a snapshot of the [`Cache`] written by [`Cache::save_to`], so
+    /// that cached guilds and users are available before they are received
+    /// from the gateway.
+    ///
+    /// The snapshot's guilds are replaced as the client's shards receive
+    /// them. If the file does not exist - such as on the first start of the
+    /// bot - the cache is left empty.
+    ///
+    /// # Examples
+    ///
+    /// Load the snapshot saved when the bot last shut down:
+    ///
+    /// ```rust,no_run
+    /// use serenity::Client;
+    ///
+    /// let mut client = Client::login("bot token here");
+    ///
+    /// client.with_cache_snapshot("cache.json").expect("Error loading the cache");
+    ///
+    /// let _ = client.start();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`] if the file could not be read from, or an
+    /// [`Error::Json`] if it does not contain a snapshot.
+    ///
+    /// [`Cache`]: ../ext/cache/struct.Cache.html
+    /// [`Cache::save_to`]: ../ext/cache/struct.Cache.html#method.save_to
+    /// [`Error::Io`]: ../enum.Error.html#variant.Io
+    /// [`Error::Json`]: ../enum.Error.html#variant.Json
+    pub fn with_cache_snapshot<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+
+        if !path.exists() {
+            return Ok(());
+        }
+
+        CACHE.write().unwrap().load_from(path)
     }
 }
 
@@ -861,11 +1878,12 @@ impl Client {
     /// Attaches a handler for when a [`ChannelUpdate`] is received.
     ///
     /// [`ChannelUpdate`]: ../model/event/enum.Event.html#variant.ChannelUpdate
-    pub fn on_channel_update<F>(&mut self, handler: F)
+    pub fn on_channel_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, Channel) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_channel_update = Some(Arc::new(handler));
+            .on_channel_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildDelete`] is received.
@@ -873,11 +1891,12 @@ impl Client {
     /// [`GuildDelete`]: ../model/event/enum.Event.html#variant.GuildDelete
     /// [`Role`]: ../model/struct.Role.html
     /// [`Cache`]: ../ext/cache/struct.Cache.html
-    pub fn on_guild_delete<F>(&mut self, handler: F)
+    pub fn on_guild_delete<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, PartialGuild) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_delete = Some(Arc::new(handler));
+            .on_guild_delete
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMemberRemove`] is received.
@@ -886,67 +1905,110 @@ impl Client {
     /// cache.
     ///
     /// [`GuildMemberRemove`]: ../model/event/enum.Event.html#variant.GuildMemberRemove
-    pub fn on_guild_member_remove<F>(&mut self, handler: F)
+    pub fn on_guild_member_remove<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, User) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_member_removal = Some(Arc::new(handler));
+            .on_guild_member_removal
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildMemberUpdate`] is received.
     ///
     /// [`GuildMemberUpdate`]: ../model/event/enum.Event.html#variant.GuildMemberUpdate
-    pub fn on_guild_member_update<F>(&mut self, handler: F)
+    pub fn on_guild_member_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildMemberUpdateEvent) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_member_update = Some(Arc::new(handler));
+            .on_guild_member_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildRoleDelete`] is received.
     ///
     /// [`GuildRoleDelete`]: ../model/event/enum.Event.html#variant.GuildRoleDelete
-    pub fn on_guild_role_delete<F>(&mut self, handler: F)
+    pub fn on_guild_role_delete<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, RoleId) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_role_delete = Some(Arc::new(handler));
+            .on_guild_role_delete
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildRoleUpdate`] is received.
     ///
     /// [`GuildRoleUpdate`]: ../model/event/enum.Event.html#variant.GuildRoleUpdate
     /// [`Cache`]: ../ext/cache/struct.Cache.html
-    pub fn on_guild_role_update<F>(&mut self, handler: F)
+    pub fn on_guild_role_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, GuildId, Role) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_role_update = Some(Arc::new(handler));
+            .on_guild_role_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`GuildUpdate`] is received.
     ///
     /// [`GuildUpdate`]: ../model/event/enum.Event.html#variant.GuildUpdate
-    pub fn on_guild_update<F>(&mut self, handler: F)
+    pub fn on_guild_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, PartialGuild) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_guild_update = Some(Arc::new(handler));
+            .on_guild_update
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageDelete`] is received.
+    ///
+    /// [`MessageDelete`]: ../model/event/enum.Event.html#variant.MessageDelete
+    pub fn on_message_delete<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, ChannelId, MessageId) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_delete
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageDeleteBulk`] is received.
+    ///
+    /// [`MessageDeleteBulk`]: ../model/event/enum.Event.html#variant.MessageDeleteBulk
+    pub fn on_message_delete_bulk<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, ChannelId, Vec<MessageId>) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_delete_bulk
+            .push(Arc::new(handler))
+    }
+
+    /// Attaches a handler for when a [`MessageUpdate`] is received.
+    ///
+    /// [`MessageUpdate`]: ../model/event/enum.Event.html#variant.MessageUpdate
+    pub fn on_message_update<F>(&mut self, handler: F) -> HandlerId
+        where F: Fn(Context, MessageUpdateEvent) + Send + Sync + 'static {
+        self.event_store.write()
+            .unwrap()
+            .on_message_update
+            .push(Arc::new(handler))
     }
 
     /// Attaches a handler for when a [`UserUpdate`] is received.
     ///
     /// [`UserUpdate`]: ../model/event/enum.Event.html#variant.UserUpdate
-    pub fn on_user_update<F>(&mut self, handler: F)
+    pub fn on_user_update<F>(&mut self, handler: F) -> HandlerId
         where F: Fn(Context, CurrentUser) + Send + Sync + 'static {
         self.event_store.write()
             .unwrap()
-            .on_user_update = Some(Arc::new(handler));
+            .on_user_update
+            .push(Arc::new(handler))
     }
 }
 
 struct BootInfo {
+    encoding: GatewayEncoding,
     gateway_url: Arc<Mutex<String>>,
+    identify: Identify,
+    recorder: Option<Recorder>,
+    refresh_gateway_url: bool,
     shard_info: Option<[u64; 2]>,
     token: String,
 }
@@ -954,10 +2016,16 @@ struct BootInfo {
 #[cfg(feature="framework")]
 struct MonitorInfo {
     data: Arc<Mutex<ShareMap>>,
+    encoding: GatewayEncoding,
     event_store: Arc<RwLock<EventStore>>,
     framework: Arc<Mutex<Framework>>,
     gateway_url: Arc<Mutex<String>>,
+    identify: Identify,
+    keep_raw_events: bool,
+    pool: WorkerPool,
     receiver: Receiver<WebSocketStream>,
+    recorder: Option<Recorder>,
+    refresh_gateway_url: bool,
     shard: Arc<Mutex<Shard>>,
     shard_info: Option<[u64; 2]>,
     token: String,
@@ -966,9 +2034,15 @@ struct MonitorInfo {
 #[cfg(not(feature="framework"))]
 struct MonitorInfo {
     data: Arc<Mutex<ShareMap>>,
+    encoding: GatewayEncoding,
     event_store: Arc<RwLock<EventStore>>,
     gateway_url: Arc<Mutex<String>>,
+    identify: Identify,
+    keep_raw_events: bool,
+    pool: WorkerPool,
     receiver: Receiver<WebSocketStream>,
+    recorder: Option<Recorder>,
+    refresh_gateway_url: bool,
     shard: Arc<Mutex<Shard>>,
     shard_info: Option<[u64; 2]>,
     token: String,
@@ -984,7 +2058,7 @@ fn boot_shard(info: &BootInfo) -> Result<(Shard, ReadyEvent, Receiver<WebSocketS
         // If we've tried over 3 times so far, get a new gateway URL.
         //
         // If doing so fails, count this as a boot attempt.
-        if attempt_number > 3 {
+        if attempt_number > 3 && info.refresh_gateway_url {
             match rest::get_gateway() {
                 Ok(g) => *info.gateway_url.lock().unwrap() = g.url,
                 Err(why) => {
@@ -996,12 +2070,24 @@ fn boot_shard(info: &BootInfo) -> Result<(Shard, ReadyEvent, Receiver<WebSocketS
             }
         }
 
-        let attempt = Shard::new(&info.gateway_url.lock().unwrap(),
-                                 &info.token,
-                                 info.shard_info);
+        let config = |s: ShardConfig| {
+            let s = s.encoding(info.encoding).identify(info.identify.clone());
+
+            match info.recorder {
+                Some(ref recorder) => s.recorder(recorder.clone()),
+                None => s,
+            }
+        };
+
+        let attempt = Shard::new_with(&info.gateway_url.lock().unwrap(),
+                                      &info.token,
+                                      info.shard_info,
+                                      config);
 
         match attempt {
-            Ok((shard, ready, receiver)) => {
+            Ok((mut shard, ready, receiver)) => {
+                shard.set_refresh_gateway_url(info.refresh_gateway_url);
+
                 #[cfg(feature="cache")]
                 {
                     CACHE.write()
@@ -1029,7 +2115,11 @@ fn monitor_shard(mut info: MonitorInfo) {
 
         for _ in 0..3 {
             let boot = boot_shard(&BootInfo {
+                encoding: info.encoding,
                 gateway_url: info.gateway_url.clone(),
+                identify: info.identify.clone(),
+                recorder: info.recorder.clone(),
+                refresh_gateway_url: info.refresh_gateway_url,
                 shard_info: info.shard_info,
                 token: info.token.clone(),
             });
@@ -1046,17 +2136,26 @@ fn monitor_shard(mut info: MonitorInfo) {
 
                     boot_successful = true;
 
+                    let raw = dispatch_ready_raw(&info.shard,
+                                                 &info.data,
+                                                 &info.event_store,
+                                                 info.keep_raw_events);
+
                     feature_framework! {{
                         dispatch(Event::Ready(ready),
                                  &info.shard,
                                  &info.framework,
                                  &info.data,
-                                 &info.event_store);
+                                 &info.event_store,
+                                 &info.pool,
+                                 raw);
                     } else {
                         dispatch(Event::Ready(ready),
                                  &info.shard,
                                  &info.data,
-                                 &info.event_store);
+                                 &info.event_store,
+                                 &info.pool,
+                                 raw);
                     }}
 
                     break;
@@ -1079,16 +2178,25 @@ fn monitor_shard(mut info: MonitorInfo) {
 
 fn handle_shard(info: &mut MonitorInfo) {
     loop {
-        let event = match info.receiver.recv_json(GatewayEvent::decode) {
+        let value = match info.receiver.recv_encoded(info.encoding, Ok) {
             Err(Error::WebSocket(WebSocketError::NoDataAvailable)) => {
                 debug!("Attempting to shutdown receiver/sender");
 
-                match info.shard.lock().unwrap().resume(&mut info.receiver) {
+                let resumed = info.shard.lock().unwrap().resume(&mut info.receiver);
+
+                match resumed {
                     Ok((_, receiver)) => {
                         debug!("Successfully resumed shard");
 
                         info.receiver = receiver;
 
+                        // The dispatch received while resuming still reaches
+                        // the raw event handlers.
+                        let _ = dispatch_ready_raw(&info.shard,
+                                                   &info.data,
+                                                   &info.event_store,
+                                                   false);
+
                         continue;
                     },
                     Err(why) => {
@@ -1101,18 +2209,41 @@ fn handle_shard(info: &mut MonitorInfo) {
             other => other,
         };
 
+        let (event, raw) = match value {
+            Ok(value) => {
+                if let Some(ref recorder) = info.recorder {
+                    recorder.record(info.shard_info, &value);
+                }
+
+                decode_event(value,
+                             &info.shard,
+                             &info.data,
+                             &info.event_store,
+                             info.keep_raw_events)
+            },
+            Err(why) => (Err(why), None),
+        };
+
         trace!("Received event on shard handler: {:?}", event);
 
         // This will only lock when _updating_ the shard, resuming, etc. Most
         // of the time, this won't be locked (i.e. when receiving an event over
         // the receiver, separate from the shard itself).
-        let event = match info.shard.lock().unwrap().handle_event(event, &mut info.receiver) {
+        let handled = info.shard.lock().unwrap().handle_event(event, &mut info.receiver);
+
+        let (event, raw) = match handled {
             Ok(Some((event, Some(new_receiver)))) => {
                 info.receiver = new_receiver;
 
-                event
+                // The shard may have reconnected, receiving a new READY.
+                let ready_raw = dispatch_ready_raw(&info.shard,
+                                                   &info.data,
+                                                   &info.event_store,
+                                                   info.keep_raw_events);
+
+                (event, ready_raw.or(raw))
             },
-            Ok(Some((event, None))) => event,
+            Ok(Some((event, None))) => (event, raw),
             Ok(None) => continue,
             Err(why) => {
                 error!("Shard handler received err: {:?}", why);
@@ -1126,30 +2257,132 @@ fn handle_shard(info: &mut MonitorInfo) {
                      &info.shard,
                      &info.framework,
                      &info.data,
-                     &info.event_store);
+                     &info.event_store,
+                     &info.pool,
+                     raw);
         } else {
             dispatch(event,
                      &info.shard,
                      &info.data,
-                     &info.event_store);
+                     &info.event_store,
+                     &info.pool,
+                     raw);
         }}
     }
 }
 
+/// Decodes a received payload, first passing it to the raw event handlers and
+/// keeping a copy of it if the payload is a dispatch.
+fn decode_event(value: Value,
+                shard: &Arc<Mutex<Shard>>,
+                data: &Arc<Mutex<ShareMap>>,
+                event_store: &Arc<RwLock<EventStore>>,
+                keep_raw_events: bool)
+                -> (Result<GatewayEvent>, Option<Arc<Value>>) {
+    if value.get("op").and_then(|op| op.as_u64()) != Some(OpCode::Event.num()) {
+        return (GatewayEvent::decode(value), None);
+    }
+
+    dispatch_raw(&value, shard, data, event_store);
+
+    if keep_raw_events {
+        let raw = Arc::new(value.clone());
+
+        (GatewayEvent::decode(value), Some(raw))
+    } else {
+        (GatewayEvent::decode(value), None)
+    }
+}
+
+/// Passes the payload of the READY - or RESUMED - that a shard received while
+/// connecting to the raw event handlers, returning a copy of it if raw events
+/// are kept.
+fn dispatch_ready_raw(shard: &Arc<Mutex<Shard>>,
+                      data: &Arc<Mutex<ShareMap>>,
+                      event_store: &Arc<RwLock<EventStore>>,
+                      keep_raw_events: bool)
+                      -> Option<Arc<Value>> {
+    let payload = shard.lock().unwrap().take_ready_payload();
+
+    let payload = match payload {
+        Some(payload) => payload,
+        None => return None,
+    };
+
+    dispatch_raw(&payload, shard, data, event_store);
+
+    if keep_raw_events {
+        Some(Arc::new(payload))
+    } else {
+        None
+    }
+}
+
+/// Calls the raw event handlers with a dispatch payload.
+///
+/// This is done on the shard's thread - rather than the pool - so that the
+/// handlers are called in order, and before the dispatch's own handlers.
+fn dispatch_raw(value: &Value,
+                shard: &Arc<Mutex<Shard>>,
+                data: &Arc<Mutex<ShareMap>>,
+                event_store: &Arc<RwLock<EventStore>>) {
+    let handlers = {
+        let event_store = event_store.read().unwrap();
+
+        match event_store.on_raw_event.callbacks(&event_store.handlers) {
+            Some(handlers) => handlers,
+            None => return,
+        }
+    };
+
+    let context = Context::new(None, shard.clone(), data.clone());
+    let name = value.get("t").and_then(|t| t.as_str()).unwrap_or("");
+    let seq = value.get("s").and_then(|s| s.as_u64()).unwrap_or(0);
+
+    for handler in &handlers {
+        let call = || match *handler {
+            Callback::Closure(ref closure) => {
+                (closure)(context.clone(), name.to_owned(), seq, value)
+            },
+            Callback::Handler(ref handler) => {
+                handler.on_raw_event(context.clone(), name.to_owned(), seq, value)
+            },
+        };
+
+        if panic::catch_unwind(AssertUnwindSafe(call)).is_err() {
+            warn!("Raw event handler panicked");
+        }
+    }
+}
+
 fn login(token: String) -> Client {
     rest::set_token(&token);
 
     feature_framework! {{
         Client {
             data: Arc::new(Mutex::new(ShareMap::custom())),
+            encoding: GatewayEncoding::default(),
             event_store: Arc::new(RwLock::new(EventStore::default())),
+            gateway_url: None,
+            identify: Identify::default(),
+            keep_raw_events: false,
+            pool: None,
+            pool_config: PoolConfig::default(),
+            recorder: None,
             framework: Arc::new(Mutex::new(Framework::default())),
             token: token,
         }
     } else {
         Client {
             data: Arc::new(Mutex::new(ShareMap::custom())),
+            encoding: GatewayEncoding::default(),
             event_store: Arc::new(RwLock::new(EventStore::default())),
+            gateway_url: None,
+            identify: Identify::default(),
+            keep_raw_events: false,
+            pool: None,
+            pool_config: PoolConfig::default(),
+            recorder: None,
             token: token,
         }
     }}
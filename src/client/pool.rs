@@ -0,0 +1,262 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use ::model::{ChannelId, GuildId};
+
+#[cfg(feature="cache")]
+use super::CACHE;
+
+/// What to do when an event is dispatched while the queue of the worker it is
+/// assigned to is full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Backpressure {
+    /// Block the shard until the worker has room in its queue. No events are
+    /// lost, but no further events are received by the shard while it is
+    /// blocked.
+    Block,
+    /// Drop the event - without calling its handlers - and log a warning.
+    Drop,
+}
+
+/// Which events are guaranteed to have their handlers called in the order in
+/// which they were received.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DispatchOrdering {
+    /// Events for the same channel are handled in order. Events which do not
+    /// relate to a channel are ordered by their guild.
+    PerChannel,
+    /// Events for the same guild - including those for any of its channels -
+    /// are handled in order. Events which do not relate to a guild are ordered
+    /// by their channel.
+    ///
+    /// **Note**: Without the `cache` feature, the guild of a channel can only
+    /// be determined for some events; others are ordered by their channel.
+    PerGuild,
+    /// No ordering is guaranteed; events are handled by whichever worker is
+    /// free.
+    Unordered,
+}
+
+/// Configuration for the pool of worker threads which event handlers and
+/// framework commands are run on.
+///
+/// Refer to [`Client::with_pool`] for more information.
+///
+/// [`Client::with_pool`]: struct.Client.html#method.with_pool
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    backpressure: Backpressure,
+    ordering: DispatchOrdering,
+    queue_len: usize,
+    size: usize,
+}
+
+impl PoolConfig {
+    /// What to do when a worker's queue is full.
+    ///
+    /// Defaults to [`Backpressure::Block`].
+    ///
+    /// [`Backpressure::Block`]: enum.Backpressure.html#variant.Block
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+
+        self
+    }
+
+    /// Which events must be handled in the order they were received.
+    ///
+    /// Defaults to [`DispatchOrdering::Unordered`].
+    ///
+    /// [`DispatchOrdering::Unordered`]: enum.DispatchOrdering.html#variant.Unordered
+    pub fn ordering(mut self, ordering: DispatchOrdering) -> Self {
+        self.ordering = ordering;
+
+        self
+    }
+
+    /// The number of events which can be waiting to be handled by each worker.
+    ///
+    /// Defaults to `256`.
+    pub fn queue_len(mut self, queue_len: usize) -> Self {
+        self.queue_len = queue_len;
+
+        self
+    }
+
+    /// The number of worker threads. This is clamped to a minimum of `1`.
+    ///
+    /// Defaults to `16`.
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+
+        self
+    }
+}
+
+impl Default for PoolConfig {
+    fn default() -> PoolConfig {
+        PoolConfig {
+            backpressure: Backpressure::Block,
+            ordering: DispatchOrdering::Unordered,
+            queue_len: 256,
+            size: 16,
+        }
+    }
+}
+
+trait Job: Send {
+    fn run(self: Box<Self>);
+}
+
+impl<F: FnOnce() + Send> Job for F {
+    fn run(self: Box<Self>) {
+        (*self)()
+    }
+}
+
+struct Inner {
+    config: PoolConfig,
+    next: AtomicUsize,
+    senders: Vec<SyncSender<Box<Job>>>,
+}
+
+/// A fixed-size pool of worker threads, each with a bounded queue.
+///
+/// The [`Client`] runs event handlers and commands on a pool configured via
+/// [`Client::with_pool`]. A pool can also be created separately to run other
+/// work in the same way.
+///
+/// Jobs with a key are always run by the same worker, and so are run in the
+/// order in which they were submitted.
+///
+/// # Examples
+///
+/// Run two jobs for the same channel in order:
+///
+/// ```rust
+/// use serenity::client::{DispatchOrdering, PoolConfig, WorkerPool};
+/// use serenity::model::ChannelId;
+///
+/// let pool = WorkerPool::new(PoolConfig::default()
+///     .ordering(DispatchOrdering::PerChannel)
+///     .size(4));
+/// let key = pool.key(Some(ChannelId(81384788765712384)), None);
+///
+/// pool.execute(key, || println!("first"));
+/// pool.execute(key, || println!("second"));
+/// ```
+///
+/// [`Client`]: struct.Client.html
+/// [`Client::with_pool`]: struct.Client.html#method.with_pool
+#[derive(Clone)]
+pub struct WorkerPool {
+    inner: Arc<Inner>,
+}
+
+impl WorkerPool {
+    /// Creates a pool, starting its worker threads.
+    ///
+    /// A size of `0` is treated as `1`.
+    pub fn new(config: PoolConfig) -> WorkerPool {
+        let size = if config.size == 0 { 1 } else { config.size };
+        let senders = (0..size).map(|i| {
+            let (tx, rx) = mpsc::sync_channel(config.queue_len);
+
+            let _ = thread::Builder::new()
+                .name(format!("serenity worker {}", i))
+                .spawn(move || work(rx));
+
+            tx
+        }).collect();
+
+        WorkerPool {
+            inner: Arc::new(Inner {
+                config: config,
+                next: AtomicUsize::new(0),
+                senders: senders,
+            }),
+        }
+    }
+
+    /// Determines the ordering key of an event relating to the given channel
+    /// and/or guild, according to the configured [`DispatchOrdering`].
+    ///
+    /// [`DispatchOrdering`]: enum.DispatchOrdering.html
+    pub fn key(&self, channel_id: Option<ChannelId>, guild_id: Option<GuildId>) -> Option<u64> {
+        match self.inner.config.ordering {
+            DispatchOrdering::PerChannel => channel_id.map(|c| c.0).or_else(|| guild_id.map(|g| g.0)),
+            DispatchOrdering::PerGuild => {
+                guild_id.or_else(|| channel_id.and_then(guild_of))
+                    .map(|g| g.0)
+                    .or_else(|| channel_id.map(|c| c.0))
+            },
+            DispatchOrdering::Unordered => None,
+        }
+    }
+
+    /// Submits a job to be run on a worker.
+    ///
+    /// Jobs with the same key are run in order on the same worker. Jobs without
+    /// a key are given to the first worker with room in its queue.
+    pub fn execute<F: FnOnce() + Send + 'static>(&self, key: Option<u64>, job: F) {
+        let senders = &self.inner.senders;
+        let mut job: Box<Job> = Box::new(job);
+
+        let index = match key {
+            Some(key) => {
+                let mut hasher = DefaultHasher::new();
+                key.hash(&mut hasher);
+
+                (hasher.finish() % senders.len() as u64) as usize
+            },
+            None => {
+                let start = self.inner.next.fetch_add(1, Ordering::Relaxed);
+
+                for offset in 0..senders.len() {
+                    match senders[(start + offset) % senders.len()].try_send(job) {
+                        Ok(()) | Err(TrySendError::Disconnected(_)) => return,
+                        Err(TrySendError::Full(returned)) => job = returned,
+                    }
+                }
+
+                start % senders.len()
+            },
+        };
+
+        match self.inner.config.backpressure {
+            Backpressure::Block => {
+                let _ = senders[index].send(job);
+            },
+            Backpressure::Drop => {
+                if let Err(TrySendError::Full(_)) = senders[index].try_send(job) {
+                    warn!("Worker queue full; dropping event");
+                }
+            },
+        }
+    }
+}
+
+fn work(rx: Receiver<Box<Job>>) {
+    while let Ok(job) = rx.recv() {
+        if panic::catch_unwind(AssertUnwindSafe(move || job.run())).is_err() {
+            warn!("Event handler panicked");
+        }
+    }
+}
+
+#[cfg(feature="cache")]
+fn guild_of(channel_id: ChannelId) -> Option<GuildId> {
+    CACHE.read()
+        .unwrap()
+        .guild_channel(channel_id)
+        .map(|channel| channel.read().unwrap().guild_id)
+}
+
+#[cfg(not(feature="cache"))]
+fn guild_of(_: ChannelId) -> Option<GuildId> {
+    None
+}
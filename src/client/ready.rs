@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use ::model::GuildId;
+
+/// The number of seconds after a READY that a shard is considered ready, even
+/// if not all of its guilds have been received.
+const TIMEOUT: u64 = 30;
+
+/// The time to wait after a READY before considering the shard ready.
+pub fn timeout() -> Duration {
+    Duration::from_secs(TIMEOUT)
+}
+
+/// The readiness of each of a client's shards, held in its event store.
+#[derive(Default)]
+pub struct State {
+    /// The number of shards started by the client, or `0` if unknown - in
+    /// which case the shards which have received a READY are all expected.
+    expected: usize,
+    generation: u64,
+    shards: HashMap<Option<[u64; 2]>, ShardState>,
+}
+
+struct ShardState {
+    /// Incremented on every READY, so that the timeout of a previous session
+    /// does not complete the current one.
+    generation: u64,
+    pending: HashSet<GuildId>,
+    unavailable: Vec<GuildId>,
+    ready: bool,
+}
+
+/// The guilds which stayed unavailable once a shard became ready, and - if
+/// that was the last shard to become ready - those of the whole client.
+pub struct Completion {
+    pub client: Option<Vec<GuildId>>,
+    pub shard: Vec<GuildId>,
+}
+
+impl State {
+    /// Marks a shard as ready, with any guilds still pending as unavailable.
+    fn complete(&mut self, shard_info: Option<[u64; 2]>) -> Option<Completion> {
+        let unavailable = match self.shards.get_mut(&shard_info) {
+            Some(shard) => {
+                if shard.ready {
+                    return None;
+                }
+
+                shard.ready = true;
+                shard.unavailable.extend(shard.pending.drain());
+
+                shard.unavailable.clone()
+            },
+            None => return None,
+        };
+
+        Some(Completion {
+            client: self.client_completion(),
+            shard: unavailable,
+        })
+    }
+
+    /// The guilds which stayed unavailable across every shard, if all of the
+    /// expected shards are ready.
+    fn client_completion(&self) -> Option<Vec<GuildId>> {
+        let all_ready = !self.shards.is_empty() &&
+                        self.shards.len() >= self.expected &&
+                        self.shards.values().all(|shard| shard.ready);
+
+        if all_ready {
+            Some(self.shards.values().flat_map(|shard| shard.unavailable.iter().cloned()).collect())
+        } else {
+            None
+        }
+    }
+
+    /// Sets the number of shards started by the client.
+    pub fn expect_shards(&mut self, count: usize) {
+        self.expected = count;
+    }
+
+    /// Stops expecting a shard which failed to start, returning the guilds of
+    /// the whole client if the shards which did start are all ready.
+    pub fn shard_failed(&mut self) -> Option<Vec<GuildId>> {
+        self.expected = self.expected.saturating_sub(1);
+
+        self.client_completion()
+    }
+
+    /// Starts waiting for the guilds which were unavailable in a shard's READY,
+    /// returning the generation to pass to [`timed_out`] and the completion if
+    /// there were no guilds to wait for.
+    ///
+    /// [`timed_out`]: #method.timed_out
+    pub fn started(&mut self, shard_info: Option<[u64; 2]>, guild_ids: &[GuildId])
+        -> (u64, Option<Completion>) {
+        self.generation += 1;
+        let generation = self.generation;
+
+        self.shards.insert(shard_info, ShardState {
+            generation: generation,
+            pending: guild_ids.iter().cloned().collect(),
+            unavailable: vec![],
+            ready: false,
+        });
+
+        let completion = if guild_ids.is_empty() {
+            self.complete(shard_info)
+        } else {
+            None
+        };
+
+        (generation, completion)
+    }
+
+    /// Marks a guild as received by a shard, returning the completion if it was
+    /// the last guild being waited for.
+    pub fn guild_received(&mut self,
+                          shard_info: Option<[u64; 2]>,
+                          guild_id: GuildId,
+                          available: bool)
+                          -> Option<Completion> {
+        let done = match self.shards.get_mut(&shard_info) {
+            Some(shard) => {
+                if !shard.pending.remove(&guild_id) {
+                    return None;
+                }
+
+                if !available {
+                    shard.unavailable.push(guild_id);
+                }
+
+                shard.pending.is_empty()
+            },
+            None => return None,
+        };
+
+        if done {
+            self.complete(shard_info)
+        } else {
+            None
+        }
+    }
+
+    /// Considers a shard ready after the timeout, if it is still waiting on the
+    /// same READY.
+    pub fn timed_out(&mut self, shard_info: Option<[u64; 2]>, generation: u64) -> Option<Completion> {
+        let current = self.shards
+            .get(&shard_info)
+            .map_or(false, |shard| shard.generation == generation);
+
+        if current {
+            self.complete(shard_info)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Lookups which retrieve data from the global [`CACHE`], falling back to
+//! requesting it over the [`rest`] API and storing the result in the cache.
+//!
+//! This differs from methods such as [`ChannelId::find`], which only search
+//! the cache, and [`ChannelId::get`], which do not store what is requested.
+//!
+//! When a request responds that the item does not exist, the item is
+//! remembered as missing for the [`CacheConfig::negative_ttl`], during which
+//! lookups of it return the same error without making a request.
+//!
+//! # Examples
+//!
+//! Retrieve a user, requesting them only if they are not cached:
+//!
+//! ```rust,no_run
+//! use serenity::ext::cache::lookup;
+//!
+//! match lookup::user(114941315417899012) {
+//!     Ok(user) => println!("Found {}", user.name),
+//!     Err(why) => println!("Error retrieving the user: {:?}", why),
+//! }
+//! ```
+//!
+//! [`CACHE`]: ../../../client/struct.CACHE.html
+//! [`CacheConfig::negative_ttl`]: ../struct.CacheConfig.html#method.negative_ttl
+//! [`ChannelId::find`]: ../../../model/struct.ChannelId.html#method.find
+//! [`ChannelId::get`]: ../../../model/struct.ChannelId.html#method.get
+//! [`rest`]: ../../../client/rest/index.html
+
+use hyper::status::StatusCode;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+use super::Cache;
+use ::client::CACHE;
+use ::internal::prelude::*;
+use ::model::event::{ChannelCreateEvent, Event};
+use ::model::*;
+
+#[cfg(feature="fake-gateway")]
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref MISSES: Mutex<HashMap<Miss, Instant>> = Mutex::new(HashMap::new());
+}
+
+#[cfg(feature="fake-gateway")]
+lazy_static! {
+    static ref REQUESTS: RwLock<Requests> = RwLock::new(Requests::default());
+}
+
+#[cfg(feature="fake-gateway")]
+#[doc(hidden)]
+pub use self::requests::Requests;
+#[cfg(not(feature="fake-gateway"))]
+use self::requests::Requests;
+
+mod requests {
+    use ::client::rest;
+    use ::internal::prelude::*;
+    use ::model::*;
+
+    /// The requests made by lookups for items which are not cached.
+    ///
+    /// With the `fake-gateway` feature, these can be replaced via
+    /// [`set_requests`], so that lookups can be tested without making
+    /// requests.
+    ///
+    /// [`set_requests`]: fn.set_requests.html
+    #[derive(Clone, Copy)]
+    pub struct Requests {
+        pub channel: fn(ChannelId) -> Result<Channel>,
+        pub guild: fn(GuildId) -> Result<PartialGuild>,
+        pub member: fn(GuildId, UserId) -> Result<Member>,
+        pub roles: fn(GuildId) -> Result<Vec<Role>>,
+        pub user: fn(UserId) -> Result<User>,
+    }
+
+    impl Default for Requests {
+        fn default() -> Requests {
+            Requests {
+                channel: |channel_id| rest::get_channel(channel_id.0),
+                guild: |guild_id| rest::get_guild(guild_id.0),
+                member: |guild_id, user_id| rest::get_member(guild_id.0, user_id.0),
+                roles: |guild_id| rest::get_guild_roles(guild_id.0),
+                user: |user_id| rest::get_user(user_id.0),
+            }
+        }
+    }
+}
+
+#[cfg(feature="fake-gateway")]
+#[doc(hidden)]
+pub fn set_requests(requests: Requests) {
+    *REQUESTS.write().unwrap() = requests;
+}
+
+#[cfg(feature="fake-gateway")]
+fn requests() -> Requests {
+    *REQUESTS.read().unwrap()
+}
+
+#[cfg(not(feature="fake-gateway"))]
+fn requests() -> Requests {
+    Requests::default()
+}
+
+// An item which a request responded does not exist.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Miss {
+    Channel(ChannelId),
+    Guild(GuildId),
+    Member(GuildId, UserId),
+    Role(GuildId, RoleId),
+    User(UserId),
+}
+
+/// Retrieves a channel from the cache, or requests it if it is not cached.
+///
+/// A requested guild channel is only stored if its guild is cached.
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidRequest`] with a `404` status if the
+/// channel does not exist, or any other error from requesting it.
+///
+/// [`ClientError::InvalidRequest`]: ../../../client/enum.ClientError.html#variant.InvalidRequest
+pub fn channel<C: Into<ChannelId>>(channel_id: C) -> Result<Channel> {
+    let channel_id = channel_id.into();
+
+    if let Some(channel) = CACHE.read().unwrap().channel(channel_id) {
+        return Ok(channel);
+    }
+
+    let request = requests().channel;
+
+    fetch(Miss::Channel(channel_id), || request(channel_id), |cache, channel| {
+        if let Channel::Guild(ref channel) = *channel {
+            if !cache.guilds.contains_key(&channel.read().unwrap().guild_id) {
+                return;
+            }
+        }
+
+        cache.update_with_channel_create(&ChannelCreateEvent {
+            channel: channel.clone(),
+        });
+    })
+}
+
+/// Retrieves a guild from the cache, or requests it if it is not cached.
+///
+/// A requested guild is not stored, as only a partial guild - without its
+/// channels, members and other data - is sent over REST. Cached guilds are
+/// returned as a [`PartialGuild`] for the same reason, without their embed
+/// settings, which are only sent over REST.
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidRequest`] with a `404` status if the guild
+/// does not exist, or any other error from requesting it.
+///
+/// [`ClientError::InvalidRequest`]: ../../../client/enum.ClientError.html#variant.InvalidRequest
+/// [`PartialGuild`]: ../../../model/struct.PartialGuild.html
+pub fn guild<G: Into<GuildId>>(guild_id: G) -> Result<PartialGuild> {
+    let guild_id = guild_id.into();
+
+    if let Some(guild) = CACHE.read().unwrap().guild(guild_id) {
+        let guild = guild.read().unwrap();
+
+        return Ok(PartialGuild {
+            id: guild.id,
+            afk_channel_id: guild.afk_channel_id,
+            afk_timeout: guild.afk_timeout,
+            default_message_notifications: guild.default_message_notifications,
+            embed_channel_id: None,
+            embed_enabled: false,
+            emojis: guild.emojis.clone(),
+            features: guild.features.clone(),
+            icon: guild.icon.clone(),
+            mfa_level: guild.mfa_level,
+            name: guild.name.clone(),
+            owner_id: guild.owner_id,
+            region: guild.region.clone(),
+            roles: guild.roles.clone(),
+            splash: guild.splash.clone(),
+            verification_level: guild.verification_level,
+        });
+    }
+
+    let request = requests().guild;
+
+    fetch(Miss::Guild(guild_id), || request(guild_id), |_, _| {})
+}
+
+/// Retrieves a member of a guild from the cache, or requests them if they are
+/// not cached.
+///
+/// A requested member is only stored if their guild is cached, and if the
+/// [`CacheConfig`] allows it.
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidRequest`] with a `404` status if the
+/// member does not exist, or any other error from requesting them.
+///
+/// [`CacheConfig`]: ../struct.CacheConfig.html
+/// [`ClientError::InvalidRequest`]: ../../../client/enum.ClientError.html#variant.InvalidRequest
+pub fn member<G, U>(guild_id: G, user_id: U) -> Result<Member>
+    where G: Into<GuildId>, U: Into<UserId> {
+    let (guild_id, user_id) = (guild_id.into(), user_id.into());
+
+    if let Some(member) = CACHE.read().unwrap().member(guild_id, user_id) {
+        return Ok(member);
+    }
+
+    let (miss, request) = (Miss::Member(guild_id, user_id), requests().member);
+
+    fetch(miss, || request(guild_id, user_id), |cache, member| {
+        let guild = match cache.guilds.get(&guild_id) {
+            Some(guild) => guild.clone(),
+            None => return,
+        };
+        let mut guild = guild.write().unwrap();
+
+        if !cache.can_store_member(&guild, user_id) {
+            return;
+        }
+
+        cache.update_user_entry(&member.user.read().unwrap());
+
+        let mut member = member.clone();
+        member.user = cache.users[&user_id].clone();
+
+        guild.members.insert(user_id, member);
+    })
+}
+
+/// Retrieves a role of a guild from the cache, or requests the guild's roles
+/// if it is not cached.
+///
+/// A requested role is only stored if its guild is cached.
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidRequest`] with a `404` status if the guild
+/// or role does not exist, or any other error from requesting the roles.
+///
+/// [`ClientError::InvalidRequest`]: ../../../client/enum.ClientError.html#variant.InvalidRequest
+pub fn role<G, R>(guild_id: G, role_id: R) -> Result<Role>
+    where G: Into<GuildId>, R: Into<RoleId> {
+    let (guild_id, role_id) = (guild_id.into(), role_id.into());
+
+    if let Some(role) = CACHE.read().unwrap().role(guild_id, role_id) {
+        return Ok(role);
+    }
+
+    let roles = requests().roles;
+    let request = || {
+        roles(guild_id)?
+            .into_iter()
+            .find(|role| role.id == role_id)
+            .ok_or_else(not_found)
+    };
+
+    fetch(Miss::Role(guild_id, role_id), request, |cache, role| {
+        if let Some(guild) = cache.guilds.get(&guild_id) {
+            guild.write().unwrap().roles.insert(role.id, role.clone());
+        }
+    })
+}
+
+/// Retrieves a user from the cache, or requests them if they are not cached.
+///
+/// **Note**: Requesting a user requires the current user to be a bot user.
+///
+/// # Errors
+///
+/// Returns a [`ClientError::InvalidRequest`] with a `404` status if the user
+/// does not exist, or any other error from requesting them.
+///
+/// [`ClientError::InvalidRequest`]: ../../../client/enum.ClientError.html#variant.InvalidRequest
+pub fn user<U: Into<UserId>>(user_id: U) -> Result<User> {
+    let user_id = user_id.into();
+
+    if let Some(user) = CACHE.read().unwrap().user(user_id) {
+        return Ok(user.read().unwrap().clone());
+    }
+
+    let request = requests().user;
+
+    fetch(Miss::User(user_id), || request(user_id), |cache, user| {
+        cache.update_user_entry(user);
+    })
+}
+
+// Forgets the misses of the items which an event shows to now exist, whether
+// or not they are stored in the cache. This is called by the library for each
+// received event.
+#[doc(hidden)]
+pub fn update(event: &Event) {
+    match *event {
+        Event::ChannelCreate(ref event) => {
+            let channel_id = event.channel.id();
+
+            forget(|miss| *miss == Miss::Channel(channel_id));
+        },
+        Event::GuildCreate(ref event) => forget(|miss| match *miss {
+            Miss::Channel(channel_id) => event.guild.channels.contains_key(&channel_id),
+            Miss::Guild(guild_id) |
+            Miss::Member(guild_id, _) |
+            Miss::Role(guild_id, _) => guild_id == event.guild.id,
+            Miss::User(_) => false,
+        }),
+        Event::GuildMemberAdd(ref event) => {
+            let user_id = event.member.user.read().unwrap().id;
+
+            forget(|miss| {
+                *miss == Miss::Member(event.guild_id, user_id) || *miss == Miss::User(user_id)
+            });
+        },
+        Event::GuildRoleCreate(ref event) => {
+            forget(|miss| *miss == Miss::Role(event.guild_id, event.role.id));
+        },
+        _ => {},
+    }
+}
+
+fn forget<F: Fn(&Miss) -> bool>(found: F) {
+    MISSES.lock().unwrap().retain(|miss, _| !found(miss));
+}
+
+// Requests an item which is not cached - unless it recently did not exist -
+// storing it in the cache if found.
+fn fetch<T, F, I>(miss: Miss, request: F, insert: I) -> Result<T>
+    where F: FnOnce() -> Result<T>, I: FnOnce(&mut Cache, &T) {
+    let ttl = CACHE.read().unwrap().config.negative_ttl;
+
+    {
+        let mut misses = MISSES.lock().unwrap();
+
+        match misses.get(&miss).map(|missed_at| missed_at.elapsed() < ttl) {
+            Some(true) => return Err(not_found()),
+            Some(false) => {
+                misses.remove(&miss);
+            },
+            None => {},
+        }
+    }
+
+    match request() {
+        Ok(item) => {
+            insert(&mut CACHE.write().unwrap(), &item);
+
+            Ok(item)
+        },
+        Err(Error::Client(ClientError::InvalidRequest(StatusCode::NotFound))) => {
+            let mut misses = MISSES.lock().unwrap();
+
+            // Remove expired misses, so that the map does not keep growing.
+            misses.retain(|_, missed_at| missed_at.elapsed() < ttl);
+            misses.insert(miss, Instant::now());
+
+            Err(not_found())
+        },
+        Err(why) => Err(why),
+    }
+}
+
+fn not_found() -> Error {
+    Error::Client(ClientError::InvalidRequest(StatusCode::NotFound))
+}
@@ -61,14 +61,31 @@
 //! [`client::CACHE`]: ../../client/struct.CACHE.html
 //! [`rest`]: ../../client/rest/index.html
 
+use serde_json;
 use std::collections::hash_map::Entry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::default::Default;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use std::mem;
+use time::{self, Timespec};
+use ::internal::prelude::*;
 use ::model::*;
 use ::model::event::*;
 
+mod sink;
+
+pub mod lookup;
+
+pub use self::sink::CacheSink;
+
+#[doc(hidden)]
+pub use self::sink::{set_sink, sink};
+
 /// A cache of all events received over a [`Connection`], where storing at least
 /// some data from the event is possible.
 ///
@@ -113,6 +130,17 @@ pub struct Cache {
     /// [`Event::GuildUnavailable`]: ../../model/event/struct.GuildUnavailableEvent.html
     /// [`Guild`]: ../../model/struct.Guild.html
     pub channels: HashMap<ChannelId, Arc<RwLock<GuildChannel>>>,
+    /// The settings of which data is stored, which stores everything by
+    /// default.
+    ///
+    /// Refer to [`Client::with_cache_config`] for more information.
+    ///
+    /// [`Client::with_cache_config`]: ../../client/struct.Client.html#method.with_cache_config
+    pub config: CacheConfig,
+    // The number of events applied of each kind, reported via `stats`.
+    events: EventTally,
+    // When users which are no longer referenced were last pruned.
+    last_prune: Option<Instant>,
     /// A map of the groups that the current user is in.
     ///
     /// For bot users this will always be empty, except for in [special cases].
@@ -125,6 +153,28 @@ pub struct Cache {
     /// [`Emoji`]: ../../model/struct.Emoji.html
     /// [`Role`]: ../../model/struct.Role.html
     pub guilds: HashMap<GuildId, Arc<RwLock<Guild>>>,
+    /// The settings of the message cache, which is disabled by default.
+    ///
+    /// Refer to [`Client::with_message_cache`] for more information.
+    ///
+    /// [`Client::with_message_cache`]: ../../client/struct.Client.html#method.with_message_cache
+    pub message_config: MessageCacheConfig,
+    /// A map of channels to their most recently received or updated messages,
+    /// with the least recently received or updated first.
+    ///
+    /// Messages are added to - and updated from - this map via
+    /// [`MessageCreate`][`MessageCreateEvent`]s and
+    /// [`MessageUpdate`][`MessageUpdateEvent`]s, and removed via
+    /// [`MessageDelete`][`MessageDeleteEvent`]s and
+    /// [`MessageDeleteBulk`][`MessageDeleteBulkEvent`]s, only if the
+    /// [`message_config`] has a capacity.
+    ///
+    /// [`MessageCreateEvent`]: ../../model/event/struct.MessageCreateEvent.html
+    /// [`MessageDeleteBulkEvent`]: ../../model/event/struct.MessageDeleteBulkEvent.html
+    /// [`MessageDeleteEvent`]: ../../model/event/struct.MessageDeleteEvent.html
+    /// [`MessageUpdateEvent`]: ../../model/event/struct.MessageUpdateEvent.html
+    /// [`message_config`]: #structfield.message_config
+    pub messages: HashMap<ChannelId, VecDeque<Message>>,
     /// A map of notes that a user has made for individual users.
     ///
     /// An empty note is equivalent to having no note, and creating an empty
@@ -148,8 +198,12 @@ pub struct Cache {
     /// is received. Guilds are "sent in" over time through the receiving of
     /// [`Event::GuildCreate`]s.
     ///
+    /// A guild restored from a snapshot is also kept in [`guilds`] while it is
+    /// unavailable, until its [`Event::GuildCreate`] is received.
+    ///
     /// [`Event::GuildCreate`]: ../../model/enum.Event.html#variant.GuildCreate
     /// [`Event::GuildUnavailable`]: ../../model/enum.Event.html#variant.GuildUnavailable
+    /// [`guilds`]: #structfield.guilds
     pub unavailable_guilds: HashSet<GuildId>,
     /// The current user "logged in" and for which events are being received
     /// for.
@@ -178,7 +232,9 @@ pub struct Cache {
     ///
     /// Note, however, that users are _not_ removed from the map on removal
     /// events such as [`GuildMemberRemove`][`GuildMemberRemoveEvent`], as other
-    /// structs such as members or recipients may still exist.
+    /// structs such as members or recipients may still exist. Users which are
+    /// no longer referenced can be removed via [`prune_users`], or on removal
+    /// events if enabled in the [`config`].
     ///
     /// [`ChannelRecipientAddEvent`]: ../../model/event/struct.ChannelRecipientAddEvent.html
     /// [`GuildMemberAddEvent`]: ../../model/event/struct.GuildMemberAddEvent.html
@@ -189,6 +245,8 @@ pub struct Cache {
     /// [`PresenceUpdateEvent`]: ../../model/event/struct.PresenceUpdateEvent.html
     /// [`Ready`]: ../../model/event/struct.ReadyEvent.html
     /// [`RelationshipAdd`][`RelationshipAddEvent`]
+    /// [`config`]: #structfield.config
+    /// [`prune_users`]: #method.prune_users
     pub users: HashMap<UserId, Arc<RwLock<User>>>,
 }
 
@@ -220,6 +278,90 @@ impl Cache {
         total
     }
 
+    /// Retrieves the number of entries in each of the cache's collections,
+    /// along with the number of events applied to the cache of each kind and
+    /// when the last was applied.
+    ///
+    /// This iterates over every guild, and so is intended to be called
+    /// periodically, such as when reporting metrics.
+    ///
+    /// # Examples
+    ///
+    /// Print the number of guilds and members, and how many messages have
+    /// been received:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::CACHE;
+    ///
+    /// let stats = CACHE.read().unwrap().stats();
+    ///
+    /// println!("{} guilds with {} members", stats.guilds, stats.members);
+    ///
+    /// let messages = stats.events.get("MESSAGE_CREATE").cloned().unwrap_or(0);
+    /// println!("{} messages received", messages);
+    /// ```
+    pub fn stats(&self) -> CacheStats {
+        let mut stats = CacheStats {
+            approximate_size: mem::size_of::<Cache>(),
+            channels: self.channels.len(),
+            events: HashMap::new(),
+            groups: self.groups.len(),
+            guilds: self.guilds.len(),
+            last_update: None,
+            members: 0,
+            messages: 0,
+            presences: self.presences.len(),
+            private_channels: self.private_channels.len(),
+            unavailable_guilds: self.unavailable_guilds.len(),
+            users: self.users.len(),
+        };
+
+        for (name, count) in EVENT_NAMES.iter().zip(&self.events.counts) {
+            let count = count.load(Ordering::Relaxed);
+
+            if count > 0 {
+                stats.events.insert(name, count as u64);
+            }
+        }
+
+        stats.last_update = match self.events.last_update.load(Ordering::Relaxed) {
+            0 => None,
+            sec => Some(Timespec::new(sec as i64, 0)),
+        };
+
+        for guild in self.guilds.values() {
+            let guild = guild.read().unwrap();
+
+            stats.members += guild.members.len();
+            stats.presences += guild.presences.len();
+            stats.approximate_size += guild_size(&guild);
+        }
+
+        for channel in self.channels.values() {
+            let channel = channel.read().unwrap();
+
+            stats.approximate_size += mem::size_of::<GuildChannel>() + channel.name.len() +
+                channel.topic.as_ref().map_or(0, |topic| topic.len());
+        }
+
+        for messages in self.messages.values() {
+            stats.messages += messages.len();
+            stats.approximate_size += messages.iter()
+                .map(|message| mem::size_of::<Message>() + message.content.len())
+                .sum::<usize>();
+        }
+
+        for user in self.users.values() {
+            stats.approximate_size += mem::size_of::<User>() + user.read().unwrap().name.len();
+        }
+
+        stats.approximate_size += self.presences.len() * mem::size_of::<Presence>() +
+            self.groups.len() * mem::size_of::<Group>() +
+            self.private_channels.len() * mem::size_of::<PrivateChannel>();
+
+        stats
+    }
+
     /// Fetches a vector of all [`PrivateChannel`] and [`Group`] Ids that are
     /// stored in the cache.
     ///
@@ -248,10 +390,16 @@ impl Cache {
     /// [`Guild`]: ../../model/struct.Guild.html
     /// [`Shard`]: ../../client/gateway/struct.Shard.html
     pub fn all_guilds(&self) -> Vec<GuildId> {
+        // A guild restored from a snapshot can be both stored and unavailable.
+        let unavailable = self.unavailable_guilds
+            .iter()
+            .filter(|guild_id| !self.guilds.contains_key(guild_id))
+            .cloned();
+
         self.guilds
             .values()
             .map(|g| g.read().unwrap().id)
-            .chain(self.unavailable_guilds.iter().cloned())
+            .chain(unavailable)
             .collect()
     }
 
@@ -294,6 +442,18 @@ impl Cache {
         None
     }
 
+    /// Retrieves a [`Guild`]'s channels in the order that they are displayed
+    /// by the client, if the guild is cached.
+    ///
+    /// Refer to [`Guild::channels_by_position`] for more information.
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::channels_by_position`]: ../../model/struct.Guild.html#method.channels_by_position
+    pub fn channels_by_position<G: Into<GuildId>>(&self, guild_id: G)
+        -> Vec<Arc<RwLock<GuildChannel>>> {
+        self.query_guild(guild_id, |guild| guild.channels_by_position())
+    }
+
     /// Retrieves a guild from the cache based on the given Id.
     ///
     /// The only advantage of this method is that you can pass in anything that
@@ -407,6 +567,92 @@ impl Cache {
             .and_then(|guild| guild.write().unwrap().members.get(&user_id.into()).cloned())
     }
 
+    /// Retrieves clones of the [`Member`]s connected to a voice channel, if
+    /// its guild is cached.
+    ///
+    /// Refer to [`Guild::members_in_voice_channel`] for more information.
+    ///
+    /// [`Guild::members_in_voice_channel`]: ../../model/struct.Guild.html#method.members_in_voice_channel
+    /// [`Member`]: ../../model/struct.Member.html
+    pub fn members_in_voice_channel<C: Into<ChannelId>>(&self, channel_id: C) -> Vec<Member> {
+        let channel_id = channel_id.into();
+        let guild_id = match self.channels.get(&channel_id) {
+            Some(channel) => channel.read().unwrap().guild_id,
+            None => return vec![],
+        };
+
+        self.query_guild(guild_id, |guild| {
+            guild.members_in_voice_channel(channel_id).into_iter().cloned().collect()
+        })
+    }
+
+    /// Retrieves clones of a [`Guild`]'s [`Member`]s playing a game with a
+    /// name containing the given name, if the guild is cached.
+    ///
+    /// Refer to [`Guild::members_playing`] for more information.
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::members_playing`]: ../../model/struct.Guild.html#method.members_playing
+    /// [`Member`]: ../../model/struct.Member.html
+    pub fn members_playing<G: Into<GuildId>>(&self, guild_id: G, name: &str) -> Vec<Member> {
+        self.query_guild(guild_id, |guild| {
+            guild.members_playing(name).into_iter().cloned().collect()
+        })
+    }
+
+    /// Retrieves clones of a [`Guild`]'s [`Member`]s which have a [`Role`], if
+    /// the guild is cached.
+    ///
+    /// Refer to [`Guild::members_with_role`] for more information.
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::members_with_role`]: ../../model/struct.Guild.html#method.members_with_role
+    /// [`Member`]: ../../model/struct.Member.html
+    /// [`Role`]: ../../model/struct.Role.html
+    pub fn members_with_role<G, R>(&self, guild_id: G, role_id: R) -> Vec<Member>
+        where G: Into<GuildId>, R: Into<RoleId> {
+        let role_id = role_id.into();
+
+        self.query_guild(guild_id, |guild| {
+            guild.members_with_role(role_id).into_iter().cloned().collect()
+        })
+    }
+
+    /// Retrieves clones of a [`Guild`]'s [`Member`]s which have an
+    /// [`OnlineStatus`], if the guild is cached.
+    ///
+    /// Refer to [`Guild::members_with_status`] for more information.
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::members_with_status`]: ../../model/struct.Guild.html#method.members_with_status
+    /// [`Member`]: ../../model/struct.Member.html
+    /// [`OnlineStatus`]: ../../model/enum.OnlineStatus.html
+    pub fn members_with_status<G>(&self, guild_id: G, status: OnlineStatus) -> Vec<Member>
+        where G: Into<GuildId> {
+        self.query_guild(guild_id, |guild| {
+            guild.members_with_status(status).into_iter().cloned().collect()
+        })
+    }
+
+    /// Retrieves a [`Message`] from the cache's [`messages`] map, if it exists
+    /// and has not expired.
+    ///
+    /// [`Message`]: ../../model/struct.Message.html
+    /// [`messages`]: #structfield.messages
+    pub fn message<C, M>(&self, channel_id: C, message_id: M) -> Option<Message>
+        where C: Into<ChannelId>, M: Into<MessageId> {
+        let message_id = message_id.into();
+
+        self.messages
+            .get(&channel_id.into())
+            .and_then(|messages| messages.iter().find(|message| message.id == message_id))
+            .and_then(|message| if self.message_config.is_expired(message) {
+                None
+            } else {
+                Some(message.clone())
+            })
+    }
+
     /// Retrieves a [`PrivateChannel`] from the cache's [`private_channels`]
     /// map, if it exists.
     ///
@@ -432,6 +678,51 @@ impl Cache {
             .and_then(|g| g.read().unwrap().roles.get(&role_id.into()).cloned())
     }
 
+    /// Retrieves clones of a [`Guild`]'s roles in order of their hierarchy, if
+    /// the guild is cached.
+    ///
+    /// Refer to [`Guild::roles_by_position`] for more information.
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::roles_by_position`]: ../../model/struct.Guild.html#method.roles_by_position
+    pub fn roles_by_position<G: Into<GuildId>>(&self, guild_id: G) -> Vec<Role> {
+        self.query_guild(guild_id, |guild| {
+            guild.roles_by_position().into_iter().cloned().collect()
+        })
+    }
+
+    /// Searches a [`Guild`]'s [`Member`]s by name, if the guild is cached,
+    /// returning clones of the members found.
+    ///
+    /// Refer to [`Guild::search_members`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// Reply with the closest match for a name given to a command:
+    ///
+    /// ```rust,ignore
+    /// use serenity::client::CACHE;
+    ///
+    /// // assumes a `guild_id`, `message` and `name` have already been bound
+    /// let found = CACHE.read().unwrap().search_members(guild_id, name);
+    ///
+    /// let reply = match found.first() {
+    ///     Some(member) => format!("Found {}", member.distinct()),
+    ///     None => "No member found".to_owned(),
+    /// };
+    ///
+    /// let _ = message.channel_id.say(&reply);
+    /// ```
+    ///
+    /// [`Guild`]: ../../model/struct.Guild.html
+    /// [`Guild::search_members`]: ../../model/struct.Guild.html#method.search_members
+    /// [`Member`]: ../../model/struct.Member.html
+    pub fn search_members<G: Into<GuildId>>(&self, guild_id: G, query: &str) -> Vec<Member> {
+        self.query_guild(guild_id, |guild| {
+            guild.search_members(query).into_iter().cloned().collect()
+        })
+    }
+
     /// Retrieves a `User` from the cache's [`users`] map, if it exists.
     ///
     /// The only advantage of this method is that you can pass in anything that
@@ -444,6 +735,165 @@ impl Cache {
         self.users.get(&user_id.into()).cloned()
     }
 
+    /// Writes a snapshot of the cache to a file, which can be loaded via
+    /// [`load_from`] - such as on the next start of the bot.
+    ///
+    /// The snapshot contains the guilds - including their channels, roles,
+    /// members, presences and voice states - along with the private channels,
+    /// groups, presences and users. Messages and notes are not included.
+    ///
+    /// # Examples
+    ///
+    /// Save the cache when the bot is shutting down, so that it is not empty
+    /// on the next start:
+    ///
+    /// ```rust,no_run
+    /// use serenity::client::CACHE;
+    ///
+    /// if let Err(why) = CACHE.read().unwrap().save_to("cache.json") {
+    ///     println!("Error saving the cache: {:?}", why);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`] if the file could not be written to, or an
+    /// [`Error::Json`] if the cache could not be serialized.
+    ///
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`load_from`]: #method.load_from
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        // Hold the locks for the duration of the write, so that the snapshot
+        // is consistent.
+        let groups = self.groups.values().map(|x| x.read().unwrap()).collect::<Vec<_>>();
+        let guilds = self.guilds.values().map(|x| x.read().unwrap()).collect::<Vec<_>>();
+        let private_channels = self.private_channels
+            .values()
+            .map(|x| x.read().unwrap())
+            .collect::<Vec<_>>();
+        let users = self.users.values().map(|x| x.read().unwrap()).collect::<Vec<_>>();
+
+        let snapshot = SnapshotRef {
+            groups: groups.iter().map(|x| &**x).collect(),
+            guilds: guilds.iter().map(|x| &**x).collect(),
+            presences: self.presences.values().collect(),
+            private_channels: private_channels.iter().map(|x| &**x).collect(),
+            shard_count: self.shard_count,
+            unavailable_guilds: &self.unavailable_guilds,
+            user: &self.user,
+            users: users.iter().map(|x| &**x).collect(),
+        };
+
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), &snapshot)?;
+
+        Ok(())
+    }
+
+    /// Replaces the contents of the cache with a snapshot written by
+    /// [`save_to`].
+    ///
+    /// Guilds from the snapshot are kept when a [`Ready`][`ReadyEvent`] lists
+    /// them as unavailable, so that they can be retrieved until their
+    /// [`GuildCreate`][`GuildCreateEvent`] is received. Guilds which the
+    /// current user is no longer in are removed on the `Ready`.
+    ///
+    /// The [`config`] and [`message_config`] are not changed, and are applied
+    /// to the loaded data. The [`messages`] are cleared.
+    ///
+    /// Refer to [`Client::with_cache_snapshot`] to load a snapshot when the
+    /// client is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::Io`] if the file could not be read from, or an
+    /// [`Error::Json`] if it does not contain a snapshot.
+    ///
+    /// [`Client::with_cache_snapshot`]: ../../client/struct.Client.html#method.with_cache_snapshot
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Json`]: ../../enum.Error.html#variant.Json
+    /// [`GuildCreateEvent`]: ../../model/event/struct.GuildCreateEvent.html
+    /// [`ReadyEvent`]: ../../model/event/struct.ReadyEvent.html
+    /// [`config`]: #structfield.config
+    /// [`message_config`]: #structfield.message_config
+    /// [`messages`]: #structfield.messages
+    /// [`save_to`]: #method.save_to
+    pub fn load_from<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let file = File::open(path)?;
+        let snapshot: Snapshot = serde_json::from_reader(BufReader::new(file))?;
+
+        *self = Cache {
+            config: self.config.clone(),
+            message_config: self.message_config.clone(),
+            shard_count: snapshot.shard_count,
+            unavailable_guilds: snapshot.unavailable_guilds,
+            user: snapshot.user,
+            ..Cache::default()
+        };
+
+        for user in snapshot.users {
+            self.update_user_entry(&user);
+        }
+
+        for guild in snapshot.guilds {
+            self.insert_guild(guild);
+        }
+
+        for presence in snapshot.presences {
+            self.insert_presence(presence);
+        }
+
+        for group in snapshot.groups {
+            self.groups.insert(group.channel_id, Arc::new(RwLock::new(group)));
+        }
+
+        for channel in snapshot.private_channels {
+            self.private_channels.insert(channel.id, Arc::new(RwLock::new(channel)));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the users from the [`users`] map which are no longer
+    /// referenced by a guild's members or presences, a presence, a private
+    /// channel or a group, returning the number of users removed.
+    ///
+    /// The current user is never removed.
+    ///
+    /// This iterates over every guild, and so can be used periodically rather
+    /// than enabling [`CacheConfig::prune_users`].
+    ///
+    /// [`CacheConfig::prune_users`]: struct.CacheConfig.html#method.prune_users
+    /// [`users`]: #structfield.users
+    pub fn prune_users(&mut self) -> usize {
+        self.last_prune = Some(Instant::now());
+
+        let mut referenced = HashSet::new();
+        referenced.insert(self.user.id);
+        referenced.extend(self.presences.keys().cloned());
+
+        for guild in self.guilds.values() {
+            let guild = guild.read().unwrap();
+
+            referenced.extend(guild.members.keys().cloned());
+            referenced.extend(guild.presences.keys().cloned());
+        }
+
+        for channel in self.private_channels.values() {
+            referenced.insert(channel.read().unwrap().recipient.read().unwrap().id);
+        }
+
+        for group in self.groups.values() {
+            referenced.extend(group.read().unwrap().recipients.keys().cloned());
+        }
+
+        let count = self.users.len();
+        self.users.retain(|user_id, _| referenced.contains(user_id));
+
+        count - self.users.len()
+    }
+
     /// Alias of [`channel`].
     ///
     /// [`channel`]: #method.channel
@@ -511,6 +961,193 @@ impl Cache {
         self.user(id)
     }
 
+    /// Updates the cache with a received event, as is done by the library for
+    /// every event.
+    ///
+    /// This is primarily useful for keeping a separate instance of the cache,
+    /// such as one used as a [`CacheSink`].
+    ///
+    /// [`CacheSink`]: trait.CacheSink.html
+    pub fn update(&mut self, event: &Event) {
+        self.record_event(event);
+
+        match *event {
+            Event::ChannelCreate(ref event) => {
+                self.update_with_channel_create(event);
+            },
+            Event::ChannelDelete(ref event) => {
+                self.update_with_channel_delete(event);
+            },
+            Event::ChannelPinsUpdate(ref event) => self.update_with_channel_pins_update(event),
+            Event::ChannelRecipientAdd(ref event) => {
+                self.update_with_channel_recipient_add(&mut event.clone());
+            },
+            Event::ChannelRecipientRemove(ref event) => {
+                self.update_with_channel_recipient_remove(event);
+            },
+            Event::ChannelUpdate(ref event) => self.update_with_channel_update(event),
+            Event::GuildCreate(ref event) => self.update_with_guild_create(event),
+            Event::GuildDelete(ref event) => {
+                self.update_with_guild_delete(event);
+            },
+            Event::GuildEmojisUpdate(ref event) => self.update_with_guild_emojis_update(event),
+            Event::GuildMemberAdd(ref event) => {
+                self.update_with_guild_member_add(&mut event.clone());
+            },
+            Event::GuildMemberRemove(ref event) => {
+                self.update_with_guild_member_remove(event);
+            },
+            Event::GuildMemberUpdate(ref event) => {
+                self.update_with_guild_member_update(event);
+            },
+            Event::GuildMembersChunk(ref event) => self.update_with_guild_members_chunk(event),
+            Event::GuildRoleCreate(ref event) => self.update_with_guild_role_create(event),
+            Event::GuildRoleDelete(ref event) => {
+                self.update_with_guild_role_delete(event);
+            },
+            Event::GuildRoleUpdate(ref event) => {
+                self.update_with_guild_role_update(event);
+            },
+            Event::GuildUnavailable(ref event) => self.update_with_guild_unavailable(event),
+            Event::GuildUpdate(ref event) => self.update_with_guild_update(event),
+            Event::MessageCreate(ref event) => self.update_with_message_create(event),
+            Event::MessageDelete(ref event) => {
+                self.update_with_message_delete(event);
+            },
+            Event::MessageDeleteBulk(ref event) => {
+                self.update_with_message_delete_bulk(event);
+            },
+            Event::MessageUpdate(ref event) => {
+                self.update_with_message_update(event);
+            },
+            Event::PresenceUpdate(ref event) => {
+                self.update_with_presence_update(&mut event.clone());
+            },
+            Event::PresencesReplace(ref event) => self.update_with_presences_replace(event),
+            Event::Ready(ref event) => self.update_with_ready(event),
+            Event::UserUpdate(ref event) => {
+                self.update_with_user_update(event);
+            },
+            Event::VoiceStateUpdate(ref event) => self.update_with_voice_state_update(event),
+            _ => {},
+        }
+    }
+
+    /// Updates the cache with a received event while only borrowing it
+    /// immutably, locking just the guild or channel which the event applies
+    /// to.
+    ///
+    /// When the cache is behind a lock - such as the global [`CACHE`] - this
+    /// allows events for different guilds to be applied concurrently while
+    /// holding a read lock, rather than each requiring the write lock.
+    ///
+    /// Returns `false` without updating the cache if the event must instead be
+    /// applied via [`update`], such as when it adds a guild, a channel or a
+    /// new user.
+    ///
+    /// # Examples
+    ///
+    /// Apply an event to a cache behind a lock, only taking the write lock if
+    /// needed:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::model::event::Event;
+    /// #
+    /// # fn apply(event: Event) {
+    /// use serenity::client::CACHE;
+    ///
+    /// if !CACHE.read().unwrap().try_update(&event) {
+    ///     CACHE.write().unwrap().update(&event);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// [`CACHE`]: ../../client/struct.CACHE.html
+    /// [`update`]: #method.update
+    pub fn try_update(&self, event: &Event) -> bool {
+        let updated = match *event {
+            Event::ChannelPinsUpdate(ref event) => {
+                self.update_with_channel_pins_update(event);
+
+                true
+            },
+            Event::GuildEmojisUpdate(ref event) => {
+                self.update_with_guild_emojis_update(event);
+
+                true
+            },
+            Event::GuildMemberAdd(ref event) => {
+                self.try_update_with_guild_member_add(&mut event.clone()).is_some()
+            },
+            Event::GuildMemberUpdate(ref event) => {
+                self.try_update_with_guild_member_update(event).is_some()
+            },
+            Event::GuildRoleCreate(ref event) => {
+                self.update_with_guild_role_create(event);
+
+                true
+            },
+            Event::GuildRoleDelete(ref event) => {
+                self.update_with_guild_role_delete(event);
+
+                true
+            },
+            Event::GuildRoleUpdate(ref event) => {
+                self.update_with_guild_role_update(event);
+
+                true
+            },
+            Event::GuildUpdate(ref event) => {
+                self.update_with_guild_update(event);
+
+                true
+            },
+            Event::MessageCreate(ref event) => self.try_update_with_message_create(event).is_some(),
+            Event::PresenceUpdate(ref event) => {
+                self.try_update_with_presence_update(&mut event.clone()).is_some()
+            },
+            Event::VoiceStateUpdate(ref event) => {
+                self.update_with_voice_state_update(event);
+
+                true
+            },
+            Event::ChannelCreate(_) |
+            Event::ChannelDelete(_) |
+            Event::ChannelRecipientAdd(_) |
+            Event::ChannelRecipientRemove(_) |
+            Event::ChannelUpdate(_) |
+            Event::GuildCreate(_) |
+            Event::GuildDelete(_) |
+            Event::GuildMemberRemove(_) |
+            Event::GuildMembersChunk(_) |
+            Event::GuildUnavailable(_) |
+            Event::MessageDelete(_) |
+            Event::MessageDeleteBulk(_) |
+            Event::MessageUpdate(_) |
+            Event::PresencesReplace(_) |
+            Event::Ready(_) |
+            Event::UserUpdate(_) => false,
+            // The remaining events are not stored.
+            _ => true,
+        };
+
+        if updated {
+            self.record_event(event);
+        }
+
+        updated
+    }
+
+    // Counts an event towards the `stats`, if it is one which is stored. This
+    // is called by the library when it applies an event to the global cache.
+    #[doc(hidden)]
+    pub fn record_event(&self, event: &Event) {
+        if let Some(index) = event_index(event) {
+            self.events.counts[index].fetch_add(1, Ordering::Relaxed);
+            self.events.last_update.store(time::get_time().sec as usize, Ordering::Relaxed);
+        }
+    }
+
     #[doc(hidden)]
     pub fn update_with_channel_create(&mut self, event: &ChannelCreateEvent) -> Option<Channel> {
         match event.channel {
@@ -586,6 +1223,7 @@ impl Cache {
                 };
 
                 self.channels.remove(&channel_id);
+                self.messages.remove(&channel_id);
 
                 self.guilds
                     .get_mut(&guild_id)
@@ -596,20 +1234,20 @@ impl Cache {
     }
 
     #[doc(hidden)]
-    pub fn update_with_channel_pins_update(&mut self, event: &ChannelPinsUpdateEvent) {
+    pub fn update_with_channel_pins_update(&self, event: &ChannelPinsUpdateEvent) {
         if let Some(channel) = self.channels.get(&event.channel_id) {
             channel.write().unwrap().last_pin_timestamp = event.last_pin_timestamp.clone();
 
             return;
         }
 
-        if let Some(channel) = self.private_channels.get_mut(&event.channel_id) {
+        if let Some(channel) = self.private_channels.get(&event.channel_id) {
             channel.write().unwrap().last_pin_timestamp = event.last_pin_timestamp.clone();
 
             return;
         }
 
-        if let Some(group) = self.groups.get_mut(&event.channel_id) {
+        if let Some(group) = self.groups.get(&event.channel_id) {
             group.write().unwrap().last_pin_timestamp = event.last_pin_timestamp.clone();
 
             return;
@@ -695,43 +1333,43 @@ impl Cache {
     #[doc(hidden)]
     pub fn update_with_guild_create(&mut self, event: &GuildCreateEvent) {
         self.unavailable_guilds.remove(&event.guild.id);
-
-        let mut guild = event.guild.clone();
-
-        for (user_id, member) in &mut guild.members {
-            self.update_user_entry(&member.user.read().unwrap());
-            let user = self.users[user_id].clone();
-
-            member.user = user.clone();
-        }
-
-        self.channels.extend(guild.channels.clone());
-        self.guilds.insert(event.guild.id, Arc::new(RwLock::new(guild)));
+        self.insert_guild(event.guild.clone());
     }
 
     #[doc(hidden)]
     pub fn update_with_guild_delete(&mut self, event: &GuildDeleteEvent)
         -> Option<Arc<RwLock<Guild>>> {
-        // Remove channel entries for the guild if the guild is found.
-        self.guilds.remove(&event.guild.id).map(|guild| {
-            for channel_id in guild.read().unwrap().channels.keys() {
-                self.channels.remove(channel_id);
-            }
+        let guild = self.remove_guild(event.guild.id);
+        self.prune_users_if_due();
 
-            guild
-        })
+        guild
     }
 
     #[doc(hidden)]
-    pub fn update_with_guild_emojis_update(&mut self, event: &GuildEmojisUpdateEvent) {
+    pub fn update_with_guild_emojis_update(&self, event: &GuildEmojisUpdateEvent) {
         self.guilds
-            .get_mut(&event.guild_id)
+            .get(&event.guild_id)
             .map(|guild| guild.write().unwrap().emojis.extend(event.emojis.clone()));
     }
 
     #[doc(hidden)]
     pub fn update_with_guild_member_add(&mut self, event: &mut GuildMemberAddEvent) {
         let user_id = event.member.user.read().unwrap().id;
+
+        let store = match self.guilds.get(&event.guild_id) {
+            Some(guild) => {
+                let mut guild = guild.write().unwrap();
+                guild.member_count += 1;
+
+                self.can_store_member(&guild, user_id)
+            },
+            None => true,
+        };
+
+        if !store {
+            return;
+        }
+
         self.update_user_entry(&event.member.user.read().unwrap());
 
         // Always safe due to being inserted above.
@@ -739,98 +1377,157 @@ impl Cache {
 
         self.guilds
             .get_mut(&event.guild_id)
-            .map(|guild| {
-                let mut guild = guild.write().unwrap();
+            .map(|guild| guild.write().unwrap().members.insert(user_id, event.member.clone()));
+    }
 
-                guild.member_count += 1;
-                guild.members.insert(user_id, event.member.clone());
-            });
+    // Adds a member whose user is already stored - or which is not stored -
+    // without adding to the cache's own maps. Returns `None` without updating
+    // anything otherwise.
+    #[doc(hidden)]
+    pub fn try_update_with_guild_member_add(&self, event: &mut GuildMemberAddEvent)
+        -> Option<()> {
+        let user_id = event.member.user.read().unwrap().id;
+
+        let mut guild = match self.guilds.get(&event.guild_id) {
+            Some(guild) => guild.write().unwrap(),
+            None => return None,
+        };
+
+        if self.can_store_member(&guild, user_id) {
+            let user = match self.users.get(&user_id) {
+                Some(user) => user,
+                None => return None,
+            };
+
+            user.write().unwrap().clone_from(&event.member.user.read().unwrap());
+            event.member.user = user.clone();
+
+            guild.members.insert(user_id, event.member.clone());
+        }
+
+        guild.member_count += 1;
+
+        Some(())
     }
 
     #[doc(hidden)]
     pub fn update_with_guild_member_remove(&mut self, event: &GuildMemberRemoveEvent)
         -> Option<Member> {
-        self.guilds
+        let member = self.guilds
             .get_mut(&event.guild_id)
             .and_then(|guild| {
                 let mut guild = guild.write().unwrap();
 
                 guild.member_count -= 1;
                 guild.members.remove(&event.user.id)
-            })
-    }
+            });
+
+        self.prune_users_if_due();
 
+        member
+    }
+
+    // Returns the member before the update - if it was stored - and after it.
     #[doc(hidden)]
     pub fn update_with_guild_member_update(&mut self, event: &GuildMemberUpdateEvent)
-        -> Option<Member> {
+        -> (Option<Member>, Member) {
+        if let Some(update) = self.try_update_with_guild_member_update(event) {
+            return update;
+        }
+
+        // The member is new, and their user is not yet stored.
         self.update_user_entry(&event.user);
 
-        if let Some(guild) = self.guilds.get_mut(&event.guild_id) {
-            let mut guild = guild.write().unwrap();
+        let mut after = updated_member(event);
+        after.user = self.users[&event.user.id].clone();
 
-            let mut found = false;
+        if let Some(guild) = self.guilds.get(&event.guild_id) {
+            guild.write().unwrap().members.insert(event.user.id, after.clone());
+        }
 
-            let item = if let Some(member) = guild.members.get_mut(&event.user.id) {
-                let item = Some(member.clone());
+        (None, after)
+    }
 
-                member.nick.clone_from(&event.nick);
-                member.roles.clone_from(&event.roles);
-                member.user.write().unwrap().clone_from(&event.user);
+    // Updates a member without adding to the cache's own maps. Returns `None`
+    // without updating anything if the member is new and their user must be
+    // added to the [`users`] map.
+    //
+    // [`users`]: #structfield.users
+    #[doc(hidden)]
+    pub fn try_update_with_guild_member_update(&self, event: &GuildMemberUpdateEvent)
+        -> Option<(Option<Member>, Member)> {
+        let mut guild = match self.guilds.get(&event.guild_id) {
+            Some(guild) => guild.write().unwrap(),
+            None => return Some((None, updated_member(event))),
+        };
 
-                found = true;
+        if let Some(member) = guild.members.get_mut(&event.user.id) {
+            let before = member.clone();
 
-                item
-            } else {
-                None
+            member.nick.clone_from(&event.nick);
+            member.roles.clone_from(&event.roles);
+            member.user.write().unwrap().clone_from(&event.user);
+
+            return Some((Some(before), member.clone()));
+        }
+
+        let mut after = updated_member(event);
+
+        if self.can_store_member(&guild, event.user.id) {
+            let user = match self.users.get(&event.user.id) {
+                Some(user) => user,
+                None => return None,
             };
 
-            if !found {
-                guild.members.insert(event.user.id, Member {
-                    deaf: false,
-                    guild_id: Some(event.guild_id),
-                    joined_at: String::default(),
-                    mute: false,
-                    nick: event.nick.clone(),
-                    roles: event.roles.clone(),
-                    user: Arc::new(RwLock::new(event.user.clone())),
-                });
-            }
+            user.write().unwrap().clone_from(&event.user);
+            after.user = user.clone();
 
-            item
-        } else {
-            None
+            guild.members.insert(event.user.id, after.clone());
         }
+
+        Some((None, after))
     }
 
     #[doc(hidden)]
     pub fn update_with_guild_members_chunk(&mut self, event: &GuildMembersChunkEvent) {
-        for member in event.members.values() {
+        let guild = match self.guilds.get(&event.guild_id) {
+            Some(guild) => guild.clone(),
+            None => return,
+        };
+        let mut guild = guild.write().unwrap();
+
+        for (user_id, member) in &event.members {
+            if !guild.members.contains_key(user_id) && !self.can_store_member(&guild, *user_id) {
+                continue;
+            }
+
             self.update_user_entry(&member.user.read().unwrap());
-        }
 
-        self.guilds
-            .get_mut(&event.guild_id)
-            .map(|guild| guild.write().unwrap().members.extend(event.members.clone()));
+            let mut member = member.clone();
+            member.user = self.users[user_id].clone();
+
+            guild.members.insert(*user_id, member);
+        }
     }
 
     #[doc(hidden)]
-    pub fn update_with_guild_role_create(&mut self, event: &GuildRoleCreateEvent) {
+    pub fn update_with_guild_role_create(&self, event: &GuildRoleCreateEvent) {
         self.guilds
-            .get_mut(&event.guild_id)
+            .get(&event.guild_id)
             .map(|guild| guild.write().unwrap().roles.insert(event.role.id, event.role.clone()));
     }
 
     #[doc(hidden)]
-    pub fn update_with_guild_role_delete(&mut self, event: &GuildRoleDeleteEvent) -> Option<Role> {
+    pub fn update_with_guild_role_delete(&self, event: &GuildRoleDeleteEvent) -> Option<Role> {
         self.guilds
-            .get_mut(&event.guild_id)
+            .get(&event.guild_id)
             .and_then(|guild| guild.write().unwrap().roles.remove(&event.role_id))
     }
 
     #[doc(hidden)]
-    pub fn update_with_guild_role_update(&mut self, event: &GuildRoleUpdateEvent) -> Option<Role> {
+    pub fn update_with_guild_role_update(&self, event: &GuildRoleUpdateEvent) -> Option<Role> {
         self.guilds
-            .get_mut(&event.guild_id)
+            .get(&event.guild_id)
             .and_then(|guild| {
                 guild.write()
                     .unwrap()
@@ -844,12 +1541,13 @@ impl Cache {
     pub fn update_with_guild_unavailable(&mut self, event: &GuildUnavailableEvent) {
         self.unavailable_guilds.insert(event.guild_id);
         self.guilds.remove(&event.guild_id);
+        self.prune_users_if_due();
     }
 
     #[doc(hidden)]
-    pub fn update_with_guild_update(&mut self, event: &GuildUpdateEvent) {
+    pub fn update_with_guild_update(&self, event: &GuildUpdateEvent) {
         self.guilds
-            .get_mut(&event.guild.id)
+            .get(&event.guild.id)
             .map(|guild| {
                 let mut guild = guild.write().unwrap();
 
@@ -865,25 +1563,181 @@ impl Cache {
     }
 
     #[doc(hidden)]
-    pub fn update_with_presences_replace(&mut self, event: &PresencesReplaceEvent) {
-        self.presences.extend({
-            let mut p: HashMap<UserId, Presence> = HashMap::default();
+    pub fn update_with_message_create(&mut self, event: &MessageCreateEvent) {
+        let capacity = self.message_config.capacity;
+
+        if capacity == 0 {
+            return;
+        }
+
+        let config = &self.message_config;
+        let messages = self.messages
+            .entry(event.message.channel_id)
+            .or_insert_with(VecDeque::new);
+
+        messages.retain(|message| message.id != event.message.id && !config.is_expired(message));
+        messages.push_back(event.message.clone());
 
-            for presence in &event.presences {
-                p.insert(presence.user_id, presence.clone());
+        while messages.len() > capacity {
+            messages.pop_front();
+        }
+    }
+
+    // Messages are not stored while the message cache is disabled, in which
+    // case the cache does not need to be written to.
+    #[doc(hidden)]
+    pub fn try_update_with_message_create(&self, _: &MessageCreateEvent) -> Option<()> {
+        if self.message_config.capacity == 0 {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn update_with_message_delete(&mut self, event: &MessageDeleteEvent) -> Option<Message> {
+        let message_id = event.message_id;
+        let message = self.messages
+            .get_mut(&event.channel_id)
+            .and_then(|messages| {
+                messages.iter()
+                    .position(|message| message.id == message_id)
+                    .and_then(|index| messages.remove(index))
+            });
+
+        match message {
+            Some(ref message) if self.message_config.is_expired(message) => None,
+            message => message,
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn update_with_message_delete_bulk(&mut self, event: &MessageDeleteBulkEvent)
+        -> Vec<Message> {
+        let config = &self.message_config;
+        let mut deleted = vec![];
+
+        if let Some(messages) = self.messages.get_mut(&event.channel_id) {
+            let mut kept = VecDeque::with_capacity(messages.len());
+
+            for message in messages.drain(..) {
+                if !event.ids.contains(&message.id) {
+                    kept.push_back(message);
+                } else if !config.is_expired(&message) {
+                    deleted.push(message);
+                }
             }
 
-            p
-        });
+            *messages = kept;
+        }
+
+        deleted
+    }
+
+    #[doc(hidden)]
+    pub fn update_with_message_update(&mut self, event: &MessageUpdateEvent) -> Option<Message> {
+        let messages = match self.messages.get_mut(&event.channel_id) {
+            Some(messages) => messages,
+            None => return None,
+        };
+
+        let mut message = match messages.iter().position(|message| message.id == event.id) {
+            Some(index) => messages.remove(index).unwrap(),
+            None => return None,
+        };
+
+        if self.message_config.is_expired(&message) {
+            return None;
+        }
+
+        let old = message.clone();
+
+        if let Some(kind) = event.kind {
+            message.kind = kind;
+        }
+
+        if let Some(ref content) = event.content {
+            message.content.clone_from(content);
+        }
+
+        if event.nonce.is_some() {
+            message.nonce.clone_from(&event.nonce);
+        }
+
+        if let Some(tts) = event.tts {
+            message.tts = tts;
+        }
+
+        if let Some(pinned) = event.pinned {
+            message.pinned = pinned;
+        }
+
+        if let Some(ref timestamp) = event.timestamp {
+            message.timestamp.clone_from(timestamp);
+        }
+
+        if event.edited_timestamp.is_some() {
+            message.edited_timestamp.clone_from(&event.edited_timestamp);
+        }
+
+        if let Some(ref author) = event.author {
+            message.author.clone_from(author);
+        }
+
+        if let Some(mention_everyone) = event.mention_everyone {
+            message.mention_everyone = mention_everyone;
+        }
+
+        if let Some(ref mentions) = event.mentions {
+            message.mentions.clone_from(mentions);
+        }
+
+        if let Some(ref mention_roles) = event.mention_roles {
+            message.mention_roles.clone_from(mention_roles);
+        }
+
+        if let Some(ref attachments) = event.attachments {
+            message.attachments.clone_from(attachments);
+        }
+
+        if let Some(ref embeds) = event.embeds {
+            message.embeds = embeds.iter()
+                .filter_map(|embed| serde_json::from_value(embed.clone()).ok())
+                .collect();
+        }
+
+        // The updated message is now the most recently used.
+        messages.push_back(message);
+
+        Some(old)
+    }
+
+    #[doc(hidden)]
+    pub fn update_with_presences_replace(&mut self, event: &PresencesReplaceEvent) {
+        for presence in &event.presences {
+            self.insert_presence(presence.clone());
+        }
     }
 
     #[doc(hidden)]
     pub fn update_with_presence_update(&mut self, event: &mut PresenceUpdateEvent) {
         let user_id = event.presence.user_id;
+        let offline = event.presence.status == OnlineStatus::Offline;
+        let max = self.config.max_presences;
+
+        let store = self.config.presences && !offline && match event.guild_id {
+            Some(guild_id) => self.guilds
+                .get(&guild_id)
+                .map_or(false, |guild| has_room(&guild.read().unwrap().presences, user_id, max)),
+            None => has_room(&self.presences, user_id, max),
+        };
 
         if let Some(user) = event.presence.user.as_mut() {
-            self.update_user_entry(&user.read().unwrap());
-            *user = self.users[&user_id].clone();
+            // Only keep known users up to date if the presence is not stored.
+            if store || self.users.contains_key(&user_id) {
+                self.update_user_entry(&user.read().unwrap());
+                *user = self.users[&user_id].clone();
+            }
         }
 
         if let Some(guild_id) = event.guild_id {
@@ -891,27 +1745,89 @@ impl Cache {
                 let mut guild = guild.write().unwrap();
 
                 // If the member went offline, remove them from the presence list.
-                if event.presence.status == OnlineStatus::Offline {
+                if offline {
                     guild.presences.remove(&event.presence.user_id);
-                } else {
+                } else if store {
                     guild.presences.insert(event.presence.user_id, event.presence.clone());
                 }
             }
-        } else if event.presence.status == OnlineStatus::Offline {
+        } else if offline {
             self.presences.remove(&event.presence.user_id);
-        } else {
+        } else if store {
             self.presences.insert(event.presence.user_id, event.presence.clone());
         }
     }
 
+    // Applies a guild's presence update without adding to the cache's own
+    // maps. Returns `None` without updating anything if the presence is not a
+    // guild's, or if it is stored and its user must be added to the [`users`]
+    // map.
+    //
+    // [`users`]: #structfield.users
+    #[doc(hidden)]
+    pub fn try_update_with_presence_update(&self, event: &mut PresenceUpdateEvent)
+        -> Option<()> {
+        let guild_id = match event.guild_id {
+            Some(guild_id) => guild_id,
+            None => return None,
+        };
+        let user_id = event.presence.user_id;
+        let offline = event.presence.status == OnlineStatus::Offline;
+
+        let mut guild = self.guilds.get(&guild_id).map(|guild| guild.write().unwrap());
+
+        let store = self.config.presences && !offline && guild.as_ref().map_or(false, |guild| {
+            has_room(&guild.presences, user_id, self.config.max_presences)
+        });
+
+        if let Some(user) = event.presence.user.as_mut() {
+            // Only keep known users up to date if the presence is not stored.
+            match self.users.get(&user_id) {
+                Some(entry) => {
+                    entry.write().unwrap().clone_from(&user.read().unwrap());
+                    *user = entry.clone();
+                },
+                None if store => return None,
+                None => {},
+            }
+        }
+
+        if let Some(ref mut guild) = guild {
+            // If the member went offline, remove them from the presence list.
+            if offline {
+                guild.presences.remove(&user_id);
+            } else if store {
+                guild.presences.insert(user_id, event.presence.clone());
+            }
+        }
+
+        Some(())
+    }
+
     #[doc(hidden)]
     pub fn update_with_ready(&mut self, event: &ReadyEvent) {
-        let mut ready = event.ready.clone();
+        let ready = event.ready.clone();
+
+        // Remove the guilds of the shard which the current user is no longer
+        // in, such as those restored from a snapshot.
+        let guild_ids = ready.guilds.iter().map(|guild| guild.id()).collect::<HashSet<_>>();
+        let removed = self.guilds
+            .keys()
+            .filter(|guild_id| !guild_ids.contains(guild_id))
+            .filter(|guild_id| ready.shard.map_or(true, |shard| {
+                (guild_id.0 >> 22) % shard[1] == shard[0]
+            }))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for guild_id in removed {
+            self.remove_guild(guild_id);
+        }
 
         for guild in ready.guilds {
             match guild {
+                // The guild's data - if any - is kept until it is available.
                 GuildStatus::Offline(unavailable) => {
-                    self.guilds.remove(&unavailable.id);
                     self.unavailable_guilds.insert(unavailable.id);
                 },
                 GuildStatus::OnlineGuild(guild) => {
@@ -936,15 +1852,10 @@ impl Cache {
             }
         }
 
-        for (user_id, presence) in &mut ready.presences {
-            if let Some(ref user) = presence.user {
-                self.update_user_entry(&user.read().unwrap());
-            }
-
-            presence.user = self.users.get(user_id).cloned();
+        for (_, presence) in ready.presences {
+            self.insert_presence(presence);
         }
 
-        self.presences.extend(ready.presences);
         self.shard_count = ready.shard.map_or(1, |s| s[1]);
         self.user = ready.user;
     }
@@ -955,9 +1866,13 @@ impl Cache {
     }
 
     #[doc(hidden)]
-    pub fn update_with_voice_state_update(&mut self, event: &VoiceStateUpdateEvent) {
+    pub fn update_with_voice_state_update(&self, event: &VoiceStateUpdateEvent) {
+        if !self.config.voice_states {
+            return;
+        }
+
         if let Some(guild_id) = event.guild_id {
-            if let Some(guild) = self.guilds.get_mut(&guild_id) {
+            if let Some(guild) = self.guilds.get(&guild_id) {
                 let mut guild = guild.write().unwrap();
 
                 if event.voice_state.channel_id.is_some() {
@@ -983,6 +1898,120 @@ impl Cache {
         }
     }
 
+    // Adds a guild, with its members' users linked to the [`users`] map and
+    // its channels added to the [`channels`] map.
+    //
+    // [`channels`]: #structfield.channels
+    // [`users`]: #structfield.users
+    fn insert_guild(&mut self, mut guild: Guild) {
+        self.filter_guild(&mut guild);
+
+        for (user_id, member) in &mut guild.members {
+            self.update_user_entry(&member.user.read().unwrap());
+            let user = self.users[user_id].clone();
+
+            member.user = user.clone();
+        }
+
+        self.channels.extend(guild.channels.clone());
+        self.guilds.insert(guild.id, Arc::new(RwLock::new(guild)));
+    }
+
+    // Removes the data from a received guild which the [`config`] does not
+    // store.
+    //
+    // [`config`]: #structfield.config
+    fn filter_guild(&self, guild: &mut Guild) {
+        let current_user_id = self.user.id;
+
+        if !self.config.presences {
+            guild.presences.clear();
+        } else if let Some(max) = self.config.max_presences {
+            truncate(&mut guild.presences, max, current_user_id);
+        }
+
+        if !self.config.voice_states {
+            guild.voice_states.clear();
+        }
+
+        if guild.large && !self.config.large_guild_members {
+            guild.members.retain(|user_id, _| *user_id == current_user_id);
+        } else if let Some(max) = self.config.max_members {
+            truncate(&mut guild.members, max, current_user_id);
+        }
+    }
+
+    // Whether a member which is not stored may be added to a guild. The
+    // current user's member is always stored, as it is needed to calculate
+    // permissions.
+    fn can_store_member(&self, guild: &Guild, user_id: UserId) -> bool {
+        if user_id == self.user.id {
+            return true;
+        }
+
+        if guild.large && !self.config.large_guild_members {
+            return false;
+        }
+
+        has_room(&guild.members, user_id, self.config.max_members)
+    }
+
+    // Adds a presence to the [`presences`] map - if stored - with its user
+    // linked to the [`users`] map.
+    //
+    // [`presences`]: #structfield.presences
+    // [`users`]: #structfield.users
+    fn insert_presence(&mut self, mut presence: Presence) {
+        if !self.config.presences ||
+           !has_room(&self.presences, presence.user_id, self.config.max_presences) {
+            return;
+        }
+
+        if let Some(ref user) = presence.user {
+            self.update_user_entry(&user.read().unwrap());
+        }
+
+        presence.user = self.users.get(&presence.user_id).cloned();
+        self.presences.insert(presence.user_id, presence);
+    }
+
+    // Prunes the users which may no longer be referenced after a removal, if
+    // enabled and the configured interval has passed since they were last
+    // pruned. Otherwise they are left for a later removal to prune.
+    fn prune_users_if_due(&mut self) {
+        if !self.config.prune_users {
+            return;
+        }
+
+        let interval = self.config.prune_interval;
+        let due = self.last_prune.map_or(true, |last| last.elapsed() >= interval);
+
+        if due {
+            self.prune_users();
+        }
+    }
+
+    // Runs a query on a guild, returning no results if it is not cached.
+    fn query_guild<G, F, T>(&self, guild_id: G, f: F) -> Vec<T>
+        where G: Into<GuildId>, F: FnOnce(&Guild) -> Vec<T> {
+        match self.guilds.get(&guild_id.into()) {
+            Some(guild) => f(&guild.read().unwrap()),
+            None => vec![],
+        }
+    }
+
+    // Removes a guild, along with its channels and their messages.
+    fn remove_guild(&mut self, guild_id: GuildId) -> Option<Arc<RwLock<Guild>>> {
+        self.guilds.remove(&guild_id).map(|guild| {
+            for channel_id in guild.read().unwrap().channels.keys() {
+                self.channels.remove(channel_id);
+                self.messages.remove(channel_id);
+            }
+
+            guild
+        })
+    }
+
     // Adds or updates a user entry in the [`users`] map with a received user.
     //
     // [`users`]: #structfield.users
@@ -1002,8 +2031,13 @@ impl Default for Cache {
     fn default() -> Cache {
         Cache {
             channels: HashMap::default(),
+            config: CacheConfig::default(),
+            events: EventTally::default(),
             groups: HashMap::default(),
             guilds: HashMap::default(),
+            last_prune: None,
+            message_config: MessageCacheConfig::default(),
+            messages: HashMap::default(),
             notes: HashMap::default(),
             presences: HashMap::default(),
             private_channels: HashMap::default(),
@@ -1023,3 +2057,426 @@ impl Default for Cache {
         }
     }
 }
+
+/// The settings of the [`Cache`]'s storage of messages.
+///
+/// Refer to [`Client::with_message_cache`] for more information.
+///
+/// [`Cache`]: struct.Cache.html
+/// [`Client::with_message_cache`]: ../../client/struct.Client.html#method.with_message_cache
+#[derive(Clone, Debug, Default)]
+pub struct MessageCacheConfig {
+    #[doc(hidden)]
+    pub capacity: usize,
+    #[doc(hidden)]
+    pub max_age: Option<Duration>,
+}
+
+impl MessageCacheConfig {
+    /// The maximum number of messages to store per channel. Once a channel is
+    /// full, its least recently received or updated message is evicted.
+    ///
+    /// Defaults to `0`, which disables the message cache.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+
+        self
+    }
+
+    /// The maximum age of a stored message, based on when it was created.
+    /// Older messages are evicted, and are not returned by lookups.
+    ///
+    /// Defaults to no maximum age.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+
+        self
+    }
+
+    fn is_expired(&self, message: &Message) -> bool {
+        self.max_age.map_or(false, |max_age| {
+            let age = time::get_time().sec - message.id.created_at().sec;
+
+            age > max_age.as_secs() as i64
+        })
+    }
+}
+
+/// The settings of which data the [`Cache`] stores.
+///
+/// Everything is stored by default, and users are never removed. Refer to
+/// [`Client::with_cache_config`] for more information.
+///
+/// [`Cache`]: struct.Cache.html
+/// [`Client::with_cache_config`]: ../../client/struct.Client.html#method.with_cache_config
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    #[doc(hidden)]
+    pub large_guild_members: bool,
+    #[doc(hidden)]
+    pub max_members: Option<usize>,
+    #[doc(hidden)]
+    pub max_presences: Option<usize>,
+    #[doc(hidden)]
+    pub negative_ttl: Duration,
+    #[doc(hidden)]
+    pub presences: bool,
+    #[doc(hidden)]
+    pub prune_interval: Duration,
+    #[doc(hidden)]
+    pub prune_users: bool,
+    #[doc(hidden)]
+    pub voice_states: bool,
+}
+
+impl CacheConfig {
+    /// Whether to store the members of guilds which Discord considers
+    /// [`large`], other than the current user's member.
+    ///
+    /// Members of large guilds are otherwise received over time, such as when
+    /// they send a message or are requested via [`Shard::chunk_guilds`].
+    ///
+    /// Defaults to `true`.
+    ///
+    /// [`Shard::chunk_guilds`]: ../../client/gateway/struct.Shard.html#method.chunk_guilds
+    /// [`large`]: ../../model/struct.Guild.html#structfield.large
+    pub fn large_guild_members(mut self, large_guild_members: bool) -> Self {
+        self.large_guild_members = large_guild_members;
+
+        self
+    }
+
+    /// The maximum number of members to store per guild. Once a guild is full,
+    /// new members are not stored.
+    ///
+    /// Defaults to no maximum.
+    pub fn max_members(mut self, max_members: usize) -> Self {
+        self.max_members = Some(max_members);
+
+        self
+    }
+
+    /// The maximum number of presences to store per guild, and in the
+    /// [`Cache`]'s own map of presences. Once full, new presences are not
+    /// stored.
+    ///
+    /// Defaults to no maximum.
+    ///
+    /// [`Cache`]: struct.Cache.html
+    pub fn max_presences(mut self, max_presences: usize) -> Self {
+        self.max_presences = Some(max_presences);
+
+        self
+    }
+
+    /// How long to remember that an item does not exist after a [`lookup`]
+    /// requested it, during which further lookups of it do not make a request.
+    ///
+    /// Defaults to 60 seconds.
+    ///
+    /// [`lookup`]: lookup/index.html
+    pub fn negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+
+        self
+    }
+
+    /// Whether to store presences.
+    ///
+    /// Defaults to `true`.
+    pub fn presences(mut self, presences: bool) -> Self {
+        self.presences = presences;
+
+        self
+    }
+
+    /// The minimum time between prunes of the users which are no longer
+    /// referenced, when enabled via [`prune_users`].
+    ///
+    /// A removal within this time of the last prune leaves its users to be
+    /// pruned by the next removal after it, so that many removals in a short
+    /// time - such as guilds becoming unavailable during an outage - do not
+    /// each check every user.
+    ///
+    /// Defaults to 60 seconds.
+    ///
+    /// [`prune_users`]: #method.prune_users
+    pub fn prune_interval(mut self, prune_interval: Duration) -> Self {
+        self.prune_interval = prune_interval;
+
+        self
+    }
+
+    /// Whether to remove users which are no longer referenced from the
+    /// [`Cache`] when a member is removed from a guild, or a guild is removed
+    /// or becomes unavailable.
+    ///
+    /// Pruning checks every user, so is done at most once per
+    /// [`prune_interval`]. It may instead be done periodically via
+    /// [`Cache::prune_users`].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`Cache`]: struct.Cache.html
+    /// [`Cache::prune_users`]: struct.Cache.html#method.prune_users
+    /// [`prune_interval`]: #method.prune_interval
+    pub fn prune_users(mut self, prune_users: bool) -> Self {
+        self.prune_users = prune_users;
+
+        self
+    }
+
+    /// Whether to store voice states.
+    ///
+    /// **Note**: The events derived from voice states - such as
+    /// [`Client::on_voice_channel_join`] - are not dispatched if this is
+    /// disabled.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// [`Client::on_voice_channel_join`]: ../../client/struct.Client.html#method.on_voice_channel_join
+    pub fn voice_states(mut self, voice_states: bool) -> Self {
+        self.voice_states = voice_states;
+
+        self
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> CacheConfig {
+        CacheConfig {
+            large_guild_members: true,
+            max_members: None,
+            max_presences: None,
+            negative_ttl: Duration::from_secs(60),
+            presences: true,
+            prune_interval: Duration::from_secs(60),
+            prune_users: false,
+            voice_states: true,
+        }
+    }
+}
+
+/// The sizes of the [`Cache`]'s collections and the events applied to it, as
+/// retrieved via [`Cache::stats`].
+///
+/// [`Cache`]: struct.Cache.html
+/// [`Cache::stats`]: struct.Cache.html#method.stats
+#[derive(Clone, Debug)]
+pub struct CacheStats {
+    /// An estimate of the memory used by the cache in bytes.
+    ///
+    /// This counts the size of each stored entry and of its larger strings,
+    /// such as names and message contents, but not the overhead of the maps
+    /// holding them. It is intended for tracking the cache's growth, rather
+    /// than as an exact figure.
+    pub approximate_size: usize,
+    /// The number of guild channels.
+    pub channels: usize,
+    /// The number of events of each kind which have been applied, keyed by
+    /// the name of the event as sent over the gateway, such as
+    /// `"GUILD_CREATE"`.
+    ///
+    /// Events which the cache does not store data from are not counted.
+    pub events: HashMap<&'static str, u64>,
+    /// The number of groups.
+    pub groups: usize,
+    /// The number of available guilds.
+    pub guilds: usize,
+    /// When the last event was applied, if any, to the second.
+    pub last_update: Option<Timespec>,
+    /// The number of members across all guilds.
+    pub members: usize,
+    /// The number of messages across all channels.
+    pub messages: usize,
+    /// The number of presences, both across all guilds and outside of them.
+    pub presences: usize,
+    /// The number of private channels.
+    pub private_channels: usize,
+    /// The number of unavailable guilds, including any guilds restored from a
+    /// snapshot which are also counted by [`guilds`].
+    ///
+    /// [`guilds`]: #structfield.guilds
+    pub unavailable_guilds: usize,
+    /// The number of users.
+    pub users: usize,
+}
+
+// The events applied to a cache, counted atomically so that events applied
+// under a read lock of the cache are also counted, without contending on a
+// lock. The counts are indexed as `EVENT_NAMES`, and the time of the last
+// update is in seconds, with `0` if there has been none.
+#[derive(Debug, Default)]
+struct EventTally {
+    counts: [AtomicUsize; 27],
+    last_update: AtomicUsize,
+}
+
+impl Clone for EventTally {
+    fn clone(&self) -> EventTally {
+        let tally = EventTally::default();
+
+        for (count, cloned) in self.counts.iter().zip(&tally.counts) {
+            cloned.store(count.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+
+        tally.last_update.store(self.last_update.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        tally
+    }
+}
+
+// The names of the events which the cache stores data from, as sent over the
+// gateway.
+const EVENT_NAMES: [&'static str; 27] = [
+    "CHANNEL_CREATE",
+    "CHANNEL_DELETE",
+    "CHANNEL_PINS_UPDATE",
+    "CHANNEL_RECIPIENT_ADD",
+    "CHANNEL_RECIPIENT_REMOVE",
+    "CHANNEL_UPDATE",
+    "GUILD_CREATE",
+    "GUILD_DELETE",
+    "GUILD_EMOJIS_UPDATE",
+    "GUILD_MEMBER_ADD",
+    "GUILD_MEMBER_REMOVE",
+    "GUILD_MEMBER_UPDATE",
+    "GUILD_MEMBERS_CHUNK",
+    "GUILD_ROLE_CREATE",
+    "GUILD_ROLE_DELETE",
+    "GUILD_ROLE_UPDATE",
+    "GUILD_UNAVAILABLE",
+    "GUILD_UPDATE",
+    "MESSAGE_CREATE",
+    "MESSAGE_DELETE",
+    "MESSAGE_DELETE_BULK",
+    "MESSAGE_UPDATE",
+    "PRESENCE_UPDATE",
+    "PRESENCES_REPLACE",
+    "READY",
+    "USER_UPDATE",
+    "VOICE_STATE_UPDATE",
+];
+
+// The index of an event in `EVENT_NAMES`, if the cache stores data from it.
+fn event_index(event: &Event) -> Option<usize> {
+    Some(match *event {
+        Event::ChannelCreate(_) => 0,
+        Event::ChannelDelete(_) => 1,
+        Event::ChannelPinsUpdate(_) => 2,
+        Event::ChannelRecipientAdd(_) => 3,
+        Event::ChannelRecipientRemove(_) => 4,
+        Event::ChannelUpdate(_) => 5,
+        Event::GuildCreate(_) => 6,
+        Event::GuildDelete(_) => 7,
+        Event::GuildEmojisUpdate(_) => 8,
+        Event::GuildMemberAdd(_) => 9,
+        Event::GuildMemberRemove(_) => 10,
+        Event::GuildMemberUpdate(_) => 11,
+        Event::GuildMembersChunk(_) => 12,
+        Event::GuildRoleCreate(_) => 13,
+        Event::GuildRoleDelete(_) => 14,
+        Event::GuildRoleUpdate(_) => 15,
+        Event::GuildUnavailable(_) => 16,
+        Event::GuildUpdate(_) => 17,
+        Event::MessageCreate(_) => 18,
+        Event::MessageDelete(_) => 19,
+        Event::MessageDeleteBulk(_) => 20,
+        Event::MessageUpdate(_) => 21,
+        Event::PresenceUpdate(_) => 22,
+        Event::PresencesReplace(_) => 23,
+        Event::Ready(_) => 24,
+        Event::UserUpdate(_) => 25,
+        Event::VoiceStateUpdate(_) => 26,
+        _ => return None,
+    })
+}
+
+// An estimate of the memory used by a guild, excluding its channels, which are
+// counted separately as they are shared with the cache's map of channels.
+fn guild_size(guild: &Guild) -> usize {
+    let members = guild.members
+        .values()
+        .map(|member| {
+            mem::size_of::<Member>() +
+                member.nick.as_ref().map_or(0, |nick| nick.len()) +
+                member.roles.len() * mem::size_of::<RoleId>()
+        })
+        .sum::<usize>();
+    let roles = guild.roles
+        .values()
+        .map(|role| mem::size_of::<Role>() + role.name.len())
+        .sum::<usize>();
+    let emojis = guild.emojis
+        .values()
+        .map(|emoji| mem::size_of::<Emoji>() + emoji.name.len())
+        .sum::<usize>();
+
+    mem::size_of::<Guild>() + guild.name.len() + members + roles + emojis +
+        guild.presences.len() * mem::size_of::<Presence>() +
+        guild.voice_states.len() * mem::size_of::<VoiceState>()
+}
+
+// Whether an entry for a user may be added to a map without exceeding the
+// maximum size, if it is not already present.
+fn has_room<V>(map: &HashMap<UserId, V>, user_id: UserId, max: Option<usize>) -> bool {
+    map.contains_key(&user_id) || max.map_or(true, |max| map.len() < max)
+}
+
+// Removes entries - other than the current user's - until a map is no larger
+// than the maximum size.
+fn truncate<V>(map: &mut HashMap<UserId, V>, max: usize, current_user_id: UserId) {
+    if map.len() <= max {
+        return;
+    }
+
+    let excess = map.len() - max;
+    let removed = map.keys()
+        .filter(|user_id| **user_id != current_user_id)
+        .take(excess)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for user_id in removed {
+        map.remove(&user_id);
+    }
+}
+
+// The member described by a member update, for when they are not stored.
+fn updated_member(event: &GuildMemberUpdateEvent) -> Member {
+    Member {
+        deaf: false,
+        guild_id: Some(event.guild_id),
+        joined_at: String::default(),
+        mute: false,
+        nick: event.nick.clone(),
+        roles: event.roles.clone(),
+        user: Arc::new(RwLock::new(event.user.clone())),
+    }
+}
+
+// A snapshot of the cache, borrowing its contents to be serialized.
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    groups: Vec<&'a Group>,
+    guilds: Vec<&'a Guild>,
+    presences: Vec<&'a Presence>,
+    private_channels: Vec<&'a PrivateChannel>,
+    shard_count: u64,
+    unavailable_guilds: &'a HashSet<GuildId>,
+    user: &'a CurrentUser,
+    users: Vec<&'a User>,
+}
+
+// A snapshot of the cache as deserialized, to be loaded into the cache.
+#[derive(Deserialize)]
+struct Snapshot {
+    groups: Vec<Group>,
+    guilds: Vec<Guild>,
+    presences: Vec<Presence>,
+    private_channels: Vec<PrivateChannel>,
+    shard_count: u64,
+    unavailable_guilds: HashSet<GuildId>,
+    user: CurrentUser,
+    users: Vec<User>,
+}
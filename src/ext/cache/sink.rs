@@ -0,0 +1,72 @@
+use std::sync::{Arc, RwLock};
+use super::Cache;
+use ::model::event::Event;
+
+lazy_static! {
+    static ref SINK: RwLock<Option<Arc<CacheSink>>> = RwLock::new(None);
+}
+
+/// A receiver of every event the library applies to the global [`Cache`], such
+/// as a store shared by the processes of a sharded bot.
+///
+/// A sink is set via [`Client::with_cache_sink`], and is only written to: the
+/// library's own lookups - such as those of model methods - always read the
+/// global [`CACHE`], which is updated whether or not a sink is set.
+///
+/// A separate instance of the in-memory [`Cache`], wrapped in a `RwLock`, can
+/// be used as a sink.
+///
+/// # Examples
+///
+/// A sink which only stores the names of guilds:
+///
+/// ```rust
+/// use serenity::ext::cache::CacheSink;
+/// use serenity::model::event::Event;
+/// use serenity::model::GuildId;
+/// use std::collections::HashMap;
+/// use std::sync::RwLock;
+///
+/// #[derive(Default)]
+/// struct GuildNames(RwLock<HashMap<GuildId, String>>);
+///
+/// impl CacheSink for GuildNames {
+///     fn update(&self, event: &Event) {
+///         if let Event::GuildCreate(ref event) = *event {
+///             let name = event.guild.name.clone();
+///
+///             self.0.write().unwrap().insert(event.guild.id, name);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`CACHE`]: ../../client/struct.CACHE.html
+/// [`Cache`]: struct.Cache.html
+/// [`Client::with_cache_sink`]: ../../client/struct.Client.html#method.with_cache_sink
+pub trait CacheSink: Send + Sync {
+    /// Updates the sink with a received event.
+    fn update(&self, event: &Event);
+}
+
+impl CacheSink for RwLock<Cache> {
+    fn update(&self, event: &Event) {
+        // Only take the write lock if needed, so that events for different
+        // guilds do not block each other.
+        if !self.read().unwrap().try_update(event) {
+            self.write().unwrap().update(event);
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn set_sink(sink: Arc<CacheSink>) {
+    *SINK.write().unwrap() = Some(sink);
+}
+
+// The sink set via `Client::with_cache_sink`, which events are mirrored to in
+// addition to being applied to the global `CACHE`.
+#[doc(hidden)]
+pub fn sink() -> Option<Arc<CacheSink>> {
+    SINK.read().unwrap().clone()
+}
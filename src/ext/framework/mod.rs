@@ -72,8 +72,7 @@ use self::command::{AfterHook, BeforeHook};
 use std::collections::HashMap;
 use std::default::Default;
 use std::sync::Arc;
-use std::thread;
-use ::client::Context;
+use ::client::{Context, WorkerPool};
 use ::model::{Message, UserId};
 use ::model::permissions::Permissions;
 use ::utils;
@@ -411,9 +410,9 @@ impl Framework {
         }
     }
 
+    // Only called by the client, which runs the commands on its worker pool.
     #[allow(cyclomatic_complexity)]
-    #[doc(hidden)]
-    pub fn dispatch(&mut self, mut context: Context, message: Message) {
+    pub(crate) fn dispatch(&mut self, mut context: Context, message: Message, pool: &WorkerPool) {
         let res = command::positions(&mut context, &message.content, &self.configuration);
 
         let positions = match res {
@@ -493,7 +492,9 @@ impl Framework {
                             return;
                         }
 
-                        thread::spawn(move || {
+                        let key = pool.key(Some(message.channel_id), None);
+
+                        pool.execute(key, move || {
                             if let Some(before) = before {
                                 if !(before)(&mut context, &message, &built) {
                                     return;
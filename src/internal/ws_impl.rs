@@ -1,24 +1,39 @@
 use flate2::read::ZlibDecoder;
 use serde_json;
+use std::io::Read;
 use websocket::client::{Receiver, Sender};
 use websocket::message::{Message as WsMessage, Type as WsType};
 use websocket::stream::WebSocketStream;
 use websocket::ws::receiver::Receiver as WsReceiver;
 use websocket::ws::sender::Sender as WsSender;
-use ::client::gateway::GatewayError;
+use ::client::gateway::{GatewayEncoding, GatewayError};
 use ::internal::prelude::*;
 
 pub trait ReceiverExt {
+    #[cfg(feature="voice")]
     fn recv_json<F, T>(&mut self, decode: F) -> Result<T>
         where F: FnOnce(Value) -> Result<T>;
+
+    fn recv_encoded<F, T>(&mut self, encoding: GatewayEncoding, decode: F) -> Result<T>
+        where F: FnOnce(Value) -> Result<T>;
 }
 
 pub trait SenderExt {
+    #[cfg(feature="voice")]
     fn send_json(&mut self, value: &Value) -> Result<()>;
+
+    fn send_encoded(&mut self, encoding: GatewayEncoding, value: &Value) -> Result<()>;
 }
 
 impl ReceiverExt for Receiver<WebSocketStream> {
+    #[cfg(feature="voice")]
+    #[inline]
     fn recv_json<F, T>(&mut self, decode: F) -> Result<T> where F: FnOnce(Value) -> Result<T> {
+        self.recv_encoded(GatewayEncoding::Json, decode)
+    }
+
+    fn recv_encoded<F, T>(&mut self, encoding: GatewayEncoding, decode: F) -> Result<T>
+        where F: FnOnce(Value) -> Result<T> {
         let message: WsMessage = self.recv_message()?;
 
         if message.opcode == WsType::Close {
@@ -26,13 +41,22 @@ impl ReceiverExt for Receiver<WebSocketStream> {
 
             Err(Error::Gateway(GatewayError::Closed(message.cd_status_code, r)))
         } else if message.opcode == WsType::Binary || message.opcode == WsType::Text {
-            let json: Value = if message.opcode == WsType::Binary {
-                serde_json::from_reader(ZlibDecoder::new(&message.payload[..]))?
+            // Compressed payloads are always sent as binary messages. When
+            // using ETF every message is binary, so tell the two apart by
+            // whether the payload starts with the term format's version byte.
+            let compressed = message.opcode == WsType::Binary &&
+                (encoding == GatewayEncoding::Json || message.payload.first() != Some(&131));
+
+            let decoded = if compressed {
+                let mut bytes = vec![];
+                ZlibDecoder::new(&message.payload[..]).read_to_end(&mut bytes)?;
+
+                encoding.decode(&bytes)?
             } else {
-                serde_json::from_reader(&message.payload[..])?
+                encoding.decode(&message.payload)?
             };
 
-            match decode(json) {
+            match decode(decoded) {
                 Ok(v) => Ok(v),
                 Err(why) => {
                     let s = String::from_utf8_lossy(&message.payload);
@@ -51,10 +75,20 @@ impl ReceiverExt for Receiver<WebSocketStream> {
 }
 
 impl SenderExt for Sender<WebSocketStream> {
+    #[cfg(feature="voice")]
+    #[inline]
     fn send_json(&mut self, value: &Value) -> Result<()> {
-        serde_json::to_string(value)
-            .map(WsMessage::text)
-            .map_err(Error::from)
-            .and_then(|m| self.send_message(&m).map_err(Error::from))
+        self.send_encoded(GatewayEncoding::Json, value)
+    }
+
+    fn send_encoded(&mut self, encoding: GatewayEncoding, value: &Value) -> Result<()> {
+        let message = match encoding {
+            GatewayEncoding::Json => {
+                serde_json::to_string(value).map(WsMessage::text)?
+            },
+            GatewayEncoding::Etf => WsMessage::binary(encoding.encode(value)?),
+        };
+
+        self.send_message(&message).map_err(Error::from)
     }
 }
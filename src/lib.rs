@@ -100,6 +100,7 @@ extern crate serde_derive;
 extern crate serde_json;
 
 extern crate base64;
+extern crate byteorder;
 extern crate flate2;
 extern crate hyper;
 extern crate multipart;
@@ -108,8 +109,6 @@ extern crate time;
 extern crate typemap;
 extern crate websocket;
 
-#[cfg(feature="voice")]
-extern crate byteorder;
 #[cfg(feature="voice")]
 extern crate opus;
 #[cfg(feature="voice")]
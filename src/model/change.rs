@@ -0,0 +1,75 @@
+/// The old and new values of something which has been updated, such as a
+/// cached model before and after an update for it was received.
+///
+/// This can be used to find what exactly changed between the two, e.g. for a
+/// [`Member`]:
+///
+/// ```rust,ignore
+/// let change = Change::new(old_member, new_member);
+///
+/// if let Some(nick) = change.field(|member| &member.nick) {
+///     println!("Nickname changed from {:?} to {:?}", nick.old, nick.new);
+/// }
+///
+/// for role_id in change.added(|member| &member.roles) {
+///     println!("Role {} added", role_id);
+/// }
+/// ```
+///
+/// [`Member`]: struct.Member.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Change<T> {
+    /// The value before the update.
+    pub old: T,
+    /// The value after the update.
+    pub new: T,
+}
+
+impl<T> Change<T> {
+    /// Creates a change from an old and new value.
+    pub fn new(old: T, new: T) -> Change<T> {
+        Change {
+            old: old,
+            new: new,
+        }
+    }
+
+    /// Retrieves the change of a single field of the value, if the field
+    /// differs between the old and new values.
+    pub fn field<U, F>(&self, f: F) -> Option<Change<U>>
+        where F: Fn(&T) -> &U, U: Clone + PartialEq {
+        let old = f(&self.old);
+        let new = f(&self.new);
+
+        if old == new {
+            None
+        } else {
+            Some(Change::new(old.clone(), new.clone()))
+        }
+    }
+
+    /// Retrieves the items of a collection field which are in the new value
+    /// but not the old one.
+    pub fn added<U, F>(&self, f: F) -> Vec<U>
+        where F: Fn(&T) -> &[U], U: Clone + PartialEq {
+        let old = f(&self.old);
+
+        f(&self.new).iter().filter(|item| !old.contains(item)).cloned().collect()
+    }
+
+    /// Retrieves the items of a collection field which are in the old value
+    /// but not the new one.
+    pub fn removed<U, F>(&self, f: F) -> Vec<U>
+        where F: Fn(&T) -> &[U], U: Clone + PartialEq {
+        let new = f(&self.new);
+
+        f(&self.old).iter().filter(|item| !new.contains(item)).cloned().collect()
+    }
+}
+
+impl<T: PartialEq> Change<T> {
+    /// Whether the old and new values differ.
+    pub fn is_changed(&self) -> bool {
+        self.old != self.new
+    }
+}
@@ -10,7 +10,7 @@ use ::utils::builder::{CreateMessage, GetMessages};
 ///
 /// [`Guild`]: struct.Guild.html
 /// [`User`]: struct.User.html
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Group {
     /// The Id of the group channel.
     #[serde(rename="id")]
@@ -26,7 +26,7 @@ pub struct Group {
     /// The Id of the group owner.
     pub owner_id: UserId,
     /// A map of the group's recipients.
-    #[serde(deserialize_with="deserialize_users")]
+    #[serde(deserialize_with="deserialize_users", serialize_with="serialize_gen_locked_map")]
     pub recipients: HashMap<UserId, Arc<RwLock<User>>>,
 }
 
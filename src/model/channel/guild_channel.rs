@@ -12,7 +12,7 @@ use ::client::CACHE;
 
 /// Represents a guild's text or voice channel. Some methods are available only
 /// for voice channels and some are only available for text channels.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct GuildChannel {
     /// The unique Id of the channel.
     ///
@@ -359,6 +359,53 @@ impl GuildChannel {
         self.id.messages(f)
     }
 
+    /// Calculates the permissions of a member in the channel, applying the
+    /// channel's permission overwrites.
+    ///
+    /// The member need not be cached, while the channel's guild must be.
+    ///
+    /// # Examples
+    ///
+    /// Check whether the author of a message can embed links in the channel:
+    ///
+    /// ```rust,ignore
+    /// // assumes a `channel` and `message` have already been bound
+    ///
+    /// let guild = channel.guild().unwrap();
+    /// let guild = guild.read().unwrap();
+    ///
+    /// if let Some(member) = guild.members.get(&message.author.id) {
+    ///     let permissions = channel.permissions_for(member)?;
+    ///
+    ///     println!("Can embed links: {}", permissions.embed_links());
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild is not in the
+    /// cache, or a [`ClientError::ItemMissing`] if the member belongs to
+    /// another guild, or the guild's `@everyone` role or any of the member's
+    /// roles is not in the cache.
+    ///
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
+    /// [`ClientError::ItemMissing`]: ../client/enum.ClientError.html#variant.ItemMissing
+    #[cfg(feature="cache")]
+    pub fn permissions_for(&self, member: &Member) -> Result<Permissions> {
+        if member.guild_id.map_or(false, |guild_id| guild_id != self.guild_id) {
+            return Err(Error::Client(ClientError::ItemMissing));
+        }
+
+        let guild = match self.guild() {
+            Some(guild) => guild,
+            None => return Err(Error::Client(ClientError::GuildNotFound)),
+        };
+
+        let guild = guild.read().unwrap();
+
+        guild.member_permissions(member, Some(self))
+    }
+
     /// Pins a [`Message`] to the channel.
     #[inline]
     pub fn pin<M: Into<MessageId>>(&self, message_id: M) -> Result<()> {
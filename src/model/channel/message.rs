@@ -1,7 +1,8 @@
 use std::fmt::Write;
 use std::mem;
+use std::time::Duration;
 use ::constants;
-use ::client::rest;
+use ::client::{self, rest};
 use ::model::*;
 use ::utils::builder::{CreateEmbed, CreateMessage};
 
@@ -66,6 +67,53 @@ pub struct Message {
 }
 
 impl Message {
+    /// Blocks the current thread while collecting reactions added to the
+    /// message which match the filter, until `max` have been collected or the
+    /// timeout expires.
+    ///
+    /// Reactions added on any shard are collected, without needing to attach
+    /// an [`on_reaction_add`] handler. Reactions added before this is called
+    /// are not collected.
+    ///
+    /// **Note**: When called from an event handler, the client's worker thread
+    /// is occupied until this returns. While every worker is waiting, further
+    /// events queue up and - with the default [`Backpressure::Block`] - the
+    /// shards stop receiving events once the queues are full, so reactions may
+    /// not be collected until the timeout. Keep the timeout short, or increase
+    /// the pool's [`size`].
+    ///
+    /// # Examples
+    ///
+    /// Run a poll for 30 seconds, counting the votes for each option:
+    ///
+    /// ```rust,no_run
+    /// # use serenity::model::Message;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn poll(message: &Message) {
+    /// let _ = message.react("👍");
+    /// let _ = message.react("👎");
+    ///
+    /// let votes = message.await_reactions(|reaction| {
+    ///     reaction.emoji.as_data() == "👍" || reaction.emoji.as_data() == "👎"
+    /// }, 100, Duration::from_secs(30));
+    ///
+    /// println!("Received {} votes", votes.len());
+    /// # }
+    /// ```
+    ///
+    /// [`Backpressure::Block`]: ../client/enum.Backpressure.html#variant.Block
+    /// [`on_reaction_add`]: ../client/struct.Client.html#method.on_reaction_add
+    /// [`size`]: ../client/struct.PoolConfig.html#method.size
+    pub fn await_reactions<F>(&self, filter: F, max: usize, timeout: Duration) -> Vec<Reaction>
+        where F: Fn(&Reaction) -> bool + Send + 'static {
+        let message_id = self.id;
+
+        client::await_reactions(move |reaction| {
+            reaction.message_id == message_id && filter(reaction)
+        }, max, timeout)
+    }
+
     /// Deletes the message.
     ///
     /// **Note**: The logged in user must either be the author of the message or
@@ -449,7 +449,7 @@ impl ChannelType {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 struct PermissionOverwriteData {
     allow: Permissions,
     deny: Permissions,
@@ -485,6 +485,22 @@ impl<'de> Deserialize<'de> for PermissionOverwrite {
     }
 }
 
+impl Serialize for PermissionOverwrite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        let (id, kind) = match self.kind {
+            PermissionOverwriteType::Member(id) => (id.0, "member"),
+            PermissionOverwriteType::Role(id) => (id.0, "role"),
+        };
+
+        PermissionOverwriteData {
+            allow: self.allow,
+            deny: self.deny,
+            id: id,
+            kind: kind.to_owned(),
+        }.serialize(serializer)
+    }
+}
+
 /// The type of edit being made to a Channel's permissions.
 ///
 /// This is for use with methods such as `Context::create_permission`.
@@ -1,11 +1,11 @@
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::io::Read;
-use super::deserialize_single_recipient;
+use super::{deserialize_single_recipient, serialize_single_recipient};
 use ::model::*;
 use ::utils::builder::{CreateMessage, GetMessages};
 
 /// A Direct Message text channel with another user.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PrivateChannel {
     /// The unique Id of the private channel.
     ///
@@ -25,7 +25,9 @@ pub struct PrivateChannel {
     #[serde(rename="type")]
     pub kind: ChannelType,
     /// The recipient to the private channel.
-    #[serde(deserialize_with="deserialize_single_recipient", rename="recipients")]
+    #[serde(deserialize_with="deserialize_single_recipient",
+            rename="recipients",
+            serialize_with="serialize_single_recipient")]
     pub recipient: Arc<RwLock<User>>,
 }
 
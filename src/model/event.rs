@@ -3,7 +3,7 @@
 use serde::de::Error as DeError;
 use serde_json::{self, Error as JsonError};
 use std::collections::HashMap;
-use super::utils::deserialize_emojis;
+use super::utils::{deserialize_emojis, deserialize_members};
 use super::*;
 use ::constants::{OpCode, VoiceOpCode};
 use ::internal::prelude::*;
@@ -177,8 +177,21 @@ pub struct GuildMemberUpdateEvent {
 
 #[derive(Clone, Debug)]
 pub struct GuildMembersChunkEvent {
+    /// The total number of chunks that will be sent for the guild in response
+    /// to the request, if Discord provided it.
+    pub chunk_count: Option<u64>,
+    /// The 0-indexed position of this chunk out of the [`chunk_count`], if
+    /// Discord provided it.
+    ///
+    /// [`chunk_count`]: #structfield.chunk_count
+    pub chunk_index: Option<u64>,
     pub guild_id: GuildId,
     pub members: HashMap<UserId, Member>,
+    /// The nonce of the request that this chunk is a response to, if one was
+    /// given.
+    pub nonce: Option<String>,
+    /// The Ids of users that were requested but are not members of the guild.
+    pub not_found: Vec<UserId>,
 }
 
 impl<'de> Deserialize<'de> for GuildMembersChunkEvent {
@@ -202,12 +215,34 @@ impl<'de> Deserialize<'de> for GuildMembersChunkEvent {
             }
         }
 
-        let members: HashMap<UserId, Member> = Deserialize::deserialize(members)
-            .map_err(DeError::custom)?;
+        let members = deserialize_members(members).map_err(DeError::custom)?;
+
+        let chunk_count = match map.remove("chunk_count") {
+            Some(v) => Option::<u64>::deserialize(v).map_err(DeError::custom)?,
+            None => None,
+        };
+        let chunk_index = match map.remove("chunk_index") {
+            Some(v) => Option::<u64>::deserialize(v).map_err(DeError::custom)?,
+            None => None,
+        };
+        let nonce = match map.remove("nonce") {
+            Some(v) => Option::<String>::deserialize(v).map_err(DeError::custom)?,
+            None => None,
+        };
+        let not_found = match map.remove("not_found") {
+            Some(v) => Option::<Vec<UserId>>::deserialize(v)
+                .map_err(DeError::custom)?
+                .unwrap_or_default(),
+            None => vec![],
+        };
 
         Ok(GuildMembersChunkEvent {
+            chunk_count: chunk_count,
+            chunk_index: chunk_index,
             guild_id: guild_id,
             members: members,
+            nonce: nonce,
+            not_found: not_found,
         })
     }
 }
@@ -1,4 +1,5 @@
 use serde::de::Error as DeError;
+use serde::ser::SerializeMap;
 use serde_json;
 use std::sync::{Arc, RwLock};
 use super::utils::*;
@@ -21,9 +22,10 @@ pub struct BotGateway {
 
 /// Representation of a game that a [`User`] is playing -- or streaming in the
 /// case that a stream URL is provided.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Game {
     /// The type of game status.
+    #[serde(rename="type")]
     pub kind: GameType,
     /// The name of the game being played.
     pub name: String,
@@ -175,6 +177,35 @@ impl<'de> Deserialize<'de> for Presence {
     }
 }
 
+impl Serialize for Presence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct PartialUser {
+            id: u64,
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("game", &self.game)?;
+
+        if let Some(last_modified) = self.last_modified {
+            map.serialize_entry("last_modified", &last_modified)?;
+        }
+
+        map.serialize_entry("nick", &self.nick)?;
+        map.serialize_entry("status", &self.status)?;
+
+        // Like the gateway, only send the user's Id if the user is not known.
+        match self.user {
+            Some(ref user) => map.serialize_entry("user", &*user.read().unwrap())?,
+            None => map.serialize_entry("user", &PartialUser {
+                id: self.user_id.0,
+            })?,
+        }
+
+        map.end()
+    }
+}
+
 /// An initial set of information given after IDENTIFYing to the gateway.
 #[derive(Clone, Debug, Deserialize)]
 pub struct Ready {
@@ -2,7 +2,7 @@
 /// to them.
 ///
 /// [`Guild`]: struct.Guild.html
-#[derive(Copy, Clone, Debug, Deserialize, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Deserialize, Hash, Eq, PartialEq, Serialize)]
 pub enum Feature {
     /// The [`Guild`] can set a custom [`splash`][`Guild::splash`] image on
     /// invite URLs.
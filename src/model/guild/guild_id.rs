@@ -8,6 +8,61 @@ use ::utils::builder::{EditGuild, EditMember, EditRole};
 use ::client::CACHE;
 
 impl GuildId {
+    // Checks that the current user sits above a member in the role hierarchy -
+    // and above any roles being given or removed by an edit - so that a
+    // request which would be refused is not made. Nothing is checked unless
+    // the guild and both members are cached.
+    #[cfg(feature="cache")]
+    #[doc(hidden)]
+    pub fn check_hierarchy(&self, target_id: UserId, edit: Option<&JsonMap>) -> Result<()> {
+        let current_user_id = CACHE.read().unwrap().user.id;
+
+        let guild = match CACHE.read().unwrap().guild(*self) {
+            Some(guild) => guild,
+            None => return Ok(()),
+        };
+        let guild = guild.read().unwrap();
+
+        let target = match guild.members.get(&target_id) {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+
+        if !guild.members.contains_key(&current_user_id) {
+            return Ok(());
+        }
+
+        // Only nicknaming and changing the roles of another member are bound
+        // by the hierarchy when editing them.
+        let moderates = edit.map_or(true, |map| {
+            target_id != current_user_id && (map.contains_key("nick") || map.contains_key("roles"))
+        });
+
+        if moderates && !guild.can_moderate(current_user_id, target_id)? {
+            return Err(Error::Client(ClientError::CannotModerate(target_id)));
+        }
+
+        let roles = match edit.and_then(|map| map.get("roles")).and_then(Value::as_array) {
+            Some(roles) => roles.iter().filter_map(Value::as_u64).map(RoleId).collect::<Vec<_>>(),
+            None => return Ok(()),
+        };
+
+        let added = roles.iter().filter(|role_id| !target.roles.contains(role_id));
+        let removed = target.roles.iter().filter(|role_id| !roles.contains(role_id));
+
+        for &role_id in added.chain(removed) {
+            // Roles which no longer exist are left for Discord to handle.
+            let manageable = !guild.roles.contains_key(&role_id)
+                || guild.can_manage_role(current_user_id, role_id)?;
+
+            if !manageable {
+                return Err(Error::Client(ClientError::CannotManageRole(role_id)));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Converts the guild Id into the default channel's Id.
     #[inline]
     pub fn as_channel_id(&self) -> ChannelId {
@@ -37,6 +92,11 @@ impl GuildId {
     /// Returns a [`ClientError::DeleteMessageDaysAmount`] if the number of
     /// days' worth of messages to delete is over the maximum.
     ///
+    /// If the `cache` is enabled, returns a [`ClientError::CannotModerate`] if
+    /// the user is the guild's owner or is not below the current user in the
+    /// role hierarchy.
+    ///
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
     /// [`ClientError::DeleteMessageDaysAmount`]: ../client/enum.ClientError.html#variant.DeleteMessageDaysAmount
     /// [`Guild::ban`]: struct.Guild.html#method.ban
     /// [`User`]: struct.User.html
@@ -47,7 +107,12 @@ impl GuildId {
             return Err(Error::Client(ClientError::DeleteMessageDaysAmount(delete_message_days)));
         }
 
-        rest::ban_user(self.0, user.into().0, delete_message_days)
+        let user_id = user.into();
+
+        #[cfg(feature="cache")]
+        self.check_hierarchy(user_id, None)?;
+
+        rest::ban_user(self.0, user_id.0, delete_message_days)
     }
 
     /// Gets a list of the guild's bans.
@@ -250,10 +315,28 @@ impl GuildId {
     /// ```rust,ignore
     /// guild.edit_member(user_id, |m| m.mute(true).roles(&vec![role_id]));
     /// ```
-    #[inline]
+    ///
+    /// # Errors
+    ///
+    /// If the `cache` is enabled, returns a [`ClientError::CannotModerate`] if
+    /// another member's nickname or roles are being edited while they are the
+    /// guild's owner or are not below the current user in the role hierarchy.
+    ///
+    /// If the `cache` is enabled, returns a [`ClientError::CannotManageRole`]
+    /// if a role being given or removed is not below the current user's highest
+    /// role.
+    ///
+    /// [`ClientError::CannotManageRole`]: ../client/enum.ClientError.html#variant.CannotManageRole
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
     pub fn edit_member<F, U>(&self, user_id: U, f: F) -> Result<()>
         where F: FnOnce(EditMember) -> EditMember, U: Into<UserId> {
-        rest::edit_member(self.0, user_id.into().0, &f(EditMember::default()).0)
+        let user_id = user_id.into();
+        let map = f(EditMember::default()).0;
+
+        #[cfg(feature="cache")]
+        self.check_hierarchy(user_id, Some(&map))?;
+
+        rest::edit_member(self.0, user_id.0, &map)
     }
 
     /// Edits the current user's nickname for the guild.
@@ -347,11 +430,22 @@ impl GuildId {
     ///
     /// Requires the [Kick Members] permission.
     ///
+    /// # Errors
+    ///
+    /// If the `cache` is enabled, returns a [`ClientError::CannotModerate`] if
+    /// the member is the guild's owner or is not below the current user in the
+    /// role hierarchy.
+    ///
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
     /// [`Member`]: struct.Member.html
     /// [Kick Members]: permissions/constant.KICK_MEMBERS.html
-    #[inline]
     pub fn kick<U: Into<UserId>>(&self, user_id: U) -> Result<()> {
-        rest::kick_member(self.0, user_id.into().0)
+        let user_id = user_id.into();
+
+        #[cfg(feature="cache")]
+        self.check_hierarchy(user_id, None)?;
+
+        rest::kick_member(self.0, user_id.0)
     }
 
     /// Leaves the guild.
@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter, Result as FmtResult};
-use super::deserialize_sync_user;
+use super::{deserialize_sync_user, serialize_sync_user};
 use ::model::*;
 
 #[cfg(feature="cache")]
@@ -13,7 +13,7 @@ use ::utils::builder::EditMember;
 use ::utils::Colour;
 
 /// Information about a member of a guild.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Member {
     /// Indicator of whether the member can hear in voice channels.
     pub deaf: bool,
@@ -30,7 +30,7 @@ pub struct Member {
     /// Vector of Ids of [`Role`]s given to the member.
     pub roles: Vec<RoleId>,
     /// Attached User struct.
-    #[serde(deserialize_with="deserialize_sync_user")]
+    #[serde(deserialize_with="deserialize_sync_user", serialize_with="serialize_sync_user")]
     pub user: Arc<RwLock<User>>,
 }
 
@@ -40,6 +40,19 @@ impl Member {
     ///
     /// **Note**: Requires the [Manage Roles] permission.
     ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild could not be
+    /// found.
+    ///
+    /// Returns a [`ClientError::CannotModerate`] if the member is another
+    /// member not below the current user in the role hierarchy, or a
+    /// [`ClientError::CannotManageRole`] if a role is not below the current
+    /// user's highest role.
+    ///
+    /// [`ClientError::CannotManageRole`]: ../client/enum.ClientError.html#variant.CannotManageRole
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: permissions/constant.MANAGE_ROLES.html
     #[cfg(feature="cache")]
@@ -51,8 +64,13 @@ impl Member {
         }
 
         let guild_id = self.find_guild()?;
+        let user_id = self.user.read().unwrap().id;
 
-        match rest::add_member_role(guild_id.0, self.user.read().unwrap().id.0, role_id.0) {
+        let mut roles = self.roles.clone();
+        roles.push(role_id);
+        guild_id.check_hierarchy(user_id, Some(&EditMember::default().roles(&roles).0))?;
+
+        match rest::add_member_role(guild_id.0, user_id.0, role_id.0) {
             Ok(()) => {
                 self.roles.push(role_id);
 
@@ -67,6 +85,19 @@ impl Member {
     ///
     /// **Note**: Requires the [Manage Roles] permission.
     ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild could not be
+    /// found.
+    ///
+    /// Returns a [`ClientError::CannotModerate`] if the member is another
+    /// member not below the current user in the role hierarchy, or a
+    /// [`ClientError::CannotManageRole`] if a role is not below the current
+    /// user's highest role.
+    ///
+    /// [`ClientError::CannotManageRole`]: ../client/enum.ClientError.html#variant.CannotManageRole
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: permissions/constant.MANAGE_ROLES.html
     #[cfg(feature="cache")]
@@ -74,9 +105,7 @@ impl Member {
         let guild_id = self.find_guild()?;
         self.roles.extend_from_slice(role_ids);
 
-        let map = EditMember::default().roles(&self.roles).0;
-
-        match rest::edit_member(guild_id.0, self.user.read().unwrap().id.0, &map) {
+        match guild_id.edit_member(self.user.read().unwrap().id, |m| m.roles(&self.roles)) {
             Ok(()) => Ok(()),
             Err(why) => {
                 self.roles.retain(|r| !role_ids.contains(r));
@@ -96,12 +125,16 @@ impl Member {
     /// Returns a [`ClientError::GuildNotFound`] if the guild could not be
     /// found.
     ///
+    /// Returns a [`ClientError::CannotModerate`] if the member is the guild's
+    /// owner or is not below the current user in the role hierarchy.
+    ///
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
     /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
     ///
     /// [Ban Members]: permissions/constant.BAN_MEMBERS.html
     #[cfg(feature="cache")]
     pub fn ban(&self, delete_message_days: u8) -> Result<()> {
-        rest::ban_user(self.find_guild()?.0, self.user.read().unwrap().id.0, delete_message_days)
+        self.find_guild()?.ban(self.user.read().unwrap().id, delete_message_days)
     }
 
     /// Determines the member's colour.
@@ -151,14 +184,17 @@ impl Member {
     /// See [`EditMember`] for the permission(s) required for separate builder
     /// methods, as well as usage of this.
     ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`GuildId::edit_member`], including when the
+    /// edit is refused by the role hierarchy.
+    ///
     /// [`Context::edit_member`]: ../client/struct.Context.html#method.edit_member
     /// [`EditMember`]: ../builder/struct.EditMember.html
+    /// [`GuildId::edit_member`]: struct.GuildId.html#method.edit_member
     #[cfg(feature="cache")]
     pub fn edit<F: FnOnce(EditMember) -> EditMember>(&self, f: F) -> Result<()> {
-        let guild_id = self.find_guild()?;
-        let map = f(EditMember::default()).0;
-
-        rest::edit_member(guild_id.0, self.user.read().unwrap().id.0, &map)
+        self.find_guild()?.edit_member(self.user.read().unwrap().id, f)
     }
 
     /// Finds the Id of the [`Guild`] that the member is in.
@@ -187,11 +223,86 @@ impl Member {
         Err(Error::Client(ClientError::GuildNotFound))
     }
 
+    /// Retrieves the member's highest [`Role`] in their guild's role
+    /// hierarchy.
+    ///
+    /// Returns `None` if the guild is not in the cache, or the member has no
+    /// roles other than `@everyone`.
+    ///
+    /// [`Role`]: struct.Role.html
+    #[cfg(feature="cache")]
+    pub fn highest_role(&self) -> Option<Role> {
+        let guild_id = match self.guild_id {
+            Some(guild_id) => guild_id,
+            None => match self.find_guild() {
+                Ok(guild_id) => guild_id,
+                Err(_) => return None,
+            },
+        };
+
+        let cache = CACHE.read().unwrap();
+        let guild = match cache.guilds.get(&guild_id) {
+            Some(guild) => guild.read().unwrap(),
+            None => return None,
+        };
+
+        self.roles
+            .iter()
+            .filter_map(|role_id| guild.roles.get(role_id))
+            .max_by_key(|role| super::hierarchy_position(role))
+            .cloned()
+    }
+
+    /// Calculates the member's permissions in their guild, disregarding any
+    /// channel's permission overwrites.
+    ///
+    /// Refer to [`GuildChannel::permissions_for`] for the member's permissions
+    /// in a channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild is not in the
+    /// cache, or a [`ClientError::ItemMissing`] if the guild's `@everyone` role
+    /// or any of the member's roles is not.
+    ///
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
+    /// [`ClientError::ItemMissing`]: ../client/enum.ClientError.html#variant.ItemMissing
+    /// [`GuildChannel::permissions_for`]: struct.GuildChannel.html#method.permissions_for
+    #[cfg(feature="cache")]
+    pub fn permissions(&self) -> Result<Permissions> {
+        let guild_id = match self.guild_id {
+            Some(guild_id) => guild_id,
+            None => self.find_guild()?,
+        };
+
+        let guild = match CACHE.read().unwrap().guild(guild_id) {
+            Some(guild) => guild,
+            None => return Err(Error::Client(ClientError::GuildNotFound)),
+        };
+
+        let guild = guild.read().unwrap();
+
+        guild.member_permissions(self, None)
+    }
+
     /// Removes a [`Role`] from the member, editing its roles in-place if the
     /// request was successful.
     ///
     /// **Note**: Requires the [Manage Roles] permission.
     ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild could not be
+    /// found.
+    ///
+    /// Returns a [`ClientError::CannotModerate`] if the member is another
+    /// member not below the current user in the role hierarchy, or a
+    /// [`ClientError::CannotManageRole`] if a role is not below the current
+    /// user's highest role.
+    ///
+    /// [`ClientError::CannotManageRole`]: ../client/enum.ClientError.html#variant.CannotManageRole
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: permissions/constant.MANAGE_ROLES.html
     #[cfg(feature="cache")]
@@ -203,8 +314,12 @@ impl Member {
         }
 
         let guild_id = self.find_guild()?;
+        let user_id = self.user.read().unwrap().id;
+
+        let roles = self.roles.iter().cloned().filter(|r| *r != role_id).collect::<Vec<_>>();
+        guild_id.check_hierarchy(user_id, Some(&EditMember::default().roles(&roles).0))?;
 
-        match rest::remove_member_role(guild_id.0, self.user.read().unwrap().id.0, role_id.0) {
+        match rest::remove_member_role(guild_id.0, user_id.0, role_id.0) {
             Ok(()) => {
                 self.roles.retain(|r| r.0 != role_id.0);
 
@@ -218,6 +333,19 @@ impl Member {
     ///
     /// **Note**: Requires the [Manage Roles] permission.
     ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::GuildNotFound`] if the guild could not be
+    /// found.
+    ///
+    /// Returns a [`ClientError::CannotModerate`] if the member is another
+    /// member not below the current user in the role hierarchy, or a
+    /// [`ClientError::CannotManageRole`] if a role is not below the current
+    /// user's highest role.
+    ///
+    /// [`ClientError::CannotManageRole`]: ../client/enum.ClientError.html#variant.CannotManageRole
+    /// [`ClientError::CannotModerate`]: ../client/enum.ClientError.html#variant.CannotModerate
+    /// [`ClientError::GuildNotFound`]: ../client/enum.ClientError.html#variant.GuildNotFound
     /// [`Role`]: struct.Role.html
     /// [Manage Roles]: permissions/constant.MANAGE_ROLES.html
     #[cfg(feature="cache")]
@@ -225,9 +353,7 @@ impl Member {
         let guild_id = self.find_guild()?;
         self.roles.retain(|r| !role_ids.contains(r));
 
-        let map = EditMember::default().roles(&self.roles).0;
-
-        match rest::edit_member(guild_id.0, self.user.read().unwrap().id.0, &map) {
+        match guild_id.edit_member(self.user.read().unwrap().id, |m| m.roles(&self.roles)) {
             Ok(()) => Ok(()),
             Err(why) => {
                 self.roles.extend_from_slice(role_ids);
@@ -16,6 +16,7 @@ pub use self::role::*;
 
 use serde::de::Error as DeError;
 use serde_json;
+use std::cmp::Reverse;
 use super::utils::*;
 use ::client::rest;
 use ::constants::LARGE_THRESHOLD;
@@ -25,6 +26,12 @@ use ::utils::builder::{EditGuild, EditMember, EditRole};
 #[cfg(feature="cache")]
 use ::client::CACHE;
 
+// The position of a role in the hierarchy. Discord ranks the role with the
+// lower Id higher when two roles share a position.
+fn hierarchy_position(role: &Role) -> (i64, Reverse<RoleId>) {
+    (role.position, Reverse(role.id))
+}
+
 /// A representation of a banning of a user.
 #[derive(Clone, Debug, Deserialize)]
 pub struct Ban {
@@ -38,7 +45,7 @@ pub struct Ban {
 }
 
 /// Information about a Discord guild, such as channels, emojis, etc.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Guild {
     /// Id of a voice channel that's considered the AFK channel.
     pub afk_channel_id: Option<ChannelId>,
@@ -49,11 +56,13 @@ pub struct Guild {
     ///
     /// This contains all channels regardless of permissions (i.e. the ability
     /// of the bot to read from or connect to them).
+    #[serde(serialize_with="serialize_gen_locked_map")]
     pub channels: HashMap<ChannelId, Arc<RwLock<GuildChannel>>>,
     /// Indicator of whether notifications for all messages are enabled by
     /// default in the guild.
     pub default_message_notifications: u64,
     /// All of the guild's custom emojis.
+    #[serde(serialize_with="serialize_gen_map")]
     pub emojis: HashMap<EmojiId, Emoji>,
     /// VIP features enabled for the guild. Can be obtained through the
     /// [Discord Partnership] website.
@@ -82,6 +91,7 @@ pub struct Guild {
     /// the library.
     ///
     /// [`ReadyEvent`]: events/struct.ReadyEvent.html
+    #[serde(serialize_with="serialize_gen_map")]
     pub members: HashMap<UserId, Member>,
     /// Indicator of whether the guild requires multi-factor authentication for
     /// [`Role`]s or [`User`]s with moderation permissions.
@@ -98,10 +108,12 @@ pub struct Guild {
     /// A mapping of [`User`]s' Ids to their current presences.
     ///
     /// [`User`]: struct.User.html
+    #[serde(serialize_with="serialize_gen_map")]
     pub presences: HashMap<UserId, Presence>,
     /// The region that the voice servers that the guild uses are located in.
     pub region: String,
     /// A mapping of the guild's roles.
+    #[serde(serialize_with="serialize_gen_map")]
     pub roles: HashMap<RoleId, Role>,
     /// An identifying hash of the guild's splash icon.
     ///
@@ -115,6 +127,7 @@ pub struct Guild {
     /// A mapping of of [`User`]s to their current voice state.
     ///
     /// [`User`]: struct.User.html
+    #[serde(serialize_with="serialize_gen_map")]
     pub voice_states: HashMap<UserId, VoiceState>,
 }
 
@@ -204,6 +217,84 @@ impl Guild {
         self.id.bans()
     }
 
+    /// Checks whether a member can give or remove a [`Role`], which requires
+    /// the role to be below the member's [highest role][`highest_role`]. The
+    /// guild's owner can manage every role.
+    ///
+    /// This does not check whether the member has the [Manage Roles]
+    /// permission.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::ItemMissing`] if the member or the role is not
+    /// in the guild.
+    ///
+    /// [`ClientError::ItemMissing`]: ../client/enum.ClientError.html#variant.ItemMissing
+    /// [`Role`]: struct.Role.html
+    /// [`highest_role`]: #method.highest_role
+    /// [Manage Roles]: permissions/constant.MANAGE_ROLES.html
+    pub fn can_manage_role<R, U>(&self, actor_id: U, role_id: R) -> Result<bool>
+        where R: Into<RoleId>, U: Into<UserId> {
+        let actor_id = actor_id.into();
+
+        let role = match self.roles.get(&role_id.into()) {
+            Some(role) => role,
+            None => return Err(Error::Client(ClientError::ItemMissing)),
+        };
+
+        if actor_id == self.owner_id {
+            return Ok(true);
+        }
+
+        Ok(self.hierarchy_position(actor_id)? > Some(hierarchy_position(role)))
+    }
+
+    /// Checks whether a member can moderate - such as kick, ban or nickname -
+    /// another member, which requires the target to not be the guild's owner
+    /// and for their [highest role][`highest_role`] to be below the actor's.
+    ///
+    /// The guild's owner can moderate every other member.
+    ///
+    /// This does not check whether the actor has the permission required for
+    /// the action, such as [Kick Members].
+    ///
+    /// # Examples
+    ///
+    /// Check whether the current user can kick a member before doing so:
+    ///
+    /// ```rust,ignore
+    /// use serenity::client::CACHE;
+    ///
+    /// // assumes a `guild` and `user_id` have already been bound
+    /// let current_user_id = CACHE.read().unwrap().user.id;
+    ///
+    /// if guild.can_moderate(current_user_id, user_id).unwrap_or(false) {
+    ///     let _ = guild.kick(user_id);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::ItemMissing`] if either member is not in the
+    /// guild.
+    ///
+    /// [`ClientError::ItemMissing`]: ../client/enum.ClientError.html#variant.ItemMissing
+    /// [`highest_role`]: #method.highest_role
+    /// [Kick Members]: permissions/constant.KICK_MEMBERS.html
+    pub fn can_moderate<A, T>(&self, actor_id: A, target_id: T) -> Result<bool>
+        where A: Into<UserId>, T: Into<UserId> {
+        let (actor_id, target_id) = (actor_id.into(), target_id.into());
+
+        let actor = self.hierarchy_position(actor_id)?;
+        let target = self.hierarchy_position(target_id)?;
+
+        if actor_id == self.owner_id {
+            return Ok(actor_id != target_id);
+        }
+
+        Ok(target_id != self.owner_id && actor > target)
+    }
+
     /// Gets all of the guild's channels over the REST API.
     ///
     /// [`Guild`]: struct.Guild.html
@@ -212,6 +303,28 @@ impl Guild {
         self.id.channels()
     }
 
+    /// Retrieves the guild's channels in the order that they are displayed
+    /// by the client: text channels before voice channels, each sorted by
+    /// their position.
+    pub fn channels_by_position(&self) -> Vec<Arc<RwLock<GuildChannel>>> {
+        let mut channels = self.channels
+            .values()
+            .map(|channel| {
+                let key = {
+                    let channel = channel.read().unwrap();
+
+                    (channel.kind == ChannelType::Voice, channel.position, channel.id)
+                };
+
+                (key, channel.clone())
+            })
+            .collect::<Vec<_>>();
+
+        channels.sort_by_key(|&(key, _)| key);
+
+        channels.into_iter().map(|(_, channel)| channel).collect()
+    }
+
     /// Creates a guild with the data provided.
     ///
     /// Only a [`PartialGuild`] will be immediately returned, and a full
@@ -584,6 +697,24 @@ impl Guild {
         guild_id.into().get()
     }
 
+    /// Retrieves a member's highest [`Role`] in the role hierarchy.
+    ///
+    /// Returns `None` if the user is not a member of the guild, or the member
+    /// has no roles other than `@everyone`.
+    ///
+    /// Of roles sharing a position, the one with the lower Id is the higher.
+    ///
+    /// [`Role`]: struct.Role.html
+    pub fn highest_role<U: Into<UserId>>(&self, user_id: U) -> Option<&Role> {
+        self.members
+            .get(&user_id.into())
+            .and_then(|member| {
+                self.existing_member_roles(member)
+                    .into_iter()
+                    .max_by_key(|role| hierarchy_position(role))
+            })
+    }
+
     /// Returns the formatted URL of the guild's icon, if one exists.
     pub fn icon_url(&self) -> Option<String> {
         self.icon.as_ref().map(|icon|
@@ -712,6 +843,86 @@ impl Guild {
             }))
     }
 
+    /// Retrieves the guild's [`Member`]s which are connected to a voice
+    /// channel, in no particular order.
+    ///
+    /// Members who are connected to the channel but are not stored are not
+    /// included.
+    ///
+    /// [`Member`]: struct.Member.html
+    pub fn members_in_voice_channel<C: Into<ChannelId>>(&self, channel_id: C) -> Vec<&Member> {
+        let channel_id = Some(channel_id.into());
+
+        self.voice_states
+            .values()
+            .filter(|state| state.channel_id == channel_id)
+            .filter_map(|state| self.members.get(&state.user_id))
+            .collect()
+    }
+
+    /// Retrieves the guild's [`Member`]s whose presence is of a game with a
+    /// name containing the given name - ignoring case - in no particular
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// Count the number of members playing Overwatch:
+    ///
+    /// ```rust,ignore
+    /// // assumes a `guild` has already been bound
+    /// let count = guild.members_playing("overwatch").len();
+    /// ```
+    ///
+    /// [`Member`]: struct.Member.html
+    pub fn members_playing(&self, name: &str) -> Vec<&Member> {
+        let name = name.to_lowercase();
+
+        self.presences
+            .values()
+            .filter(|presence| presence.game.as_ref().map_or(false, |game| {
+                game.name.to_lowercase().contains(&name)
+            }))
+            .filter_map(|presence| self.members.get(&presence.user_id))
+            .collect()
+    }
+
+    /// Retrieves the guild's [`Member`]s which have a [`Role`], in no
+    /// particular order.
+    ///
+    /// Every member has the `@everyone` role, which has the same Id as the
+    /// guild.
+    ///
+    /// [`Member`]: struct.Member.html
+    /// [`Role`]: struct.Role.html
+    pub fn members_with_role<R: Into<RoleId>>(&self, role_id: R) -> Vec<&Member> {
+        let role_id = role_id.into();
+        let everyone = role_id.0 == self.id.0;
+
+        self.members
+            .values()
+            .filter(|member| everyone || member.roles.contains(&role_id))
+            .collect()
+    }
+
+    /// Retrieves the guild's [`Member`]s whose presence has an
+    /// [`OnlineStatus`], in no particular order.
+    ///
+    /// Members without a presence are considered to be
+    /// [`OnlineStatus::Offline`].
+    ///
+    /// [`Member`]: struct.Member.html
+    /// [`OnlineStatus`]: enum.OnlineStatus.html
+    /// [`OnlineStatus::Offline`]: enum.OnlineStatus.html#variant.Offline
+    pub fn members_with_status(&self, status: OnlineStatus) -> Vec<&Member> {
+        self.members
+            .iter()
+            .filter(|&(user_id, _)| {
+                self.presences.get(user_id).map_or(OnlineStatus::Offline, |p| p.status) == status
+            })
+            .map(|(_, member)| member)
+            .collect()
+    }
+
     /// Moves a member to a specific voice channel.
     ///
     /// Requires the [Move Members] permission.
@@ -725,11 +936,16 @@ impl Guild {
 
     /// Calculate a [`User`]'s permissions in a given channel in the guild.
     ///
+    /// If the user is not a cached member of the guild, then only the
+    /// permissions of the `@everyone` role are returned. Refer to
+    /// [`GuildChannel::permissions_for`] and [`Member::permissions`] to instead
+    /// receive an error when data is missing.
+    ///
+    /// [`GuildChannel::permissions_for`]: struct.GuildChannel.html#method.permissions_for
+    /// [`Member::permissions`]: struct.Member.html#method.permissions
     /// [`User`]: struct.User.html
     pub fn permissions_for<C, U>(&self, channel_id: C, user_id: U)
         -> Permissions where C: Into<ChannelId>, U: Into<UserId> {
-        use super::permissions::*;
-
         let user_id = user_id.into();
 
         // The owner has all permissions in all cases.
@@ -751,94 +967,135 @@ impl Guild {
             },
         };
 
-        // Create a base set of permissions, starting with `@everyone`s.
-        let mut permissions = everyone.permissions;
-
         let member = match self.members.get(&user_id) {
             Some(member) => member,
             None => return everyone.permissions,
         };
 
-        for &role in &member.roles {
-            if let Some(role) = self.roles.get(&role) {
-                permissions |= role.permissions;
-            } else {
-                warn!("(╯°□°）╯︵ ┻━┻ {} on {} has non-existent role {:?}",
-                      member.user.read().unwrap().id,
+        let roles = self.existing_member_roles(member);
+
+        match self.channels.get(&channel_id) {
+            Some(channel) => {
+                let channel = channel.read().unwrap();
+
+                permissions::calculate(everyone, &roles, user_id, Some(&channel))
+            },
+            None => {
+                warn!("(╯°□°）╯︵ ┻━┻ Guild {} does not contain channel {}",
                       self.id,
-                      role);
-            }
+                      channel_id);
+
+                permissions::calculate(everyone, &roles, user_id, None)
+            },
         }
+    }
 
-        // Administrators have all permissions in any channel.
-        if permissions.contains(ADMINISTRATOR) {
-            return Permissions::all();
+    // Calculates a member's permissions in the guild or one of its channels,
+    // returning an error if the `@everyone` role or any of the member's roles
+    // are missing rather than guessing.
+    #[doc(hidden)]
+    pub fn member_permissions(&self, member: &Member, channel: Option<&GuildChannel>)
+        -> Result<Permissions> {
+        let user_id = member.user.read().unwrap().id;
+
+        if user_id == self.owner_id {
+            return Ok(Permissions::all());
         }
 
-        if let Some(channel) = self.channels.get(&channel_id) {
-            let channel = channel.read().unwrap();
+        let everyone = match self.roles.get(&RoleId(self.id.0)) {
+            Some(everyone) => everyone,
+            None => return Err(Error::Client(ClientError::ItemMissing)),
+        };
+
+        let roles = self.member_roles(member)?;
 
-            // If this is a text channel, then throw out voice permissions.
-            if channel.kind == ChannelType::Text {
-                permissions &= !(CONNECT | SPEAK | MUTE_MEMBERS |
-                    DEAFEN_MEMBERS | MOVE_MEMBERS | USE_VAD);
-            }
+        Ok(permissions::calculate(everyone, &roles, user_id, channel))
+    }
 
-            // Apply the permission overwrites for the channel for each of the
-            // overwrites that - first - applies to the member's roles, and then
-            // the member itself.
-            //
-            // First apply the denied permission overwrites for each, then apply
-            // the allowed.
-
-            // Roles
-            for overwrite in &channel.permission_overwrites {
-                if let PermissionOverwriteType::Role(role) = overwrite.kind {
-                    if !member.roles.contains(&role) || role.0 == self.id.0 {
-                        continue;
-                    }
+    // The roles of a member, returning an error if any of them no longer
+    // exist rather than calculating with a partial set.
+    fn member_roles(&self, member: &Member) -> Result<Vec<&Role>> {
+        member.roles
+            .iter()
+            .map(|role_id| self.roles.get(role_id).ok_or(Error::Client(ClientError::ItemMissing)))
+            .collect()
+    }
 
-                    permissions = (permissions & !overwrite.deny) | overwrite.allow;
+    // The roles of a member, other than any which no longer exist.
+    fn existing_member_roles(&self, member: &Member) -> Vec<&Role> {
+        member.roles
+            .iter()
+            .filter_map(|role_id| {
+                let role = self.roles.get(role_id);
+
+                if role.is_none() {
+                    warn!("(╯°□°）╯︵ ┻━┻ {} on {} has non-existent role {:?}",
+                          member.user.read().unwrap().id,
+                          self.id,
+                          role_id);
                 }
-            }
 
-            // Member
-            for overwrite in &channel.permission_overwrites {
-                if PermissionOverwriteType::Member(user_id) != overwrite.kind {
-                    continue;
-                }
+                role
+            })
+            .collect()
+    }
 
-                permissions = (permissions & !overwrite.deny) | overwrite.allow;
-            }
-        } else {
-            warn!("(╯°□°）╯︵ ┻━┻ Guild {} does not contain channel {}",
-                  self.id,
-                  channel_id);
+    // Retrieves the position of a member's highest role in the hierarchy, with
+    // members without any roles sitting below every role.
+    fn hierarchy_position(&self, user_id: UserId) -> Result<Option<(i64, Reverse<RoleId>)>> {
+        if !self.members.contains_key(&user_id) {
+            return Err(Error::Client(ClientError::ItemMissing));
         }
 
-        // The default channel is always readable.
-        if channel_id.0 == self.id.0 {
-            permissions |= READ_MESSAGES;
-        }
+        Ok(self.highest_role(user_id).map(hierarchy_position))
+    }
 
-        // No SEND_MESSAGES => no message-sending-related actions
-        // If the member does not have the `SEND_MESSAGES` permission, then
-        // throw out message-able permissions.
-        if !permissions.contains(SEND_MESSAGES) {
-            permissions &= !(SEND_TTS_MESSAGES |
-                             MENTION_EVERYONE |
-                             EMBED_LINKS |
-                             ATTACH_FILES);
-        }
+    /// Explains how a member's permissions in a channel of the guild are
+    /// calculated, recording which roles, overwrites and rules allowed or
+    /// denied each permission.
+    ///
+    /// This is useful for diagnosing why a member can or can not perform an
+    /// action, as the explanation can be displayed as a report.
+    ///
+    /// # Examples
+    ///
+    /// Reply to a message with an explanation of its author's permissions:
+    ///
+    /// ```rust,ignore
+    /// // assumes a `guild` and `message` have already been bound
+    ///
+    /// let explanation = guild.explain_permissions(message.channel_id, message.author.id)?;
+    ///
+    /// let _ = message.reply(&format!("```\n{}```", explanation));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ClientError::ItemMissing`] if the member, the channel, the
+    /// `@everyone` role or any of the member's roles is not in the guild.
+    ///
+    /// [`ClientError::ItemMissing`]: ../client/enum.ClientError.html#variant.ItemMissing
+    pub fn explain_permissions<C, U>(&self, channel_id: C, user_id: U)
+        -> Result<permissions::PermissionsExplanation> where C: Into<ChannelId>, U: Into<UserId> {
+        let user_id = user_id.into();
 
-        // If the member does not have the `READ_MESSAGES` permission, then
-        // throw out actionable permissions.
-        if !permissions.contains(READ_MESSAGES) {
-            permissions &= KICK_MEMBERS | BAN_MEMBERS | ADMINISTRATOR |
-                MANAGE_GUILD | CHANGE_NICKNAME | MANAGE_NICKNAMES;
+        if user_id == self.owner_id {
+            return Ok(permissions::PermissionsExplanation::owner());
         }
 
-        permissions
+        let everyone = self.roles.get(&RoleId(self.id.0));
+        let member = self.members.get(&user_id);
+        let channel = self.channels.get(&channel_id.into());
+
+        match (everyone, member, channel) {
+            (Some(everyone), Some(member), Some(channel)) => {
+                let roles = self.member_roles(member)?;
+                let channel = channel.read().unwrap();
+
+                Ok(permissions::explain(everyone, &roles, user_id, Some(&channel)))
+            },
+            _ => Err(Error::Client(ClientError::ItemMissing)),
+        }
     }
 
     /// Retrieves the count of the number of [`Member`]s that would be pruned
@@ -870,6 +1127,73 @@ impl Guild {
         self.id.prune_count(days)
     }
 
+    /// Retrieves the guild's [`Role`]s in order of their hierarchy, with the
+    /// highest role first.
+    ///
+    /// [`Role`]: struct.Role.html
+    pub fn roles_by_position(&self) -> Vec<&Role> {
+        let mut roles = self.roles.values().collect::<Vec<_>>();
+        roles.sort_by(|a, b| b.cmp(a));
+
+        roles
+    }
+
+    /// Searches the guild's [`Member`]s by their username, username and
+    /// discriminator, or nickname, ignoring case.
+    ///
+    /// Unlike [`member_named`], members whose names only partially match are
+    /// also found. Members are ordered by how closely one of their names
+    /// matches the query:
+    ///
+    /// 1. the name is the query;
+    /// 2. the name starts with the query;
+    /// 3. the name contains the query;
+    /// 4. the name contains the characters of the query, in order.
+    ///
+    /// Members which match equally closely are ordered by their display name.
+    ///
+    /// # Examples
+    ///
+    /// Find members named similarly to "zey", such as "zeyla#5479" or
+    /// "Mitzey":
+    ///
+    /// ```rust,ignore
+    /// // assumes a `guild` has already been bound
+    /// for member in guild.search_members("zey").iter().take(10) {
+    ///     println!("{}", member.distinct());
+    /// }
+    /// ```
+    ///
+    /// [`Member`]: struct.Member.html
+    /// [`member_named`]: #method.member_named
+    pub fn search_members(&self, query: &str) -> Vec<&Member> {
+        let query = query.to_lowercase();
+
+        let mut found = self.members
+            .values()
+            .filter_map(|member| {
+                let user = member.user.read().unwrap();
+                let tag = format!("{}#{:04}", user.name, user.discriminator);
+                let rank = [Some(&user.name), member.nick.as_ref(), Some(&tag)]
+                    .iter()
+                    .filter_map(|name| name.and_then(|name| {
+                        closeness(&name.to_lowercase(), &query)
+                    }))
+                    .min();
+
+                rank.map(|rank| {
+                    let name = member.nick.as_ref().unwrap_or(&user.name).to_lowercase();
+
+                    ((rank, name, user.id), member)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+
+        found.into_iter().map(|(_, member)| member).collect()
+    }
+
     /// Returns the Id of the shard associated with the guild.
     ///
     /// When the cache is enabled this will automatically retrieve the total
@@ -1092,6 +1416,26 @@ impl Guild {
     }
 }
 
+// How closely a name matches a search query - both being lowercase - with
+// lower values being closer.
+fn closeness(name: &str, query: &str) -> Option<u8> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else {
+        let mut chars = name.chars();
+
+        if query.chars().all(|c| chars.any(|name_char| name_char == c)) {
+            Some(3)
+        } else {
+            None
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for Guild {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
         let mut map = JsonMap::deserialize(deserializer)?;
@@ -1306,14 +1650,28 @@ pub struct GuildUnavailable {
 }
 
 #[allow(large_enum_variant)]
-#[derive(Clone, Debug, Deserialize)]
-#[serde(untagged)]
+#[derive(Clone, Debug)]
 pub enum GuildStatus {
     OnlinePartialGuild(PartialGuild),
     OnlineGuild(Guild),
     Offline(GuildUnavailable),
 }
 
+impl<'de> Deserialize<'de> for GuildStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> StdResult<Self, D::Error> {
+        // Each variant is tried in turn from a `Value`, rather than deriving an
+        // untagged enum, as the ids of the guild can not be deserialized from
+        // the buffered content of an untagged enum.
+        let v = Value::deserialize(deserializer)?;
+
+        serde_json::from_value::<PartialGuild>(v.clone())
+            .map(GuildStatus::OnlinePartialGuild)
+            .or_else(|_| serde_json::from_value::<Guild>(v.clone()).map(GuildStatus::OnlineGuild))
+            .or_else(|_| serde_json::from_value::<GuildUnavailable>(v).map(GuildStatus::Offline))
+            .map_err(|_| DeError::custom("data did not match any variant of GuildStatus"))
+    }
+}
+
 impl GuildStatus {
     /// Retrieves the Id of the inner [`Guild`].
     ///
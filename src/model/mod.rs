@@ -19,6 +19,7 @@ mod utils;
 pub mod event;
 pub mod permissions;
 
+mod change;
 mod channel;
 mod gateway;
 mod guild;
@@ -28,6 +29,7 @@ mod user;
 mod voice;
 mod webhook;
 
+pub use self::change::Change;
 pub use self::channel::*;
 pub use self::gateway::*;
 pub use self::guild::*;
@@ -291,10 +293,15 @@ impl Region {
     }
 }
 
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::result::Result as StdResult;
 
 fn deserialize_sync_user<'de, D: Deserializer<'de>>(deserializer: D)
     -> StdResult<Arc<RwLock<User>>, D::Error> {
     Ok(Arc::new(RwLock::new(User::deserialize(deserializer)?)))
 }
+
+fn serialize_sync_user<S: Serializer>(user: &Arc<RwLock<User>>, serializer: S)
+    -> StdResult<S::Ok, S::Error> {
+    user.read().unwrap().serialize(serializer)
+}
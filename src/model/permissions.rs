@@ -38,9 +38,11 @@
 //! [Manage Webhooks]: constant.MANAGE_WEBHOOKS.html
 
 use serde::de::{Error as DeError, Visitor};
-use serde::{Deserialize, Deserializer};
-use std::fmt::{Formatter, Result as FmtResult};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::result::Result as StdResult;
+use super::{ChannelType, GuildChannel, PermissionOverwriteType, Role, RoleId, UserId};
 
 /// Returns a set of permissions with the original @everyone permissions set
 /// to true.
@@ -149,6 +151,368 @@ pub fn voice() -> Permissions {
     CONNECT | SPEAK | USE_VAD
 }
 
+/// Calculates the permissions of a user with the given roles, either in their
+/// guild or - if given - in one of its channels.
+///
+/// The guild's `@everyone` role must be given separately to the user's other
+/// roles. Any set of roles may be given, which allows calculating the
+/// permissions of a hypothetical member, and the channel's
+/// [`PermissionOverwrite`]s are applied in the order that Discord applies
+/// them:
+///
+/// 1. the overwrite for the `@everyone` role;
+/// 2. the overwrites for the user's roles, denying before allowing;
+/// 3. the overwrite for the user.
+///
+/// The owner of a guild has all permissions, which this does not account for.
+///
+/// # Examples
+///
+/// Calculate the permissions of a member if they were given a role:
+///
+/// ```rust,no_run
+/// use serenity::client::CACHE;
+/// use serenity::model::permissions;
+/// use serenity::model::{ChannelId, GuildId, RoleId, UserId};
+///
+/// let cache = CACHE.read().unwrap();
+/// let guild = cache.guild(GuildId(81384788765712384)).unwrap();
+/// let guild = guild.read().unwrap();
+/// let channel = guild.channels[&ChannelId(381880193700069377)].read().unwrap();
+/// let member = &guild.members[&UserId(114941315417899012)];
+///
+/// let everyone = &guild.roles[&RoleId(guild.id.0)];
+/// let mut roles = member.roles.iter().filter_map(|id| guild.roles.get(id)).collect::<Vec<_>>();
+/// roles.push(&guild.roles[&RoleId(131210592802291712)]);
+///
+/// let user_id = UserId(114941315417899012);
+/// let perms = permissions::calculate(everyone, &roles, user_id, Some(&channel));
+///
+/// println!("They would be able to send messages: {}", perms.send_messages());
+/// ```
+///
+/// [`PermissionOverwrite`]: ../struct.PermissionOverwrite.html
+pub fn calculate(everyone: &Role, roles: &[&Role], user_id: UserId, channel: Option<&GuildChannel>)
+    -> Permissions {
+    apply(everyone, roles, user_id, channel, |_, _, _| {})
+}
+
+/// Explains how the permissions of a user with the given roles are calculated
+/// via [`calculate`], recording for each permission which roles, overwrites
+/// and rules allowed or denied it.
+///
+/// The explanation can be displayed as a report, such as for a diagnostic
+/// command. Refer to [`Guild::explain_permissions`] to explain the
+/// permissions of a cached member.
+///
+/// [`Guild::explain_permissions`]: ../struct.Guild.html#method.explain_permissions
+/// [`calculate`]: fn.calculate.html
+pub fn explain(everyone: &Role, roles: &[&Role], user_id: UserId, channel: Option<&GuildChannel>)
+    -> PermissionsExplanation {
+    let mut steps = vec![];
+    let permissions = apply(everyone, roles, user_id, channel, |source, allow, deny| {
+        steps.push((source, allow, deny));
+    });
+
+    let mut role_names = HashMap::new();
+    role_names.insert(everyone.id, everyone.name.clone());
+
+    for role in roles {
+        role_names.insert(role.id, role.name.clone());
+    }
+
+    PermissionsExplanation::new(permissions, &steps, role_names)
+}
+
+// Calculates permissions as described by `calculate`, passing the permissions
+// allowed and denied by each step to `record`.
+fn apply<F>(everyone: &Role,
+            roles: &[&Role],
+            user_id: UserId,
+            channel: Option<&GuildChannel>,
+            mut record: F)
+            -> Permissions where F: FnMut(PermissionSource, Permissions, Permissions) {
+    use self::*;
+
+    let mut permissions = everyone.permissions;
+    record(PermissionSource::Everyone, everyone.permissions, Permissions::empty());
+
+    for role in roles {
+        permissions |= role.permissions;
+        record(PermissionSource::Role(role.id), role.permissions, Permissions::empty());
+    }
+
+    // Administrators have all permissions in any channel.
+    if permissions.contains(ADMINISTRATOR) {
+        record(PermissionSource::Administrator, Permissions::all(), Permissions::empty());
+
+        return Permissions::all();
+    }
+
+    let channel = match channel {
+        Some(channel) => channel,
+        None => return permissions,
+    };
+
+    // The `@everyone` role's Id is the same as the guild's.
+    let everyone_id = everyone.id;
+    let role_ids = roles.iter().map(|role| role.id).collect::<Vec<RoleId>>();
+
+    for overwrite in &channel.permission_overwrites {
+        if overwrite.kind == PermissionOverwriteType::Role(everyone_id) {
+            permissions = (permissions & !overwrite.deny) | overwrite.allow;
+            record(PermissionSource::RoleOverwrite(everyone_id), overwrite.allow, overwrite.deny);
+        }
+    }
+
+    // The overwrites of the user's roles are combined, with all of them
+    // denying before any of them allow.
+    let role_overwrites = channel.permission_overwrites
+        .iter()
+        .filter_map(|overwrite| match overwrite.kind {
+            PermissionOverwriteType::Role(id) if id != everyone_id && role_ids.contains(&id) => {
+                Some((id, overwrite))
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    for &(role_id, overwrite) in &role_overwrites {
+        permissions &= !overwrite.deny;
+        record(PermissionSource::RoleOverwrite(role_id), Permissions::empty(), overwrite.deny);
+    }
+
+    for &(role_id, overwrite) in &role_overwrites {
+        permissions |= overwrite.allow;
+        record(PermissionSource::RoleOverwrite(role_id), overwrite.allow, Permissions::empty());
+    }
+
+    for overwrite in &channel.permission_overwrites {
+        if overwrite.kind == PermissionOverwriteType::Member(user_id) {
+            permissions = (permissions & !overwrite.deny) | overwrite.allow;
+            record(PermissionSource::MemberOverwrite, overwrite.allow, overwrite.deny);
+        }
+    }
+
+    // If this is a text channel, then throw out voice permissions.
+    if channel.kind == ChannelType::Text {
+        let voice = CONNECT | SPEAK | MUTE_MEMBERS | DEAFEN_MEMBERS | MOVE_MEMBERS | USE_VAD;
+
+        record(PermissionSource::TextChannel, Permissions::empty(), permissions & voice);
+        permissions &= !voice;
+    }
+
+    // The default channel is always readable.
+    if channel.id.0 == everyone_id.0 {
+        permissions |= READ_MESSAGES;
+        record(PermissionSource::DefaultChannel, READ_MESSAGES, Permissions::empty());
+    }
+
+    // If the member does not have the `SEND_MESSAGES` permission, then throw
+    // out message-able permissions.
+    if !permissions.contains(SEND_MESSAGES) {
+        let sending = SEND_TTS_MESSAGES | MENTION_EVERYONE | EMBED_LINKS | ATTACH_FILES;
+
+        record(PermissionSource::NoSendMessages, Permissions::empty(), permissions & sending);
+        permissions &= !sending;
+    }
+
+    // If the member does not have the `READ_MESSAGES` permission, then throw
+    // out actionable permissions.
+    if !permissions.contains(READ_MESSAGES) {
+        let kept = KICK_MEMBERS | BAN_MEMBERS | ADMINISTRATOR | MANAGE_GUILD | CHANGE_NICKNAME |
+            MANAGE_NICKNAMES;
+
+        record(PermissionSource::NoReadMessages, Permissions::empty(), permissions & !kept);
+        permissions &= kept;
+    }
+
+    permissions
+}
+
+/// A role, overwrite or rule which allowed or denied permissions, as recorded
+/// in a [`PermissionsExplanation`].
+///
+/// [`PermissionsExplanation`]: struct.PermissionsExplanation.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PermissionSource {
+    /// The administrator permission allowed every permission.
+    Administrator,
+    /// The channel is the guild's default channel, which is always readable.
+    DefaultChannel,
+    /// The permissions of the guild's `@everyone` role.
+    Everyone,
+    /// The overwrite for the user in the channel.
+    MemberOverwrite,
+    /// Permissions which require reading messages were denied, as the user can
+    /// not read messages in the channel.
+    NoReadMessages,
+    /// Permissions which require sending messages were denied, as the user can
+    /// not send messages in the channel.
+    NoSendMessages,
+    /// The owner of the guild has every permission.
+    Owner,
+    /// The permissions of one of the user's roles.
+    Role(RoleId),
+    /// The overwrite for a role in the channel, which may be the `@everyone`
+    /// role.
+    RoleOverwrite(RoleId),
+    /// Voice permissions were denied, as the channel is a text channel.
+    TextChannel,
+}
+
+/// How a single permission was allowed or denied, as part of a
+/// [`PermissionsExplanation`].
+///
+/// [`PermissionsExplanation`]: struct.PermissionsExplanation.html
+#[derive(Clone, Debug)]
+pub struct PermissionExplanation {
+    /// Whether the permission is allowed.
+    pub allowed: bool,
+    /// The permission being explained, which is a single flag.
+    pub permission: Permissions,
+    /// The sources which allowed - `true` - or denied - `false` - the
+    /// permission, in the order they were applied. The last of these
+    /// determines whether the permission is allowed.
+    ///
+    /// A permission which is not allowed by any source is denied.
+    pub sources: Vec<(PermissionSource, bool)>,
+}
+
+/// An explanation of how a user's permissions were calculated, as returned by
+/// [`explain`].
+///
+/// This is displayed as a report, listing each permission along with the
+/// sources which allowed or denied it:
+///
+/// ```text
+/// Read Messages: denied
+///   + @everyone
+///   - overwrite for role Muted
+/// ```
+///
+/// [`explain`]: fn.explain.html
+#[derive(Clone, Debug)]
+pub struct PermissionsExplanation {
+    /// An explanation of each permission, ordered by the value of its flag.
+    pub bits: Vec<PermissionExplanation>,
+    /// The calculated permissions.
+    pub permissions: Permissions,
+    // The names of the roles referenced by the sources, for displaying.
+    role_names: HashMap<RoleId, String>,
+}
+
+impl PermissionsExplanation {
+    #[doc(hidden)]
+    pub fn owner() -> Self {
+        let steps = [(PermissionSource::Owner, Permissions::all(), Permissions::empty())];
+
+        PermissionsExplanation::new(Permissions::all(), &steps, HashMap::new())
+    }
+
+    fn new(permissions: Permissions,
+           steps: &[(PermissionSource, Permissions, Permissions)],
+           role_names: HashMap<RoleId, String>)
+           -> Self {
+        let bits = NAMES.iter().map(|&(permission, _)| {
+            let sources = steps.iter()
+                .filter(|&&(_, allow, deny)| (allow | deny).contains(permission))
+                .map(|&(source, allow, _)| (source, allow.contains(permission)))
+                .collect();
+
+            PermissionExplanation {
+                allowed: permissions.contains(permission),
+                permission: permission,
+                sources: sources,
+            }
+        }).collect();
+
+        PermissionsExplanation {
+            bits: bits,
+            permissions: permissions,
+            role_names: role_names,
+        }
+    }
+
+    /// Retrieves the explanation of a single permission.
+    ///
+    /// Returns `None` if the given permissions are not a single flag.
+    pub fn get(&self, permission: Permissions) -> Option<&PermissionExplanation> {
+        self.bits.iter().find(|bit| bit.permission == permission)
+    }
+
+    fn role_name(&self, role_id: RoleId) -> String {
+        match self.role_names.get(&role_id) {
+            Some(name) => name.clone(),
+            None => role_id.to_string(),
+        }
+    }
+}
+
+impl Display for PermissionsExplanation {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for bit in &self.bits {
+            let name = NAMES.iter()
+                .find(|&&(permission, _)| permission == bit.permission)
+                .map_or("Unknown", |&(_, name)| name);
+
+            writeln!(f, "{}: {}", name, if bit.allowed { "allowed" } else { "denied" })?;
+
+            for &(source, allowed) in &bit.sources {
+                let source = match source {
+                    PermissionSource::Administrator => "administrator".to_owned(),
+                    PermissionSource::DefaultChannel => "default channel".to_owned(),
+                    PermissionSource::Everyone => "@everyone".to_owned(),
+                    PermissionSource::MemberOverwrite => "overwrite for member".to_owned(),
+                    PermissionSource::NoReadMessages => "cannot read messages".to_owned(),
+                    PermissionSource::NoSendMessages => "cannot send messages".to_owned(),
+                    PermissionSource::Owner => "guild owner".to_owned(),
+                    PermissionSource::Role(role_id) => format!("role {}", self.role_name(role_id)),
+                    PermissionSource::RoleOverwrite(role_id) => {
+                        format!("overwrite for role {}", self.role_name(role_id))
+                    },
+                    PermissionSource::TextChannel => "text channel".to_owned(),
+                };
+
+                writeln!(f, "  {} {}", if allowed { "+" } else { "-" }, source)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The display names of each permission, ordered by the value of their flags.
+static NAMES: &'static [(Permissions, &'static str)] = &[
+    (CREATE_INVITE, "Create Invite"),
+    (KICK_MEMBERS, "Kick Members"),
+    (BAN_MEMBERS, "Ban Members"),
+    (ADMINISTRATOR, "Administrator"),
+    (MANAGE_CHANNELS, "Manage Channels"),
+    (MANAGE_GUILD, "Manage Guild"),
+    (ADD_REACTIONS, "Add Reactions"),
+    (READ_MESSAGES, "Read Messages"),
+    (SEND_MESSAGES, "Send Messages"),
+    (SEND_TTS_MESSAGES, "Send TTS Messages"),
+    (MANAGE_MESSAGES, "Manage Messages"),
+    (EMBED_LINKS, "Embed Links"),
+    (ATTACH_FILES, "Attach Files"),
+    (READ_MESSAGE_HISTORY, "Read Message History"),
+    (MENTION_EVERYONE, "Mention Everyone"),
+    (USE_EXTERNAL_EMOJIS, "Use External Emojis"),
+    (CONNECT, "Connect"),
+    (SPEAK, "Speak"),
+    (MUTE_MEMBERS, "Mute Members"),
+    (DEAFEN_MEMBERS, "Deafen Members"),
+    (MOVE_MEMBERS, "Move Members"),
+    (USE_VAD, "Use VAD"),
+    (CHANGE_NICKNAME, "Change Nickname"),
+    (MANAGE_NICKNAMES, "Manage Nicknames"),
+    (MANAGE_ROLES, "Manage Roles"),
+    (MANAGE_WEBHOOKS, "Manage Webhooks"),
+    (MANAGE_EMOJIS, "Manage Emojis"),
+];
+
 bitflags! {
     /// A set of permissions that can be assigned to [`User`]s and [`Role`]s via
     /// [`PermissionOverwrite`]s, roles globally in a [`Guild`], and to
@@ -488,6 +852,12 @@ impl<'de> Deserialize<'de> for Permissions {
     }
 }
 
+impl Serialize for Permissions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> StdResult<S::Ok, S::Error> {
+        serializer.serialize_u64(self.bits())
+    }
+}
+
 struct U64Visitor;
 
 impl<'de> Visitor<'de> for U64Visitor {
@@ -49,7 +49,7 @@ pub enum ConnectionType {
 }
 
 /// Information about the current user.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CurrentUser {
     pub id: UserId,
     pub avatar: Option<String>,
@@ -304,7 +304,7 @@ pub struct Tutorial {
 }
 
 /// Information about a user.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct User {
     /// The unique Id of the user. Can be used to calculate the account's
     /// cration date.
@@ -1,5 +1,7 @@
 use serde::de::Error as DeError;
+use serde::ser::{Serialize, SerializeSeq, Serializer};
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::{Arc, RwLock};
 use super::*;
 
@@ -115,6 +117,38 @@ pub fn deserialize_users<'de, D: Deserializer<'de>>(deserializer: D)
     Ok(users)
 }
 
+// Serializes the values of a map as a sequence, the inverse of the map
+// deserializers above.
+pub fn serialize_gen_map<K, S, V>(map: &HashMap<K, V>, serializer: S)
+    -> StdResult<S::Ok, S::Error> where K: Eq + Hash, S: Serializer, V: Serialize {
+    let mut seq = serializer.serialize_seq(Some(map.len()))?;
+
+    for value in map.values() {
+        seq.serialize_element(value)?;
+    }
+
+    seq.end()
+}
+
+pub fn serialize_gen_locked_map<K, S, V>(map: &HashMap<K, Arc<RwLock<V>>>, serializer: S)
+    -> StdResult<S::Ok, S::Error> where K: Eq + Hash, S: Serializer, V: Serialize {
+    let mut seq = serializer.serialize_seq(Some(map.len()))?;
+
+    for value in map.values() {
+        seq.serialize_element(&*value.read().unwrap())?;
+    }
+
+    seq.end()
+}
+
+pub fn serialize_single_recipient<S: Serializer>(user: &Arc<RwLock<User>>, serializer: S)
+    -> StdResult<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(1))?;
+    seq.serialize_element(&*user.read().unwrap())?;
+
+    seq.end()
+}
+
 pub fn deserialize_u16<'de, D: Deserializer<'de>>(deserializer: D)
     -> StdResult<u16, D::Error> {
     deserializer.deserialize_u16(U16Visitor)
@@ -22,7 +22,7 @@ pub struct VoiceRegion {
 }
 
 /// A user's state within a voice channel.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct VoiceState {
     pub channel_id: Option<ChannelId>,
     pub deaf: bool,
@@ -15,6 +15,7 @@ mod edit_profile;
 mod edit_role;
 mod execute_webhook;
 mod get_messages;
+mod request_guild_members;
 
 pub use self::create_embed::{
     CreateEmbed,
@@ -31,3 +32,4 @@ pub use self::edit_profile::EditProfile;
 pub use self::edit_role::EditRole;
 pub use self::execute_webhook::ExecuteWebhook;
 pub use self::get_messages::GetMessages;
+pub use self::request_guild_members::RequestGuildMembers;
@@ -0,0 +1,53 @@
+use ::model::UserId;
+use ::internal::prelude::*;
+
+/// A builder to specify which members of one or more [`Guild`]s to request
+/// over the gateway, to be used in conjunction with
+/// [`Context::request_guild_members`] and [`Shard::request_guild_members`].
+///
+/// By default, all members of the guilds are requested.
+///
+/// [`Context::request_guild_members`]: ../../client/struct.Context.html#method.request_guild_members
+/// [`Guild`]: ../../model/struct.Guild.html
+/// [`Shard::request_guild_members`]: ../../client/gateway/struct.Shard.html#method.request_guild_members
+#[derive(Default)]
+pub struct RequestGuildMembers(pub JsonMap);
+
+impl RequestGuildMembers {
+    /// The maximum number of members to receive per guild. A limit of `0`
+    /// requests all members matching the [`query`].
+    ///
+    /// [`query`]: #method.query
+    pub fn limit(mut self, limit: u16) -> Self {
+        self.0.insert("limit".to_owned(), Value::Number(Number::from(limit)));
+
+        self
+    }
+
+    /// Only request members whose username starts with the given string. An
+    /// empty string matches all members.
+    ///
+    /// This is mutually exclusive with [`user_ids`].
+    ///
+    /// [`user_ids`]: #method.user_ids
+    pub fn query(mut self, query: &str) -> Self {
+        self.0.remove("user_ids");
+        self.0.insert("query".to_owned(), Value::String(query.to_owned()));
+
+        self
+    }
+
+    /// Only request the members with the given user Ids.
+    ///
+    /// This is mutually exclusive with [`query`].
+    ///
+    /// [`query`]: #method.query
+    pub fn user_ids(mut self, user_ids: &[UserId]) -> Self {
+        let ids = user_ids.iter().map(|x| Value::Number(Number::from(x.0))).collect();
+
+        self.0.remove("query");
+        self.0.insert("user_ids".to_owned(), Value::Array(ids));
+
+        self
+    }
+}
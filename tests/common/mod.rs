@@ -0,0 +1,11 @@
+// Fixtures shared by the tests, which include this module via `#[macro_use]`.
+
+// Deserializes a type from a JSON file in `tests/resources`.
+macro_rules! p {
+    ($s:ident, $filename:expr) => {{
+        let f = ::std::fs::File::open(concat!("./tests/resources/", $filename, ".json")).unwrap();
+        let v = ::serde_json::from_reader::<_, ::serde_json::Value>(f).unwrap();
+
+        <$s as ::serde::de::Deserialize>::deserialize(v).unwrap()
+    }}
+}
@@ -0,0 +1,149 @@
+#![cfg(feature="cache")]
+
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::ext::cache::{Cache, CacheConfig};
+use serenity::model::event::*;
+use serenity::model::*;
+use std::time::Duration;
+
+// The large guild of `guild_create_1`, and one of its members.
+const GUILD_ID: u64 = 81384788765712384;
+const MEMBER_ID: u64 = 145265491093487617;
+
+fn cache(config: CacheConfig) -> Cache {
+    let mut cache = Cache::default();
+    cache.config = config;
+    cache.user.id = UserId(MEMBER_ID);
+    cache.update_with_guild_create(&p!(GuildCreateEvent, "guild_create_1"));
+
+    cache
+}
+
+fn member_add() -> GuildMemberAddEvent {
+    GuildMemberAddEvent {
+        guild_id: GuildId(GUILD_ID),
+        member: p!(Member, "guild_member_add_1"),
+    }
+}
+
+#[test]
+fn stores_everything_by_default() {
+    let mut cache = cache(CacheConfig::default());
+    cache.update_with_guild_member_add(&mut member_add());
+
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let guild = guild.read().unwrap();
+    assert_eq!(guild.members.len(), 9594);
+    assert_eq!(guild.presences.len(), 8834);
+}
+
+#[test]
+fn large_guild_members() {
+    let mut cache = cache(CacheConfig::default().large_guild_members(false));
+    cache.update_with_guild_member_add(&mut member_add());
+
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let guild = guild.read().unwrap();
+
+    // Only the current user's member is kept, while the count is updated.
+    assert_eq!(guild.members.keys().collect::<Vec<_>>(), vec![&UserId(MEMBER_ID)]);
+    assert_eq!(guild.member_count, 18553);
+    assert!(cache.user(249608457672458240).is_none());
+}
+
+#[test]
+fn max_members_and_presences() {
+    let mut cache = cache(CacheConfig::default().max_members(100).max_presences(10));
+    cache.update_with_guild_member_add(&mut member_add());
+
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let guild = guild.read().unwrap();
+    assert_eq!(guild.members.len(), 100);
+    assert!(guild.members.contains_key(&UserId(MEMBER_ID)));
+    assert_eq!(guild.presences.len(), 10);
+}
+
+#[test]
+fn presences() {
+    let mut cache = cache(CacheConfig::default().presences(false));
+
+    let mut event = PresenceUpdateEvent {
+        guild_id: None,
+        presence: Presence {
+            game: None,
+            last_modified: None,
+            nick: None,
+            status: OnlineStatus::Online,
+            user: None,
+            user_id: UserId(1),
+        },
+        roles: None,
+    };
+    cache.update_with_presence_update(&mut event);
+
+    assert!(cache.presences.is_empty());
+    assert!(cache.guild(GUILD_ID).unwrap().read().unwrap().presences.is_empty());
+}
+
+#[test]
+fn prune_users() {
+    let config = CacheConfig::default().prune_users(true).prune_interval(Duration::from_secs(0));
+    let mut cache = cache(config);
+
+    // Removing a member removes their user, unless they are otherwise
+    // referenced, such as by a presence.
+    let member = {
+        let guild = cache.guild(GUILD_ID).unwrap();
+        let guild = guild.read().unwrap();
+
+        guild.members
+            .iter()
+            .find(|&(user_id, _)| *user_id != MEMBER_ID && !guild.presences.contains_key(user_id))
+            .unwrap()
+            .1
+            .clone()
+    };
+    let user = member.user.read().unwrap().clone();
+    let user_id = user.id;
+
+    cache.update_with_guild_member_remove(&GuildMemberRemoveEvent {
+        guild_id: GuildId(GUILD_ID),
+        user: user,
+    });
+    assert!(cache.user(user_id).is_none());
+
+    let users = cache.users.len();
+    cache.update_with_guild_unavailable(&GuildUnavailableEvent {
+        guild_id: GuildId(GUILD_ID),
+    });
+    assert!(users > 1);
+    assert_eq!(cache.users.keys().collect::<Vec<_>>(), vec![&UserId(MEMBER_ID)]);
+    assert_eq!(cache.prune_users(), 0);
+}
+
+#[test]
+fn prune_interval() {
+    let mut cache = cache(CacheConfig::default().prune_users(true));
+
+    // The first removal prunes, but those within the interval after it are
+    // left for a later prune.
+    cache.update_with_guild_member_remove(&GuildMemberRemoveEvent {
+        guild_id: GuildId(GUILD_ID),
+        user: cache.user(MEMBER_ID).unwrap().read().unwrap().clone(),
+    });
+
+    let users = cache.users.len();
+    cache.update_with_guild_unavailable(&GuildUnavailableEvent {
+        guild_id: GuildId(GUILD_ID),
+    });
+    assert_eq!(cache.users.len(), users);
+
+    assert_eq!(cache.prune_users(), users - 1);
+    assert_eq!(cache.users.keys().collect::<Vec<_>>(), vec![&UserId(MEMBER_ID)]);
+}
@@ -0,0 +1,100 @@
+#![cfg(feature="cache")]
+
+#[macro_use] extern crate serde_json;
+extern crate serenity;
+
+use serde_json::Value;
+use serenity::client::gateway::Recording;
+use serenity::ext::cache::Cache;
+use serenity::model::event::{Event, GatewayEvent};
+use serenity::model::*;
+
+const GUILD_ID: u64 = 300000000000000000;
+const ALICE_ID: u64 = 300000000000000001;
+const BOB_ID: u64 = 300000000000000002;
+
+fn decode(payload: Value) -> Event {
+    match GatewayEvent::decode(payload).unwrap() {
+        GatewayEvent::Dispatch(_, event) => event,
+        other => panic!("expected a dispatch, got {:?}", other),
+    }
+}
+
+fn has_presence(cache: &Cache, user_id: u64) -> bool {
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let has_presence = guild.read().unwrap().presences.contains_key(&UserId(user_id));
+
+    has_presence
+}
+
+fn session() -> Vec<Event> {
+    Recording::open("./tests/resources/session_2.jsonl")
+        .unwrap()
+        .map(|recorded| recorded.unwrap().payload)
+        .filter(|payload| payload["op"] == 0)
+        .map(decode)
+        .collect()
+}
+
+#[test]
+fn updates_in_place() {
+    let mut cache = Cache::default();
+    let mut applied = vec![];
+
+    for event in session() {
+        let in_place = cache.try_update(&event);
+
+        if !in_place {
+            cache.update(&event);
+        }
+
+        applied.push(in_place);
+    }
+
+    // Only the guild create and the channel update replace entries in the
+    // cache's own maps.
+    assert_eq!(applied, vec![false, true, true, false, true, true, true, true]);
+
+    let member = cache.member(GUILD_ID, ALICE_ID).unwrap();
+    assert_eq!(member.nick, Some("al".to_owned()));
+    assert_eq!(member.roles, vec![RoleId(300000000000000011)]);
+    assert_eq!(cache.role(GUILD_ID, 300000000000000010).unwrap().name, "Regulars");
+
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let guild = guild.read().unwrap();
+    assert_eq!(guild.voice_states[&UserId(ALICE_ID)].channel_id,
+               Some(ChannelId(300000000000000021)));
+    assert!(!guild.voice_states.contains_key(&UserId(BOB_ID)));
+}
+
+#[test]
+fn new_users_need_write() {
+    let mut cache = Cache::default();
+    cache.update(&session()[0]);
+
+    let presence = |user: Value| decode(json!({
+        "op": 0,
+        "s": 1,
+        "t": "PRESENCE_UPDATE",
+        "d": {"game": null, "guild_id": GUILD_ID.to_string(), "status": "online", "user": user},
+    }));
+
+    // A presence of a known user is stored in place.
+    assert!(cache.try_update(&presence(json!({"id": BOB_ID.to_string()}))));
+    assert!(has_presence(&cache, BOB_ID));
+
+    // The user of a new presence must be added, and so nothing is updated.
+    let event = presence(json!({
+        "avatar": null,
+        "discriminator": "0001",
+        "id": "300000000000000003",
+        "username": "carol",
+    }));
+    assert!(!cache.try_update(&event));
+    assert!(cache.user(300000000000000003).is_none());
+    assert!(!has_presence(&cache, 300000000000000003));
+
+    cache.update(&event);
+    assert_eq!(cache.user(300000000000000003).unwrap().read().unwrap().name, "carol");
+    assert!(has_presence(&cache, 300000000000000003));
+}
@@ -0,0 +1,178 @@
+#![cfg(feature="cache")]
+
+extern crate hyper;
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::client::CACHE;
+use serenity::ext::cache::lookup;
+use serenity::model::event::GuildCreateEvent;
+use serenity::model::*;
+use std::sync::{ONCE_INIT, Once};
+
+#[cfg(feature="fake-gateway")]
+use hyper::status::StatusCode;
+#[cfg(feature="fake-gateway")]
+use serde::de::Deserialize;
+#[cfg(feature="fake-gateway")]
+use serde_json::Value;
+#[cfg(feature="fake-gateway")]
+use serenity::client::ClientError;
+#[cfg(feature="fake-gateway")]
+use serenity::ext::cache::lookup::Requests;
+#[cfg(feature="fake-gateway")]
+use serenity::model::event::{Event, GuildMemberAddEvent};
+#[cfg(feature="fake-gateway")]
+use serenity::Error;
+#[cfg(feature="fake-gateway")]
+use std::sync::atomic::{ATOMIC_USIZE_INIT, AtomicUsize, Ordering};
+#[cfg(feature="fake-gateway")]
+use std::time::Duration;
+
+const GUILD_ID: u64 = 81384788765712384;
+
+static SETUP: Once = ONCE_INIT;
+#[cfg(feature="fake-gateway")]
+static MEMBER_REQUESTS: AtomicUsize = ATOMIC_USIZE_INIT;
+#[cfg(feature="fake-gateway")]
+static ROLE_REQUESTS: AtomicUsize = ATOMIC_USIZE_INIT;
+#[cfg(feature="fake-gateway")]
+static USER_REQUESTS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+#[cfg(feature="fake-gateway")]
+fn not_found() -> Error {
+    Error::Client(ClientError::InvalidRequest(StatusCode::NotFound))
+}
+
+#[cfg(feature="fake-gateway")]
+fn stub_member(_guild_id: GuildId, user_id: UserId) -> serenity::Result<Member> {
+    MEMBER_REQUESTS.fetch_add(1, Ordering::SeqCst);
+
+    let member: Member = p!(Member, "guild_member_add_1");
+    member.user.write().unwrap().id = user_id;
+
+    Ok(member)
+}
+
+#[cfg(feature="fake-gateway")]
+fn stub_roles(_guild_id: GuildId) -> serenity::Result<Vec<Role>> {
+    ROLE_REQUESTS.fetch_add(1, Ordering::SeqCst);
+
+    let role: Value = serde_json::from_str(r#"{
+        "color": 0,
+        "hoist": false,
+        "id": "2",
+        "managed": false,
+        "name": "Requested",
+        "permissions": 0,
+        "position": 1
+    }"#).unwrap();
+
+    Ok(vec![Role::deserialize(role).unwrap()])
+}
+
+#[cfg(feature="fake-gateway")]
+fn stub_user(_user_id: UserId) -> serenity::Result<User> {
+    USER_REQUESTS.fetch_add(1, Ordering::SeqCst);
+
+    Err(not_found())
+}
+
+// Caches the guild - and, with the `fake-gateway` feature, replaces the
+// requests made for uncached items - once for all of the tests, as both are
+// global.
+fn setup() {
+    SETUP.call_once(|| {
+        CACHE.write().unwrap().update_with_guild_create(&GuildCreateEvent {
+            guild: p!(Guild, "guild_create_1"),
+        });
+
+        #[cfg(feature="fake-gateway")]
+        lookup::set_requests(Requests {
+            channel: |_| Err(not_found()),
+            guild: |_| Err(not_found()),
+            member: stub_member,
+            roles: stub_roles,
+            user: stub_user,
+        });
+    });
+}
+
+#[test]
+fn cached_lookups() {
+    setup();
+
+    // Each of these is found in the cache, and so no request is made.
+    let guild = lookup::guild(GUILD_ID).unwrap();
+    assert_eq!(guild.name, "Discord API");
+    assert_eq!(guild.roles.len(), 51);
+
+    match lookup::channel(290944320192774154).unwrap() {
+        Channel::Guild(channel) => assert_eq!(channel.read().unwrap().name, "elixir_nostrum"),
+        other => panic!("expected a guild channel, got {:?}", other),
+    }
+
+    let member = lookup::member(GUILD_ID, 145265491093487617).unwrap();
+    assert_eq!(member.user.read().unwrap().name, "Dadragon6");
+    assert_eq!(lookup::user(145265491093487617).unwrap().name, "Dadragon6");
+    assert_eq!(lookup::role(GUILD_ID, 158331219535331328).unwrap().name, "Admin");
+}
+
+#[cfg(feature="fake-gateway")]
+#[test]
+fn requested_items_are_cached() {
+    setup();
+
+    let member = lookup::member(GUILD_ID, 1).unwrap();
+    assert_eq!(member.user.read().unwrap().id, UserId(1));
+    assert_eq!(MEMBER_REQUESTS.load(Ordering::SeqCst), 1);
+    assert!(CACHE.read().unwrap().member(GUILD_ID, 1).is_some());
+
+    // The member is now cached, and so is not requested again.
+    assert!(lookup::member(GUILD_ID, 1).is_ok());
+    assert_eq!(MEMBER_REQUESTS.load(Ordering::SeqCst), 1);
+
+    assert_eq!(lookup::role(GUILD_ID, 2).unwrap().name, "Requested");
+    assert_eq!(ROLE_REQUESTS.load(Ordering::SeqCst), 1);
+    assert_eq!(lookup::role(GUILD_ID, 2).unwrap().name, "Requested");
+    assert_eq!(ROLE_REQUESTS.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature="fake-gateway")]
+#[test]
+fn missing_items_are_remembered() {
+    setup();
+
+    let is_not_found = |result: serenity::Result<User>| match result {
+        Err(Error::Client(ClientError::InvalidRequest(StatusCode::NotFound))) => true,
+        _ => false,
+    };
+
+    assert!(is_not_found(lookup::user(404)));
+    assert_eq!(USER_REQUESTS.load(Ordering::SeqCst), 1);
+
+    // The miss is remembered, and so the user is not requested again.
+    assert!(is_not_found(lookup::user(404)));
+    assert_eq!(USER_REQUESTS.load(Ordering::SeqCst), 1);
+
+    // The user joining a guild which is not cached shows that they exist.
+    let member: Member = p!(Member, "guild_member_add_1");
+    member.user.write().unwrap().id = UserId(404);
+    lookup::update(&Event::GuildMemberAdd(GuildMemberAddEvent {
+        guild_id: GuildId(5),
+        member: member,
+    }));
+
+    assert!(is_not_found(lookup::user(404)));
+    assert_eq!(USER_REQUESTS.load(Ordering::SeqCst), 2);
+
+    // Once the miss expires, the user is requested again.
+    CACHE.write().unwrap().config.negative_ttl = Duration::from_secs(0);
+
+    assert!(is_not_found(lookup::user(404)));
+    assert_eq!(USER_REQUESTS.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,122 @@
+#![cfg(feature="fake-gateway")]
+
+extern crate serde_json;
+extern crate serenity;
+
+use serde_json::Value;
+use serenity::client::gateway::{FakeGateway, Recording};
+use serenity::model::*;
+use serenity::Client;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+fn ready(guild_ids: &[u64]) -> Value {
+    let guilds = guild_ids.iter()
+        .map(|id| serde_json::from_str(&format!(r#"{{"id": "{}", "unavailable": true}}"#, id)).unwrap())
+        .collect::<Vec<Value>>();
+
+    let mut ready: Value = serde_json::from_str(r#"{
+        "presences": [],
+        "private_channels": [],
+        "session_id": "",
+        "shard": null,
+        "user": {
+            "avatar": null,
+            "bot": true,
+            "discriminator": "0001",
+            "email": null,
+            "id": "1",
+            "mfa_enabled": false,
+            "username": "fake",
+            "verified": true
+        },
+        "v": 6
+    }"#).unwrap();
+    ready["guilds"] = Value::Array(guilds);
+
+    ready
+}
+
+#[test]
+fn cache_ready() {
+    // The guild sent in the second payload of the recorded session.
+    let guild = Recording::open("./tests/resources/session_2.jsonl")
+        .unwrap()
+        .nth(1)
+        .unwrap()
+        .unwrap()
+        .payload["d"]
+        .clone();
+
+    let gateway = FakeGateway::start().unwrap();
+    gateway.set_ready(ready(&[300000000000000000, 2]));
+
+    let (tx, rx) = mpsc::channel();
+    let shard_tx = Mutex::new(tx.clone());
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&gateway.url());
+    client.on_shard_cache_ready(move |_context, unavailable| {
+        let _ = shard_tx.lock().unwrap().send(("shard", unavailable));
+    });
+    client.on_cache_ready(move |_context, unavailable| {
+        let _ = tx.lock().unwrap().send(("client", unavailable));
+    });
+
+    thread::spawn(move || client.start());
+
+    assert!(gateway.wait_for(Duration::from_secs(10), |p| p["op"] == 2).is_some());
+    assert!(gateway.dispatch("GUILD_CREATE", guild));
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+    let unavailable = serde_json::from_str(r#"{"id": "2", "unavailable": true}"#).unwrap();
+    assert!(gateway.dispatch("GUILD_DELETE", unavailable));
+
+    let mut received = vec![
+        rx.recv_timeout(Duration::from_secs(10)).unwrap(),
+        rx.recv_timeout(Duration::from_secs(10)).unwrap(),
+    ];
+    received.sort();
+
+    assert_eq!(received, vec![("client", vec![GuildId(2)]), ("shard", vec![GuildId(2)])]);
+}
+
+#[test]
+fn cache_ready_is_per_client() {
+    let waiting = FakeGateway::start().unwrap();
+    waiting.set_ready(ready(&[2]));
+    let other = FakeGateway::start().unwrap();
+    other.set_ready(ready(&[]));
+
+    let (tx, rx) = mpsc::channel();
+    let other_tx = Mutex::new(tx.clone());
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&waiting.url());
+    client.on_cache_ready(move |_context, unavailable| {
+        let _ = tx.lock().unwrap().send(("waiting", unavailable));
+    });
+
+    let mut other_client = Client::login("fake token");
+    other_client.with_gateway_url(&other.url());
+    other_client.on_cache_ready(move |_context, unavailable| {
+        let _ = other_tx.lock().unwrap().send(("other", unavailable));
+    });
+
+    thread::spawn(move || client.start());
+    assert!(waiting.wait_for(Duration::from_secs(10), |p| p["op"] == 2).is_some());
+
+    // Another client becoming ready must not affect the guilds the first is
+    // waiting on.
+    thread::spawn(move || other_client.start());
+    assert_eq!(rx.recv_timeout(Duration::from_secs(10)).unwrap(), ("other", vec![]));
+
+    let unavailable = serde_json::from_str(r#"{"id": "2", "unavailable": true}"#).unwrap();
+    assert!(waiting.dispatch("GUILD_DELETE", unavailable));
+
+    assert_eq!(rx.recv_timeout(Duration::from_secs(10)).unwrap(), ("waiting", vec![GuildId(2)]));
+}
@@ -0,0 +1,59 @@
+#![cfg(feature="cache")]
+
+extern crate serenity;
+
+use serenity::Client;
+use serenity::ext::cache::{Cache, CacheSink};
+use serenity::model::event::Event;
+use serenity::model::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+// An in-process stand-in for a store shared between processes, which counts
+// the events applied to it.
+struct Shared {
+    cache: Arc<RwLock<Cache>>,
+    events: Arc<AtomicUsize>,
+}
+
+impl CacheSink for Shared {
+    fn update(&self, event: &Event) {
+        self.events.fetch_add(1, Ordering::SeqCst);
+        self.cache.update(event);
+    }
+}
+
+const GUILD_ID: GuildId = GuildId(300000000000000000);
+const ALICE_ID: UserId = UserId(300000000000000001);
+
+#[test]
+fn cache_sink() {
+    let cache = Arc::new(RwLock::new(Cache::default()));
+    let events = Arc::new(AtomicUsize::new(0));
+
+    let mut client = Client::login("");
+    client.with_cache_sink(Shared {
+        cache: cache.clone(),
+        events: events.clone(),
+    });
+    client.replay("./tests/resources/session_2.jsonl", None).unwrap();
+
+    // The hello is not dispatched, while every other event is.
+    assert_eq!(events.load(Ordering::SeqCst), 8);
+
+    let cache = cache.read().unwrap();
+    assert_eq!(cache.guild(GUILD_ID).unwrap().read().unwrap().name, "Derived Events");
+    assert_eq!(cache.user(ALICE_ID).unwrap().read().unwrap().name, "alice");
+    assert_eq!(cache.role(GUILD_ID, RoleId(300000000000000010)).unwrap().name, "Regulars");
+
+    let member = cache.member(GUILD_ID, ALICE_ID).unwrap();
+    assert_eq!(member.nick, Some("al".to_owned()));
+    assert_eq!(member.roles, vec![RoleId(300000000000000011)]);
+
+    match cache.channel(ChannelId(300000000000000020)) {
+        Some(Channel::Guild(channel)) => assert_eq!(channel.read().unwrap().name, "lobby"),
+        _ => panic!("guild channel not found"),
+    }
+
+    assert!(cache.guild(GuildId(1)).is_none());
+}
@@ -0,0 +1,83 @@
+#![cfg(feature="cache")]
+
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serde::de::Deserialize;
+use serde_json::Value;
+use serenity::ext::cache::Cache;
+use serenity::model::event::*;
+use serenity::model::*;
+use std::env;
+use std::fs;
+use std::sync::Arc;
+
+const GUILD_ID: u64 = 81384788765712384;
+
+fn ready(guild_ids: &[u64]) -> ReadyEvent {
+    let mut v: Value = p!(Value, "ready_1");
+    v["guilds"] = Value::Array(guild_ids.iter().map(|id| {
+        serde_json::from_str(&format!(r#"{{"id": "{}", "unavailable": true}}"#, id)).unwrap()
+    }).collect());
+
+    ReadyEvent::deserialize(v).unwrap()
+}
+
+fn saved_cache(name: &str) -> Cache {
+    let mut cache = Cache::default();
+    cache.update_with_ready(&p!(ReadyEvent, "ready_1"));
+    cache.update_with_guild_create(&p!(GuildCreateEvent, "guild_create_1"));
+
+    let path = env::temp_dir().join(name);
+    cache.save_to(&path).unwrap();
+
+    let mut loaded = Cache::default();
+    loaded.load_from(&path).unwrap();
+    let _ = fs::remove_file(&path);
+
+    loaded
+}
+
+#[test]
+fn round_trip() {
+    let cache = saved_cache("serenity_test_cache_snapshot_round_trip.json");
+
+    assert_eq!(cache.user.name, "Ein");
+    assert_eq!(cache.private_channels.len(), 1);
+    assert!(cache.unavailable_guilds.contains(&GuildId(244567637332328449)));
+
+    let guild = cache.guild(GUILD_ID).unwrap();
+    let guild = guild.read().unwrap();
+    assert_eq!(guild.name, "Discord API");
+    assert_eq!(guild.members.len(), 9593);
+    assert_eq!(guild.presences.len(), 8834);
+    assert_eq!(guild.channels.len(), 53);
+    assert_eq!(cache.channels.len(), 53);
+
+    let channel = cache.guild_channel(290944320192774154).unwrap();
+    assert_eq!(channel.read().unwrap().guild_id, GuildId(GUILD_ID));
+
+    // Members share their users with the cache's map of users.
+    let member = &guild.members[&UserId(145265491093487617)];
+    assert!(Arc::ptr_eq(&member.user, &cache.users[&UserId(145265491093487617)]));
+}
+
+#[test]
+fn kept_until_ready() {
+    let mut cache = saved_cache("serenity_test_cache_snapshot_ready.json");
+
+    // The guild is kept while it is unavailable.
+    cache.update_with_ready(&ready(&[GUILD_ID]));
+    assert!(cache.guild(GUILD_ID).is_some());
+    assert!(cache.unavailable_guilds.contains(&GuildId(GUILD_ID)));
+    assert_eq!(cache.all_guilds().iter().filter(|id| **id == GUILD_ID).count(), 1);
+
+    // The guild is removed when the current user is no longer in it.
+    cache.update_with_ready(&ready(&[]));
+    assert!(cache.guild(GUILD_ID).is_none());
+    assert!(cache.channels.is_empty());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature="cache")]
+
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::client::gateway::Recording;
+use serenity::ext::cache::Cache;
+use serenity::model::event::{Event, GatewayEvent, GuildCreateEvent};
+use serenity::model::*;
+
+fn session() -> Vec<Event> {
+    Recording::open("./tests/resources/session_2.jsonl")
+        .unwrap()
+        .map(|recorded| recorded.unwrap().payload)
+        .filter(|payload| payload["op"] == 0)
+        .map(|payload| match GatewayEvent::decode(payload).unwrap() {
+            GatewayEvent::Dispatch(_, event) => event,
+            other => panic!("expected a dispatch, got {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn collection_counts() {
+    let mut cache = Cache::default();
+    let empty = cache.stats();
+    assert_eq!(empty.guilds, 0);
+    assert_eq!(empty.members, 0);
+    assert!(empty.last_update.is_none());
+
+    cache.update_with_guild_create(&GuildCreateEvent {
+        guild: p!(Guild, "guild_create_1"),
+    });
+
+    let stats = cache.stats();
+    assert_eq!(stats.guilds, 1);
+    assert_eq!(stats.unavailable_guilds, 0);
+    assert_eq!(stats.channels, 53);
+    assert_eq!(stats.members, cache.users.len());
+    assert_eq!(stats.users, cache.users.len());
+    assert_eq!(stats.presences, 5445 + 2553 + 836);
+    assert_eq!(stats.private_channels, 0);
+    assert!(stats.approximate_size > empty.approximate_size + stats.members * 100);
+
+    // Calling an `update_with_*` method directly does not count the event.
+    assert!(stats.events.is_empty());
+}
+
+#[test]
+fn event_counts() {
+    let mut cache = Cache::default();
+
+    for event in session() {
+        if !cache.try_update(&event) {
+            cache.update(&event);
+        }
+    }
+
+    let stats = cache.stats();
+    assert_eq!(stats.events.len(), 5);
+    assert_eq!(stats.events["GUILD_CREATE"], 1);
+    assert_eq!(stats.events["GUILD_MEMBER_UPDATE"], 1);
+    assert_eq!(stats.events["CHANNEL_UPDATE"], 1);
+    assert_eq!(stats.events["VOICE_STATE_UPDATE"], 4);
+    assert!(stats.last_update.is_some());
+
+    // Events which are not stored are not counted.
+    cache.update(&Event::Resumed(serde_json::from_str(r#"{"_trace": []}"#).unwrap()));
+    assert_eq!(cache.stats().events.values().sum::<u64>(), 8);
+
+    // Clones of the cache have their own counts.
+    let clone = cache.clone();
+    cache.update(&session()[1]);
+    assert_eq!(clone.stats().events["GUILD_MEMBER_UPDATE"], 1);
+    assert_eq!(cache.stats().events["GUILD_MEMBER_UPDATE"], 2);
+}
@@ -0,0 +1,133 @@
+extern crate serde_json;
+extern crate serenity;
+extern crate typemap;
+
+use serde_json::Value;
+use serenity::client::gateway::Shard;
+use serenity::client::{self, Context};
+use serenity::model::*;
+use std::fs::File;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use typemap::ShareMap;
+
+#[cfg(feature="fake-gateway")]
+use serenity::client::gateway::FakeGateway;
+#[cfg(feature="fake-gateway")]
+use serenity::Client;
+
+fn resource(filename: &str) -> Value {
+    let f = File::open(format!("./tests/resources/{}", filename)).unwrap();
+
+    serde_json::from_reader(f).unwrap()
+}
+
+fn message(content: &str) -> Message {
+    let mut value = resource("message_create_1.json");
+    value["content"] = Value::String(content.to_owned());
+
+    serde_json::from_value(value).unwrap()
+}
+
+fn context() -> Context {
+    Context::new(None,
+                 Arc::new(Mutex::new(Shard::offline(None))),
+                 Arc::new(Mutex::new(ShareMap::custom())))
+}
+
+// Repeatedly feeds an item to the collectors until the waiting thread returns,
+// as the thread may not have registered its collector yet.
+fn feed<T, F: Fn()>(rx: &Receiver<T>, f: F) -> T {
+    for _ in 0..500 {
+        f();
+
+        if let Ok(result) = rx.recv_timeout(Duration::from_millis(10)) {
+            return result;
+        }
+    }
+
+    panic!("collector did not return");
+}
+
+#[test]
+fn await_message() {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let message = context().await_message(|m| m.content == "yes", Duration::from_secs(10));
+        let _ = tx.send(message);
+    });
+
+    let message = feed(&rx, || {
+        client::message_received(&message("no"));
+        client::message_received(&message("yes"));
+    });
+
+    assert_eq!(message.unwrap().content, "yes");
+}
+
+#[test]
+fn await_message_timeout() {
+    let message = context().await_message(|m| m.content == "never sent", Duration::from_millis(50));
+
+    assert!(message.is_none());
+}
+
+#[test]
+fn await_reactions() {
+    let reacted: Reaction = serde_json::from_value(resource("message_reaction_add_1.json")).unwrap();
+    let other: Reaction = serde_json::from_value(resource("message_reaction_add_2.json")).unwrap();
+    let mut message = message("poll");
+    message.id = reacted.message_id;
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let reactions = message.await_reactions(|_| true, 2, Duration::from_secs(10));
+        let _ = tx.send(reactions);
+    });
+
+    let reactions = feed(&rx, || {
+        client::reaction_added(&other);
+        client::reaction_added(&reacted);
+    });
+
+    assert_eq!(reactions.len(), 2);
+    assert!(reactions.iter().all(|r| r.message_id == MessageId(302928389764022282)));
+}
+
+#[cfg(feature="fake-gateway")]
+#[test]
+fn await_message_from_gateway() {
+    let gateway = FakeGateway::start().unwrap();
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&gateway.url());
+    client.on_message(move |context, message| {
+        if message.content == "start" {
+            let reply = context.await_message(|m| m.content == "yes", Duration::from_secs(10));
+            let _ = tx.lock().unwrap().send(reply);
+        }
+    });
+
+    thread::spawn(move || client.start());
+
+    assert!(gateway.wait_for(Duration::from_secs(10), |p| p["op"] == 2).is_some());
+
+    let mut start = resource("message_create_1.json");
+    start["content"] = Value::String("start".to_owned());
+    let mut yes = resource("message_create_1.json");
+    yes["content"] = Value::String("yes".to_owned());
+
+    assert!(gateway.dispatch("MESSAGE_CREATE", start));
+
+    let reply = feed(&rx, || {
+        gateway.dispatch("MESSAGE_CREATE", yes.clone());
+    });
+
+    assert_eq!(reply.unwrap().content, "yes");
+}
@@ -0,0 +1,96 @@
+extern crate serenity;
+
+use serenity::model::*;
+
+#[cfg(feature="cache")]
+use serenity::Client;
+#[cfg(feature="cache")]
+use std::sync::mpsc;
+#[cfg(feature="cache")]
+use std::sync::Mutex;
+#[cfg(feature="cache")]
+use std::time::Duration;
+
+#[test]
+fn change() {
+    let change = Change::new(vec![RoleId(1), RoleId(2)], vec![RoleId(2), RoleId(3)]);
+
+    assert!(change.is_changed());
+    assert_eq!(change.added(|roles| roles), vec![RoleId(3)]);
+    assert_eq!(change.removed(|roles| roles), vec![RoleId(1)]);
+    assert_eq!(change.field(|roles| &roles[1]), Some(Change::new(RoleId(2), RoleId(3))));
+    assert_eq!(change.field(|roles| &roles[0]), Some(Change::new(RoleId(1), RoleId(2))));
+    assert_eq!(Change::new(1, 1).field(|n| n), None);
+    assert!(!Change::new("a", "a").is_changed());
+}
+
+#[cfg(feature="cache")]
+#[test]
+fn derived_events() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    macro_rules! record {
+        ($client:ident, $method:ident, |$($arg:pat),*| $value:expr) => {{
+            let tx = Mutex::new(tx.lock().unwrap().clone());
+
+            $client.$method(move |_context, $($arg),*| {
+                let _ = tx.lock().unwrap().send($value);
+            });
+        }}
+    }
+
+    // A single worker runs the handlers in the order they were dispatched.
+    let mut client = Client::login("");
+    client.with_pool(|p| p.size(1));
+    record!(client, on_guild_member_role_add, |_, member, role_id| {
+        format!("role add {} {}", member.user.read().unwrap().name, role_id.0)
+    });
+    record!(client, on_guild_member_role_remove, |_, member, role_id| {
+        format!("role remove {} {}", member.user.read().unwrap().name, role_id.0)
+    });
+    record!(client, on_guild_member_nickname_update, |_, _, nick| {
+        format!("nick {:?} {:?}", nick.old, nick.new)
+    });
+    record!(client, on_guild_role_rename, |_, role, name| {
+        format!("role rename {} {} {}", role.id.0, name.old, name.new)
+    });
+    record!(client, on_channel_rename, |channel_id, name| {
+        format!("channel rename {} {} {}", channel_id.0, name.old, name.new)
+    });
+    record!(client, on_voice_channel_join, |_, user_id, channel_id| {
+        format!("join {} {}", user_id.0, channel_id.0)
+    });
+    record!(client, on_voice_channel_leave, |_, user_id, channel_id| {
+        format!("leave {} {}", user_id.0, channel_id.0)
+    });
+    record!(client, on_voice_channel_move, |_, user_id, channel| {
+        format!("move {} {} {}", user_id.0, channel.old.0, channel.new.0)
+    });
+    record!(client, on_voice_server_mute_update, |_, user_id, muted| {
+        format!("mute {} {}", user_id.0, muted)
+    });
+    record!(client, on_voice_server_deaf_update, |_, user_id, deafened| {
+        format!("deaf {} {}", user_id.0, deafened)
+    });
+    drop(tx);
+
+    client.replay("./tests/resources/session_2.jsonl", None).unwrap();
+
+    let received = (0..9)
+        .map(|_| rx.recv_timeout(Duration::from_secs(5)).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(received, vec![
+        "nick None Some(\"al\")".to_owned(),
+        "role add alice 300000000000000011".to_owned(),
+        "role remove alice 300000000000000010".to_owned(),
+        "role rename 300000000000000010 Members Regulars".to_owned(),
+        "channel rename 300000000000000020 general lobby".to_owned(),
+        "move 300000000000000002 300000000000000021 300000000000000022".to_owned(),
+        "mute 300000000000000002 true".to_owned(),
+        "leave 300000000000000002 300000000000000022".to_owned(),
+        "join 300000000000000001 300000000000000021".to_owned(),
+    ]);
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}
@@ -89,6 +89,11 @@ fn guild_member_update() {
     p!(GuildMemberUpdateEvent, "guild_member_update_1");
 }
 
+#[test]
+fn guild_members_chunk() {
+    p!(GuildMembersChunkEvent, "guild_members_chunk_1");
+}
+
 #[test]
 fn guild_role_create() {
     p!(GuildRoleCreateEvent, "guild_role_create_1");
@@ -156,3 +161,21 @@ fn voice_state_update() {
 fn webhooks_update() {
     p!(WebhookUpdateEvent, "webhooks_update_1");
 }
+
+// Ids are sent as strings, but integers are accepted too. An unavailable guild,
+// as in a READY, is told apart from the other guild statuses.
+#[test]
+fn ids() {
+    let id = |json: &str| GuildId::deserialize(serde_json::from_str::<Value>(json).unwrap());
+
+    assert_eq!(id(r#""81384788765712384""#).unwrap(), GuildId(81384788765712384));
+    assert_eq!(id("81384788765712384").unwrap(), GuildId(81384788765712384));
+    assert!(id(r#""not an id""#).is_err());
+
+    let v = serde_json::from_str::<Value>(r#"{"id": "2", "unavailable": true}"#).unwrap();
+
+    match GuildStatus::deserialize(v).unwrap() {
+        GuildStatus::Offline(guild) => assert_eq!(guild.id, GuildId(2)),
+        other => panic!("expected an unavailable guild, got {:?}", other),
+    }
+}
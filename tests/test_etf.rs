@@ -0,0 +1,103 @@
+extern crate serde_json;
+extern crate serenity;
+
+use serde_json::Value;
+use serenity::client::gateway::GatewayEncoding;
+use serenity::model::event::*;
+use serenity::model::*;
+use std::fs::File;
+use std::io::Read;
+
+fn resource(filename: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    File::open(format!("./tests/resources/{}", filename))
+        .unwrap()
+        .read_to_end(&mut bytes)
+        .unwrap();
+
+    bytes
+}
+
+fn decode(filename: &str) -> Value {
+    GatewayEncoding::Etf.decode(&resource(filename)).unwrap()
+}
+
+#[test]
+fn hello() {
+    let value = decode("hello_1.etf");
+
+    match GatewayEvent::decode(value).unwrap() {
+        GatewayEvent::Hello(interval) => assert_eq!(interval, 41250),
+        other => panic!("Unexpected event: {:?}", other),
+    }
+}
+
+#[test]
+fn message_create() {
+    let value = decode("message_create_1.etf");
+
+    match GatewayEvent::decode(value).unwrap() {
+        GatewayEvent::Dispatch(5, Event::MessageCreate(event)) => {
+            assert_eq!(event.message.id, MessageId(302917639565475840));
+            assert_eq!(event.message.channel_id, ChannelId(244567637332328449));
+            assert_eq!(event.message.author.id, UserId(114941315417899012));
+            assert_eq!(event.message.author.discriminator, 5479);
+            assert_eq!(event.message.content, "a");
+            assert!(event.message.edited_timestamp.is_none());
+        },
+        other => panic!("Unexpected event: {:?}", other),
+    }
+}
+
+#[test]
+fn compressed_reaction_add() {
+    let value = decode("message_reaction_add_1.etf");
+
+    match GatewayEvent::decode(value).unwrap() {
+        GatewayEvent::Dispatch(7, Event::ReactionAdd(event)) => {
+            assert_eq!(event.reaction.message_id, MessageId(302928389764022282));
+            assert_eq!(event.reaction.user_id, UserId(114941315417899012));
+        },
+        other => panic!("Unexpected event: {:?}", other),
+    }
+}
+
+#[test]
+fn round_trip_captured() {
+    for filename in &["hello_1.etf", "message_create_1.etf", "typing_start_1.etf"] {
+        let value = decode(filename);
+        let encoded = GatewayEncoding::Etf.encode(&value).unwrap();
+
+        assert_eq!(GatewayEncoding::Etf.decode(&encoded).unwrap(), value);
+    }
+}
+
+#[test]
+fn round_trip_json() {
+    for filename in &["channel_create_1.json", "guild_member_update_1.json", "ready_1.json"] {
+        let value = GatewayEncoding::Json.decode(&resource(filename)).unwrap();
+        let encoded = GatewayEncoding::Etf.encode(&value).unwrap();
+
+        assert_eq!(GatewayEncoding::Etf.decode(&encoded).unwrap(), value);
+    }
+}
+
+#[test]
+fn numbers() {
+    let value = json_value(r#"[0, 255, 256, -1, 2147483648, -2147483649, 18446744073709551615, 1.5]"#);
+    let encoded = GatewayEncoding::Etf.encode(&value).unwrap();
+
+    assert_eq!(GatewayEncoding::Etf.decode(&encoded).unwrap(), value);
+}
+
+#[test]
+fn invalid() {
+    assert!(GatewayEncoding::Etf.decode(&[]).is_err());
+    assert!(GatewayEncoding::Etf.decode(&[130, 97, 1]).is_err());
+    assert!(GatewayEncoding::Etf.decode(&[131, 109, 0, 0, 0, 5, 97]).is_err());
+    assert!(GatewayEncoding::Etf.decode(&[131, 97, 1, 97]).is_err());
+}
+
+fn json_value(s: &str) -> Value {
+    serde_json::from_str(s).unwrap()
+}
@@ -0,0 +1,87 @@
+extern crate serenity;
+
+use serenity::client::Backpressure;
+use serenity::model::event::Event;
+use serenity::model::*;
+use serenity::Client;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn events() {
+    let mut client = Client::login("");
+    let events = client.events();
+    let filtered = client.events();
+
+    client.add_middleware(|_shard, event| {
+        match event {
+            Event::GuildCreate(_) => None,
+            event => Some(event),
+        }
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+    drop(client);
+
+    let events = events.collect::<Vec<_>>();
+    assert_eq!(events.len(), 1);
+
+    match events[0] {
+        (Some([0, 1]), Event::MessageCreate(ref e)) => {
+            assert_eq!(e.message.id, MessageId(302917639565475840));
+        },
+        ref other => panic!("unexpected event: {:?}", other),
+    }
+
+    assert!(filtered.try_next().is_some());
+    assert!(filtered.try_next().is_none());
+    assert!(filtered.next_timeout(Duration::from_millis(10)).is_none());
+}
+
+#[test]
+fn dropped_events() {
+    let mut client = Client::login("");
+    let events = client.events();
+    drop(events);
+
+    // Sending to the dropped iterator must not block or fail the replay.
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+}
+
+#[test]
+fn full_events_buffer_drops_events() {
+    let mut client = Client::login("");
+    let events = client.events();
+
+    // Each replay publishes two events, so this overflows the buffer of 1000;
+    // publishing must drop the rest rather than block the replay.
+    for _ in 0..600 {
+        client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+    }
+
+    drop(client);
+
+    assert_eq!(events.dropped(), 200);
+    assert_eq!(events.count(), 1000);
+}
+
+#[test]
+fn blocking_events_buffer_keeps_events() {
+    let mut client = Client::login("");
+    let mut events = client.events_with(|e| e.backpressure(Backpressure::Block).buffer(10));
+
+    let receiver = thread::spawn(move || {
+        let received = events.by_ref().count();
+
+        (received, events.dropped())
+    });
+
+    // Publishing waits for the events to be received rather than dropping any.
+    for _ in 0..100 {
+        client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+    }
+
+    drop(client);
+
+    assert_eq!(receiver.join().unwrap(), (200, 0));
+}
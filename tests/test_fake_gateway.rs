@@ -0,0 +1,185 @@
+#![cfg(feature="fake-gateway")]
+
+#[macro_use] extern crate serde_json;
+extern crate serenity;
+
+use serde_json::Value;
+use serenity::client::gateway::{FakeGateway, GatewayEncoding, Identify, Shard};
+use serenity::model::*;
+use serenity::Client;
+use std::fs::File;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn resource(filename: &str) -> Value {
+    let f = File::open(format!("./tests/resources/{}", filename)).unwrap();
+
+    serde_json::from_reader(f).unwrap()
+}
+
+fn timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn wait_for_count(gateway: &FakeGateway, op: u64, count: usize) -> bool {
+    let deadline = Instant::now() + timeout();
+
+    while Instant::now() < deadline {
+        if gateway.received().iter().filter(|p| p["op"] == op).count() >= count {
+            return true;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    false
+}
+
+fn start_client(gateway: &FakeGateway) -> Receiver<Message> {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&gateway.url());
+    client.on_message(move |_context, message| {
+        let _ = tx.lock().unwrap().send(message);
+    });
+
+    thread::spawn(move || client.start());
+
+    rx
+}
+
+#[test]
+fn shard_connects() {
+    let gateway = FakeGateway::start().unwrap();
+    gateway.set_heartbeat_interval(50);
+
+    let identify = Identify::default().large_threshold(100);
+    let (shard, ready, _receiver) = Shard::new_with(&gateway.url(),
+                                                    "Bot fake token",
+                                                    Some([1, 2]),
+                                                    |s| s.identify(identify)).unwrap();
+
+    assert_eq!(ready.ready.session_id, "fake-session-1");
+    assert_eq!(ready.ready.shard, Some([1, 2]));
+    assert_eq!(shard.shard_info(), Some([1, 2]));
+
+    let identify = gateway.wait_for(timeout(), |p| p["op"] == 2).unwrap();
+    assert_eq!(identify["d"]["token"], "Bot fake token");
+    assert_eq!(identify["d"]["large_threshold"], 100);
+
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 1).is_some());
+    assert_eq!(gateway.connections(), 1);
+}
+
+#[test]
+fn presence_update_matches_identify() {
+    let gateway = FakeGateway::start().unwrap();
+    gateway.set_heartbeat_interval(50);
+
+    let (mut shard, _, _receiver) = Shard::new(&gateway.url(), "Bot fake token", None).unwrap();
+
+    let game = Game::streaming("with fire", "https://twitch.tv/zeyla");
+    shard.set_game(Some(game.clone()));
+
+    let update = gateway.wait_for(timeout(), |p| p["op"] == 3).unwrap();
+    let identify = Identify::default().game(game).payload("Bot fake token", None);
+    assert_eq!(update["d"]["game"], identify["d"]["presence"]["game"]);
+    assert_eq!(update["d"]["game"]["url"], "https://twitch.tv/zeyla");
+}
+
+#[test]
+fn shard_connects_etf() {
+    let gateway = FakeGateway::start().unwrap();
+
+    let (shard, ready, _receiver) = Shard::new_with(&gateway.url(),
+                                                    "Bot fake token",
+                                                    None,
+                                                    |s| s.encoding(GatewayEncoding::Etf)).unwrap();
+
+    assert_eq!(ready.ready.session_id, "fake-session-1");
+    assert_eq!(shard.encoding(), GatewayEncoding::Etf);
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 2).is_some());
+}
+
+#[test]
+fn client_dispatch() {
+    let gateway = FakeGateway::start().unwrap();
+    let messages = start_client(&gateway);
+
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 2).is_some());
+    assert!(gateway.dispatch("MESSAGE_CREATE", resource("message_create_1.json")));
+
+    let message = messages.recv_timeout(timeout()).unwrap();
+    assert_eq!(message.id, MessageId(302917639565475840));
+}
+
+#[test]
+fn client_resumes_after_dropped_connection() {
+    let gateway = FakeGateway::start().unwrap();
+    let messages = start_client(&gateway);
+
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 2).is_some());
+    assert!(gateway.drop_connection());
+
+    let resume = gateway.wait_for(timeout(), |p| p["op"] == 6).unwrap();
+    assert_eq!(resume["d"]["session_id"], "fake-session-1");
+    assert_eq!(gateway.connections(), 2);
+
+    assert!(gateway.dispatch("MESSAGE_CREATE", resource("message_create_1.json")));
+    assert!(messages.recv_timeout(timeout()).is_ok());
+}
+
+#[test]
+fn raw_handlers_receive_resumed() {
+    let gateway = FakeGateway::start().unwrap();
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&gateway.url());
+    client.on_raw_event(move |_context, name, _seq, _value| {
+        let _ = tx.lock().unwrap().send(name);
+    });
+    thread::spawn(move || client.start());
+
+    assert_eq!(rx.recv_timeout(timeout()).unwrap(), "READY");
+    assert!(gateway.drop_connection());
+
+    assert_eq!(rx.recv_timeout(timeout()).unwrap(), "RESUMED");
+}
+
+#[test]
+fn client_reidentifies_after_invalid_session_close() {
+    let gateway = FakeGateway::start().unwrap();
+    let messages = start_client(&gateway);
+
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 2).is_some());
+    assert!(gateway.close(4009, "Session timed out"));
+
+    assert!(wait_for_count(&gateway, 2, 2));
+    assert!(gateway.received().iter().all(|p| p["op"] != 6));
+
+    assert!(gateway.dispatch("MESSAGE_CREATE", resource("message_create_1.json")));
+    assert!(messages.recv_timeout(timeout()).is_ok());
+}
+
+#[test]
+fn client_reconnects_to_custom_gateway_url() {
+    let gateway = FakeGateway::start().unwrap();
+    let messages = start_client(&gateway);
+
+    assert!(gateway.wait_for(timeout(), |p| p["op"] == 2).is_some());
+
+    // The URL is not retrieved from the REST API, which would fail with the
+    // fake token.
+    assert!(gateway.send(json!({"op": 7, "d": null})));
+    assert!(wait_for_count(&gateway, 2, 2));
+    assert_eq!(gateway.connections(), 2);
+
+    assert!(gateway.dispatch("MESSAGE_CREATE", resource("message_create_1.json")));
+    assert!(messages.recv_timeout(timeout()).is_ok());
+}
@@ -0,0 +1,135 @@
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::model::*;
+
+#[cfg(feature="cache")]
+use serenity::ext::cache::Cache;
+#[cfg(feature="cache")]
+use serenity::model::event::GuildCreateEvent;
+
+fn names(members: &[&Member]) -> Vec<String> {
+    members.iter().map(|member| member.user.read().unwrap().name.clone()).collect()
+}
+
+#[test]
+fn members_with_role() {
+    let guild = p!(Guild, "guild_create_1");
+
+    // The "discord.py news" role.
+    let members = guild.members_with_role(187053776920641536);
+    assert_eq!(members.len(), 366);
+    assert!(members.iter().all(|member| member.roles.contains(&RoleId(187053776920641536))));
+
+    // Every member has the `@everyone` role.
+    assert_eq!(guild.members_with_role(guild.id.0).len(), guild.members.len());
+}
+
+#[test]
+fn members_with_status() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert_eq!(guild.members_with_status(OnlineStatus::Online).len(), 5445);
+    assert_eq!(guild.members_with_status(OnlineStatus::Idle).len(), 2553);
+    assert_eq!(guild.members_with_status(OnlineStatus::DoNotDisturb).len(), 836);
+    assert_eq!(guild.members_with_status(OnlineStatus::Offline).len(), 759);
+}
+
+#[test]
+fn members_playing() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert_eq!(guild.members_playing("overwatch").len(), 99);
+    assert!(guild.members_playing("not a game").is_empty());
+}
+
+#[test]
+fn members_in_voice_channel() {
+    let mut guild = p!(Guild, "guild_create_1");
+    let member = guild.members.values().next().unwrap().clone();
+    let user_id = member.user.read().unwrap().id;
+
+    guild.voice_states.insert(user_id, VoiceState {
+        channel_id: Some(ChannelId(1)),
+        deaf: false,
+        mute: false,
+        self_deaf: false,
+        self_mute: false,
+        session_id: String::new(),
+        suppress: false,
+        token: None,
+        user_id: user_id,
+    });
+
+    assert_eq!(names(&guild.members_in_voice_channel(1)), names(&[&member]));
+    assert!(guild.members_in_voice_channel(2).is_empty());
+}
+
+#[test]
+fn roles_by_position() {
+    let guild = p!(Guild, "guild_create_1");
+    let roles = guild.roles_by_position()
+        .iter()
+        .take(3)
+        .map(|role| role.name.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(roles, vec!["VoltyVolt", "Admin", "PastelPride"]);
+}
+
+#[test]
+fn channels_by_position() {
+    let guild = p!(Guild, "guild_create_1");
+    let channels = guild.channels_by_position()
+        .iter()
+        .map(|channel| {
+            let channel = channel.read().unwrap();
+
+            (channel.kind, channel.name.clone())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(channels.len(), 53);
+    assert_eq!(&channels[..3], &[
+        (ChannelType::Text, "info".to_owned()),
+        (ChannelType::Text, "rules".to_owned()),
+        (ChannelType::Text, "faq".to_owned()),
+    ]);
+    assert!(channels[45..].iter().all(|&(kind, _)| kind == ChannelType::Voice));
+}
+
+#[test]
+fn search_members() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert_eq!(names(&guild.search_members("ZEYLA#5479")), vec!["zeyla"]);
+    // Prefix matches rank above other matches.
+    assert_eq!(names(&guild.search_members("zey")[..2]), vec!["zeyla", "Mitzey"]);
+    assert_eq!(names(&guild.search_members("zeyzey")), vec!["zeyla"]);
+    assert!(guild.search_members("zyl").len() > 1);
+    assert!(guild.search_members("zeyla#1234").is_empty());
+}
+
+#[cfg(feature="cache")]
+#[test]
+fn cache_queries() {
+    let mut cache = Cache::default();
+    cache.update_with_guild_create(&GuildCreateEvent {
+        guild: p!(Guild, "guild_create_1"),
+    });
+
+    let guild_id = GuildId(81384788765712384);
+    assert_eq!(cache.members_with_role(guild_id, 187053776920641536).len(), 366);
+    assert_eq!(cache.members_with_status(guild_id, OnlineStatus::Idle).len(), 2553);
+    assert_eq!(cache.members_playing(guild_id, "overwatch").len(), 99);
+    assert_eq!(cache.roles_by_position(guild_id)[0].name, "VoltyVolt");
+    assert_eq!(cache.channels_by_position(guild_id).len(), 53);
+    assert_eq!(cache.search_members(guild_id, "zeyla")[0].display_name().as_str(), "zeyzey♫");
+    assert!(cache.members_in_voice_channel(290944320192774154).is_empty());
+
+    assert!(cache.search_members(1, "zey").is_empty());
+}
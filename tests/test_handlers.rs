@@ -0,0 +1,183 @@
+extern crate serenity;
+
+use serenity::client::{Context, EventHandler, HandlerId};
+use serenity::model::event::Event;
+use serenity::model::*;
+use serenity::Client;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn handlers_run_in_registration_order() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+
+    for i in 0..3 {
+        let calls = calls.clone();
+
+        client.on_message(move |_context, _message| {
+            calls.lock().unwrap().push(i);
+        });
+    }
+
+    client.on_message(move |_context, _message| {
+        let _ = tx.lock().unwrap().send(());
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![0, 1, 2]);
+}
+
+#[test]
+fn remove_handler() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+
+    let ids = (0..3).map(|i| {
+        let calls = calls.clone();
+
+        client.on_message(move |_context, _message| {
+            calls.lock().unwrap().push(i);
+        })
+    }).collect::<Vec<HandlerId>>();
+
+    client.on_message(move |_context, _message| {
+        let _ = tx.lock().unwrap().send(());
+    });
+
+    assert!(client.remove_handler(ids[1]));
+    assert!(!client.remove_handler(ids[1]));
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![0, 2]);
+}
+
+struct Recorder {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl EventHandler for Recorder {
+    fn on_guild_create(&self, _: Context, guild: Guild) {
+        self.calls.lock().unwrap().push(format!("guild {}", guild.id.0));
+    }
+
+    fn on_message(&self, _: Context, message: Message) {
+        self.calls.lock().unwrap().push(format!("message {}", message.id.0));
+    }
+}
+
+#[test]
+fn event_handler() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    // A single worker runs the handlers in the order they were dispatched.
+    let mut client = Client::login("");
+    client.with_pool(|p| p.size(1));
+    client.add_handler(Recorder {
+        calls: calls.clone(),
+    });
+    let removed = client.add_handler(Recorder {
+        calls: calls.clone(),
+    });
+    client.on_message(move |_context, _message| {
+        let _ = tx.lock().unwrap().send(());
+    });
+
+    assert!(client.remove_handler(removed));
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![
+        "guild 81384788765712384".to_owned(),
+        "message 302917639565475840".to_owned(),
+    ]);
+}
+
+#[test]
+fn event_handlers_interleave_with_closures() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    // A single worker runs the handlers in the order they were dispatched.
+    let mut client = Client::login("");
+    client.with_pool(|p| p.size(1));
+
+    let before = calls.clone();
+    client.on_message(move |_context, _message| {
+        before.lock().unwrap().push("before".to_owned());
+    });
+    client.add_handler(Recorder {
+        calls: calls.clone(),
+    });
+    let after = calls.clone();
+    client.on_message(move |_context, _message| {
+        after.lock().unwrap().push("after".to_owned());
+        let _ = tx.lock().unwrap().send(());
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![
+        "guild 81384788765712384".to_owned(),
+        "before".to_owned(),
+        "message 302917639565475840".to_owned(),
+        "after".to_owned(),
+    ]);
+}
+
+#[test]
+fn middleware() {
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+    client.add_middleware(|_shard, event| {
+        match event {
+            Event::GuildCreate(_) => None,
+            Event::MessageCreate(mut e) => {
+                e.message.content = "first".to_owned();
+
+                Some(Event::MessageCreate(e))
+            },
+            event => Some(event),
+        }
+    });
+    client.add_middleware(|_shard, event| {
+        match event {
+            Event::MessageCreate(mut e) => {
+                e.message.content.push_str(", second");
+
+                Some(Event::MessageCreate(e))
+            },
+            event => Some(event),
+        }
+    });
+    client.add_handler(Recorder {
+        calls: calls.clone(),
+    });
+    client.on_message(move |_context, message| {
+        let _ = tx.lock().unwrap().send(message.content);
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), "first, second");
+    assert_eq!(*calls.lock().unwrap(), vec!["message 302917639565475840".to_owned()]);
+}
@@ -0,0 +1,50 @@
+#[macro_use] extern crate serde_json;
+extern crate serenity;
+
+use serenity::client::gateway::Identify;
+use serenity::model::{Game, OnlineStatus};
+
+#[test]
+fn payload() {
+    let identify = Identify::default()
+        .afk(true)
+        .game(Game::streaming("with fire", "https://twitch.tv/zeyla"))
+        .status(OnlineStatus::DoNotDisturb)
+        .large_threshold(100);
+
+    let payload = identify.payload("Bot token", Some([1, 4]));
+    assert_eq!(payload["op"], 2);
+
+    let data = &payload["d"];
+    assert_eq!(data["token"], "Bot token");
+    assert_eq!(data["shard"], json!([1, 4]));
+    assert_eq!(data["large_threshold"], 100);
+    assert_eq!(data["presence"]["afk"], json!(true));
+    assert_eq!(data["presence"]["status"], "dnd");
+    assert_eq!(data["presence"]["game"]["name"], "with fire");
+    assert_eq!(data["presence"]["game"]["type"], 1);
+    assert_eq!(data["presence"]["game"]["url"], "https://twitch.tv/zeyla");
+}
+
+#[test]
+fn payload_defaults() {
+    let payload = Identify::default().payload("Bot token", None);
+    let data = &payload["d"];
+
+    assert_eq!(data["shard"], json!([0, 1]));
+    assert_eq!(data["large_threshold"], 250);
+    assert_eq!(data["presence"]["status"], "online");
+    assert!(data["presence"]["game"].is_null());
+    assert_eq!(data["properties"]["$browser"], "serenity");
+}
+
+#[test]
+fn large_threshold_is_clamped() {
+    let large_threshold = |value| {
+        Identify::default().large_threshold(value).payload("", None)["d"]["large_threshold"].clone()
+    };
+
+    assert_eq!(large_threshold(10), 50);
+    assert_eq!(large_threshold(50), 50);
+    assert_eq!(large_threshold(251), 250);
+}
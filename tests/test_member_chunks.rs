@@ -0,0 +1,134 @@
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+use serenity::client::MemberChunks;
+use serenity::model::event::GuildMembersChunkEvent;
+use serenity::model::*;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature="cache")]
+use serde::de::Deserialize;
+#[cfg(feature="cache")]
+use serde_json::Value;
+#[cfg(feature="cache")]
+use serenity::client::CACHE;
+#[cfg(feature="cache")]
+use serenity::model::event::GuildCreateEvent;
+#[cfg(feature="cache")]
+use std::fs::File;
+
+fn member(id: u64) -> Member {
+    Member {
+        deaf: false,
+        guild_id: None,
+        joined_at: "2017-01-01T00:00:00+00:00".to_owned(),
+        mute: false,
+        nick: None,
+        roles: vec![],
+        user: Arc::new(RwLock::new(User {
+            id: UserId(id),
+            avatar: None,
+            bot: false,
+            discriminator: 1,
+            name: format!("user {}", id),
+        })),
+    }
+}
+
+fn chunk(chunks: &MemberChunks, guild_id: u64, count: Option<u64>, members: u64)
+    -> GuildMembersChunkEvent {
+    GuildMembersChunkEvent {
+        chunk_count: count,
+        chunk_index: None,
+        guild_id: GuildId(guild_id),
+        members: (1..members + 1).map(|id| (UserId(id), member(id))).collect::<HashMap<_, _>>(),
+        nonce: Some(chunks.nonce().to_owned()),
+        not_found: vec![],
+    }
+}
+
+#[test]
+fn completes_with_chunk_count() {
+    let chunks = MemberChunks::new(&[GuildId(1), GuildId(2)]);
+
+    MemberChunks::received(&chunk(&chunks, 1, Some(2), 1000));
+    MemberChunks::received(&chunk(&chunks, 1, Some(2), 500));
+    assert!(!chunks.is_complete());
+
+    let mut last = chunk(&chunks, 2, Some(1), 0);
+    last.not_found = vec![UserId(7)];
+    MemberChunks::received(&last);
+
+    assert!(chunks.is_complete());
+    assert!(chunks.wait_timeout(Duration::from_millis(0)));
+    assert_eq!(chunks.not_found(), vec![UserId(7)]);
+}
+
+#[test]
+fn completes_on_partial_chunk() {
+    let chunks = MemberChunks::new(&[GuildId(1)]);
+
+    MemberChunks::received(&chunk(&chunks, 1, None, 1000));
+    assert!(!chunks.is_complete());
+
+    MemberChunks::received(&chunk(&chunks, 1, None, 3));
+    assert!(chunks.is_complete());
+}
+
+#[test]
+fn wait_timeout_completes() {
+    let chunks = MemberChunks::new(&[GuildId(1)]);
+    let event = chunk(&chunks, 1, Some(1), 2);
+
+    let waiter = {
+        let chunks = chunks.clone();
+
+        thread::spawn(move || chunks.wait_timeout(Duration::from_secs(10)))
+    };
+
+    MemberChunks::received(&event);
+    assert!(waiter.join().unwrap());
+}
+
+#[test]
+fn timed_out_requests_are_abandoned() {
+    let chunks = MemberChunks::new(&[GuildId(1)]);
+    assert!(!chunks.wait_timeout(Duration::from_millis(10)));
+
+    MemberChunks::received(&chunk(&chunks, 1, Some(1), 2));
+    assert!(!chunks.is_complete());
+}
+
+#[test]
+fn dropping_a_clone_keeps_tracking() {
+    let chunks = MemberChunks::new(&[GuildId(1)]);
+    drop(chunks.clone());
+
+    MemberChunks::received(&chunk(&chunks, 1, Some(1), 2));
+    assert!(chunks.is_complete());
+}
+
+#[cfg(feature="cache")]
+#[test]
+fn completes_on_exact_multiple_of_chunk_size() {
+    let f = File::open("./tests/resources/guild_create_1.json").unwrap();
+    let mut guild = Guild::deserialize(serde_json::from_reader::<File, Value>(f).unwrap()).unwrap();
+    guild.member_count = 2000;
+    let guild_id = guild.id.0;
+
+    CACHE.write().unwrap().update_with_guild_create(&GuildCreateEvent {
+        guild: guild,
+    });
+
+    let chunks = MemberChunks::new(&[GuildId(guild_id)]);
+
+    MemberChunks::received(&chunk(&chunks, guild_id, None, 1000));
+    assert!(!chunks.is_complete());
+
+    MemberChunks::received(&chunk(&chunks, guild_id, None, 1000));
+    assert!(chunks.is_complete());
+}
@@ -0,0 +1,101 @@
+#![cfg(feature="cache")]
+
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+use serde::de::Deserialize;
+use serde_json::Value;
+use serenity::ext::cache::{Cache, MessageCacheConfig};
+use serenity::model::event::*;
+use serenity::model::*;
+use std::fs::File;
+use std::time::Duration;
+
+fn message_create(id: u64) -> MessageCreateEvent {
+    let f = File::open("./tests/resources/message_create_1.json").unwrap();
+    let mut v = serde_json::from_reader::<File, Value>(f).unwrap();
+    v["id"] = Value::String(id.to_string());
+
+    MessageCreateEvent::deserialize(v).unwrap()
+}
+
+fn message_update() -> MessageUpdateEvent {
+    let f = File::open("./tests/resources/message_update_1.json").unwrap();
+    let v = serde_json::from_reader::<File, Value>(f).unwrap();
+
+    MessageUpdateEvent::deserialize(v).unwrap()
+}
+
+fn cache(config: MessageCacheConfig) -> Cache {
+    let mut cache = Cache::default();
+    cache.message_config = config;
+
+    cache
+}
+
+const CHANNEL_ID: u64 = 244567637332328449;
+const MESSAGE_ID: u64 = 302917639565475840;
+
+#[test]
+fn disabled_by_default() {
+    let mut cache = Cache::default();
+    cache.update_with_message_create(&message_create(MESSAGE_ID));
+
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID).is_none());
+    assert!(cache.update_with_message_update(&message_update()).is_none());
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let mut cache = cache(MessageCacheConfig::default().capacity(2));
+    cache.update_with_message_create(&message_create(MESSAGE_ID));
+    cache.update_with_message_create(&message_create(MESSAGE_ID + 1));
+
+    // Updating the first message makes the second the least recently used.
+    let old = cache.update_with_message_update(&message_update()).unwrap();
+    assert_eq!(old.content, "a");
+    assert_eq!(cache.message(CHANNEL_ID, MESSAGE_ID).unwrap().content, "b");
+
+    cache.update_with_message_create(&message_create(MESSAGE_ID + 2));
+
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID).is_some());
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID + 1).is_none());
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID + 2).is_some());
+}
+
+#[test]
+fn deletes() {
+    let mut cache = cache(MessageCacheConfig::default().capacity(10));
+
+    for i in 0..4 {
+        cache.update_with_message_create(&message_create(MESSAGE_ID + i));
+    }
+
+    let deleted = cache.update_with_message_delete(&MessageDeleteEvent {
+        channel_id: ChannelId(CHANNEL_ID),
+        message_id: MessageId(MESSAGE_ID),
+    });
+    assert_eq!(deleted.map(|m| m.id), Some(MessageId(MESSAGE_ID)));
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID).is_none());
+
+    let deleted = cache.update_with_message_delete_bulk(&MessageDeleteBulkEvent {
+        channel_id: ChannelId(CHANNEL_ID),
+        ids: vec![MessageId(MESSAGE_ID), MessageId(MESSAGE_ID + 1), MessageId(MESSAGE_ID + 3)],
+    });
+    let ids = deleted.iter().map(|m| m.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![MessageId(MESSAGE_ID + 1), MessageId(MESSAGE_ID + 3)]);
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID + 2).is_some());
+}
+
+#[test]
+fn expires() {
+    // The message was sent in 2017, so is older than a day.
+    let mut cache = cache(MessageCacheConfig::default()
+        .capacity(10)
+        .max_age(Duration::from_secs(60 * 60 * 24)));
+    cache.update_with_message_create(&message_create(MESSAGE_ID));
+
+    assert!(cache.message(CHANNEL_ID, MESSAGE_ID).is_none());
+    assert!(cache.update_with_message_update(&message_update()).is_none());
+}
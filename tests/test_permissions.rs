@@ -0,0 +1,209 @@
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::Error;
+use serenity::client::ClientError;
+use serenity::model::permissions::{self, *};
+use serenity::model::*;
+
+#[cfg(feature="cache")]
+use serenity::client::CACHE;
+#[cfg(feature="cache")]
+use serenity::model::event::GuildCreateEvent;
+
+const ELIXIR: u64 = 290944320192774154;
+const MODS: u64 = 175643578071121920;
+const MUTED: u64 = 257017090932867072;
+const YUI: u64 = 285449303596597259;
+
+fn calculate(guild: &Guild, roles: &[u64], user_id: u64, channel_id: Option<u64>) -> Permissions {
+    let everyone = &guild.roles[&RoleId(guild.id.0)];
+    let roles = roles.iter().map(|id| &guild.roles[&RoleId(*id)]).collect::<Vec<_>>();
+
+    match channel_id {
+        Some(channel_id) => {
+            let channel = guild.channels[&ChannelId(channel_id)].read().unwrap();
+
+            permissions::calculate(everyone, &roles, UserId(user_id), Some(&channel))
+        },
+        None => permissions::calculate(everyone, &roles, UserId(user_id), None),
+    }
+}
+
+#[test]
+fn guild_permissions() {
+    let guild = p!(Guild, "guild_create_1");
+    let everyone = guild.roles[&RoleId(guild.id.0)].permissions;
+    let mods = guild.roles[&RoleId(MODS)].permissions;
+
+    assert_eq!(calculate(&guild, &[], 1, None), everyone);
+    assert_eq!(calculate(&guild, &[MODS], 1, None), everyone | mods);
+
+    // The "Admin" role has the administrator permission.
+    assert_eq!(calculate(&guild, &[158331219535331328], 1, None), Permissions::all());
+}
+
+#[test]
+fn channel_overwrites() {
+    let guild = p!(Guild, "guild_create_1");
+
+    let everyone = calculate(&guild, &[], 1, Some(ELIXIR));
+    assert!(everyone.read_messages() && everyone.send_messages());
+
+    // Voice permissions are removed in text channels.
+    assert!(!everyone.connect());
+
+    let muted = calculate(&guild, &[MUTED], 1, Some(ELIXIR));
+    assert!(!muted.read_messages() && !muted.send_messages());
+
+    // Without reading messages, only guild-wide permissions are kept.
+    assert!(!muted.add_reactions());
+    assert!(muted.change_nickname());
+
+    // Role overwrites are combined, with allows taking priority over denies.
+    let muted_mod = calculate(&guild, &[MUTED, MODS], 1, Some(ELIXIR));
+    assert!(muted_mod.read_messages() && muted_mod.send_messages());
+    assert_eq!(muted_mod, calculate(&guild, &[MODS, MUTED], 1, Some(ELIXIR)));
+
+    // The member overwrite is applied last.
+    let member = calculate(&guild, &[MUTED], 89918932789497856, Some(ELIXIR));
+    assert!(member.read_messages() && member.send_messages());
+    assert!(member.manage_roles());
+
+    // The `@everyone` overwrite denies connecting to the voice channel, while
+    // the member overwrite allows it.
+    assert!(!calculate(&guild, &[], 1, Some(YUI)).connect());
+    assert!(calculate(&guild, &[], 111761808640978944, Some(YUI)).connect());
+}
+
+#[test]
+fn guild_permissions_for() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert_eq!(guild.permissions_for(ELIXIR, guild.owner_id), Permissions::all());
+
+    // A muted member.
+    let perms = guild.permissions_for(ELIXIR, 274211819902730240);
+    assert!(!perms.contains(READ_MESSAGES));
+
+    // Users which are not members only receive the `@everyone` permissions.
+    assert_eq!(guild.permissions_for(ELIXIR, 1), guild.roles[&RoleId(guild.id.0)].permissions);
+}
+
+#[test]
+fn explain() {
+    let guild = p!(Guild, "guild_create_1");
+    let everyone = &guild.roles[&RoleId(guild.id.0)];
+    let muted = &guild.roles[&RoleId(MUTED)];
+    let channel = guild.channels[&ChannelId(ELIXIR)].read().unwrap();
+
+    let explanation = permissions::explain(everyone, &[muted], UserId(1), Some(&channel));
+    assert_eq!(explanation.permissions, calculate(&guild, &[MUTED], 1, Some(ELIXIR)));
+    assert_eq!(explanation.bits.len(), 27);
+
+    let read = explanation.get(READ_MESSAGES).unwrap();
+    assert!(!read.allowed);
+    assert_eq!(read.sources, vec![
+        (PermissionSource::Everyone, true),
+        (PermissionSource::RoleOverwrite(RoleId(guild.id.0)), true),
+        (PermissionSource::RoleOverwrite(RoleId(MUTED)), false),
+    ]);
+
+    let connect = explanation.get(CONNECT).unwrap();
+    assert_eq!(connect.sources.last(), Some(&(PermissionSource::TextChannel, false)));
+
+    // Reactions are denied as a result of not being able to read messages.
+    let reactions = explanation.get(ADD_REACTIONS).unwrap();
+    assert_eq!(reactions.sources.last(), Some(&(PermissionSource::NoReadMessages, false)));
+
+    let report = explanation.to_string();
+    assert!(report.contains("Read Messages: denied
+  + @everyone
+  + overwrite for role @everyone
+  - overwrite for role Muted
+"));
+    assert!(report.contains("Manage Roles: denied\nManage Webhooks: denied\n"));
+}
+
+#[test]
+fn guild_explain_permissions() {
+    let guild = p!(Guild, "guild_create_1");
+
+    let muted = guild.explain_permissions(ELIXIR, 274211819902730240).unwrap();
+    assert_eq!(muted.permissions, guild.permissions_for(ELIXIR, 274211819902730240));
+
+    let owner = guild.explain_permissions(ELIXIR, guild.owner_id).unwrap();
+    assert_eq!(owner.permissions, Permissions::all());
+    assert!(owner.bits.iter().all(|bit| bit.sources == vec![(PermissionSource::Owner, true)]));
+
+    // The user is not a member.
+    match guild.explain_permissions(ELIXIR, 1) {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the member to be missing, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn missing_member_roles() {
+    let mut guild = p!(Guild, "guild_create_1");
+    let muted = UserId(274211819902730240);
+    let expected = guild.permissions_for(ELIXIR, muted);
+
+    guild.members.get_mut(&muted).unwrap().roles.push(RoleId(1));
+
+    // Explaining errors rather than calculating without the missing role.
+    match guild.explain_permissions(ELIXIR, muted) {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the role to be missing, got {:?}", other.map(|_| ())),
+    }
+
+    // While calculating directly disregards the missing role.
+    assert_eq!(guild.permissions_for(ELIXIR, muted), expected);
+}
+
+#[cfg(feature="cache")]
+#[test]
+fn cached_permissions() {
+    let guild = p!(Guild, "guild_create_1");
+    let muted = guild.members[&UserId(274211819902730240)].clone();
+    let channel = guild.channels[&ChannelId(ELIXIR)].read().unwrap().clone();
+
+    // The guild is not yet cached.
+    match channel.permissions_for(&muted) {
+        Err(Error::Client(ClientError::GuildNotFound)) => {},
+        other => panic!("expected the guild to not be found, got {:?}", other),
+    }
+
+    CACHE.write().unwrap().update_with_guild_create(&GuildCreateEvent {
+        guild: guild,
+    });
+
+    let perms = channel.permissions_for(&muted).unwrap();
+    assert!(!perms.read_messages());
+
+    // A member of another guild.
+    let mut other = muted.clone();
+    other.guild_id = Some(GuildId(1));
+
+    match channel.permissions_for(&other) {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the member to be missing, got {:?}", other),
+    }
+
+    // A member with a role which no longer exists.
+    let mut missing_role = muted.clone();
+    missing_role.roles.push(RoleId(1));
+
+    match missing_role.permissions() {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the role to be missing, got {:?}", other),
+    }
+
+    let perms = muted.permissions().unwrap();
+    assert!(perms.read_messages() && perms.send_messages());
+    assert!(!perms.administrator());
+}
@@ -0,0 +1,139 @@
+extern crate serenity;
+
+use serenity::client::{Backpressure, DispatchOrdering, PoolConfig, WorkerPool};
+use serenity::model::{ChannelId, GuildId};
+use serenity::Client;
+use std::sync::mpsc;
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn keyed_jobs_run_in_order() {
+    let pool = WorkerPool::new(PoolConfig::default().size(4).queue_len(8));
+    let calls = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+
+    for i in 0..100 {
+        let calls = calls.clone();
+        let tx = tx.clone();
+
+        pool.execute(Some(81384788765712384), move || {
+            // Sleep on early jobs, so that later ones would overtake them if
+            // they were run concurrently.
+            if i % 10 == 0 {
+                thread::sleep(Duration::from_millis(5));
+            }
+
+            calls.lock().unwrap().push(i);
+            let _ = tx.send(());
+        });
+    }
+
+    for _ in 0..100 {
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    assert_eq!(*calls.lock().unwrap(), (0..100).collect::<Vec<_>>());
+}
+
+#[test]
+fn unkeyed_jobs_run_concurrently() {
+    let pool = WorkerPool::new(PoolConfig::default().size(3));
+    let barrier = Arc::new(Barrier::new(3));
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..3 {
+        let barrier = barrier.clone();
+        let tx = tx.clone();
+
+        pool.execute(None, move || {
+            barrier.wait();
+            let _ = tx.send(());
+        });
+    }
+
+    for _ in 0..3 {
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
+
+#[test]
+fn drop_when_full() {
+    let pool = WorkerPool::new(PoolConfig::default()
+        .size(1)
+        .queue_len(1)
+        .backpressure(Backpressure::Drop));
+    let (block_tx, block_rx) = mpsc::channel::<()>();
+    let (started_tx, started_rx) = mpsc::channel();
+    let (tx, rx) = mpsc::channel();
+
+    pool.execute(None, move || {
+        let _ = started_tx.send(());
+        let _ = block_rx.recv();
+    });
+    started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    // The first fills the queue, the second is dropped.
+    for i in 0..2 {
+        let tx = tx.clone();
+
+        pool.execute(None, move || {
+            let _ = tx.send(i);
+        });
+    }
+
+    drop(block_tx);
+
+    assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(0));
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn panicking_job_keeps_worker() {
+    let pool = WorkerPool::new(PoolConfig::default().size(1));
+    let (tx, rx) = mpsc::channel();
+
+    pool.execute(None, || panic!("handler failure"));
+    pool.execute(None, move || {
+        let _ = tx.send(());
+    });
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn ordering_keys() {
+    let channel = Some(ChannelId(1));
+    let guild = Some(GuildId(2));
+
+    let pool = WorkerPool::new(PoolConfig::default().ordering(DispatchOrdering::PerChannel));
+    assert_eq!(pool.key(channel, guild), Some(1));
+    assert_eq!(pool.key(None, guild), Some(2));
+
+    let pool = WorkerPool::new(PoolConfig::default().ordering(DispatchOrdering::PerGuild));
+    assert_eq!(pool.key(channel, guild), Some(2));
+    assert_eq!(pool.key(channel, None), Some(1));
+
+    let pool = WorkerPool::new(PoolConfig::default());
+    assert_eq!(pool.key(channel, guild), None);
+}
+
+#[test]
+fn client_pool() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+    client.with_pool(|p| p.size(1).ordering(DispatchOrdering::PerGuild));
+    client.on_message(move |_context, message| {
+        let name = thread::current().name().map(|name| name.to_owned());
+        let _ = tx.lock().unwrap().send((message.id, name));
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    let (id, name) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(id.0, 302917639565475840);
+    assert_eq!(name, Some("serenity worker 0".to_owned()));
+}
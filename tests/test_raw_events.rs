@@ -0,0 +1,75 @@
+extern crate serenity;
+
+use serenity::Client;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature="fake-gateway")]
+use serenity::client::gateway::FakeGateway;
+#[cfg(feature="fake-gateway")]
+use std::thread;
+
+#[test]
+fn replay_calls_raw_handlers_in_order() {
+    let raw = Arc::new(Mutex::new(vec![]));
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+
+    {
+        let raw = raw.clone();
+
+        client.on_raw_event(move |_context, name, seq, payload| {
+            assert_eq!(payload["t"], name.as_str());
+            raw.lock().unwrap().push((name, seq));
+        });
+    }
+
+    {
+        let raw = raw.clone();
+
+        client.on_message(move |_context, _message| {
+            let seen = raw.lock().unwrap().iter().any(|&(ref name, _)| name == "MESSAGE_CREATE");
+            let _ = tx.lock().unwrap().send(seen);
+        });
+    }
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    // The raw handlers have already been called by the time that replaying
+    // returns, while only dispatches are passed to them.
+    assert_eq!(*raw.lock().unwrap(), vec![
+        ("GUILD_CREATE".to_owned(), 2),
+        ("MESSAGE_CREATE".to_owned(), 3),
+    ]);
+
+    // The raw handler is called before the message's handler.
+    assert!(rx.recv_timeout(Duration::from_secs(5)).unwrap());
+}
+
+#[cfg(feature="fake-gateway")]
+#[test]
+fn boot_ready_reaches_raw_handlers() {
+    let gateway = FakeGateway::start().unwrap();
+    let (tx, rx) = mpsc::channel();
+    let (raw_tx, ready_tx) = (Mutex::new(tx.clone()), Mutex::new(tx));
+
+    let mut client = Client::login("fake token");
+    client.with_gateway_url(&gateway.url());
+    client.keep_raw_events(true);
+    client.on_raw_event(move |_context, name, _seq, _payload| {
+        let _ = raw_tx.lock().unwrap().send(name);
+    });
+    client.on_ready(move |context, _ready| {
+        let name = context.raw_event.map(|raw| raw["t"].to_string());
+        let _ = ready_tx.lock().unwrap().send(format!("ready handler: {:?}", name));
+    });
+
+    thread::spawn(move || client.start());
+
+    let timeout = Duration::from_secs(10);
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "READY");
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), r#"ready handler: Some("\"READY\"")"#);
+}
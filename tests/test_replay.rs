@@ -0,0 +1,75 @@
+extern crate serde_json;
+extern crate serenity;
+
+use serenity::client::gateway::{Recorder, Recording};
+use serenity::model::*;
+use serenity::Client;
+use std::env;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[cfg(feature="cache")]
+use serenity::client::CACHE;
+
+#[test]
+fn read_recording() {
+    let events = Recording::open("./tests/resources/session_1.jsonl")
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].shard_info, Some([0, 1]));
+    assert_eq!(events[0].time, 1492390000000);
+    assert_eq!(events[1].payload["t"], "GUILD_CREATE");
+    assert_eq!(events[3].payload["s"], 3);
+}
+
+#[test]
+fn record_and_read() {
+    let path = env::temp_dir().join("serenity_test_record.jsonl");
+    let payload = serde_json::from_str(r#"{"op": 11, "d": null}"#).unwrap();
+
+    {
+        let recorder = Recorder::create(&path).unwrap();
+        recorder.record(None, &payload);
+        recorder.record(Some([1, 2]), &payload);
+    }
+
+    let events = Recording::open(&path)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].shard_info, None);
+    assert_eq!(events[1].shard_info, Some([1, 2]));
+    assert_eq!(events[1].payload, payload);
+    assert!(events[0].time <= events[1].time);
+}
+
+#[test]
+fn replay() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    let mut client = Client::login("");
+    client.on_message(move |_context, message| {
+        let _ = tx.lock().unwrap().send(message);
+    });
+
+    client.replay("./tests/resources/session_1.jsonl", None).unwrap();
+
+    let message = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(message.id, MessageId(302917639565475840));
+    assert_eq!(message.content, "a");
+
+    #[cfg(feature="cache")]
+    {
+        let cache = CACHE.read().unwrap();
+        let guild = cache.guild(GuildId(81384788765712384)).unwrap();
+
+        assert_eq!(guild.read().unwrap().members.len(), 2);
+    }
+}
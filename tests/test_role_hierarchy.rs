@@ -0,0 +1,171 @@
+extern crate serde;
+extern crate serde_json;
+extern crate serenity;
+
+#[macro_use]
+mod common;
+
+use serenity::Error;
+use serenity::client::ClientError;
+use serenity::model::*;
+
+#[cfg(feature="cache")]
+use serenity::client::CACHE;
+#[cfg(feature="cache")]
+use serenity::model::event::GuildCreateEvent;
+
+const ADMIN_ROLE: u64 = 158331219535331328;
+const MODS_ROLE: u64 = 175643578071121920;
+const VOLTYVOLT_ROLE: u64 = 288581540080910336;
+
+// Members whose highest role is "Admin", "Mods" or none at all.
+const ADMIN: u64 = 66237334693085184;
+const ADMIN_2: u64 = 80088516616269824;
+const MOD: u64 = 98295630480314368;
+const MEMBER: u64 = 145265491093487617;
+
+#[test]
+fn highest_role() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert_eq!(guild.highest_role(ADMIN).unwrap().id, RoleId(ADMIN_ROLE));
+    assert_eq!(guild.highest_role(MOD).unwrap().id, RoleId(MODS_ROLE));
+    assert_eq!(guild.highest_role(guild.owner_id).unwrap().id, RoleId(VOLTYVOLT_ROLE));
+    assert!(guild.highest_role(MEMBER).is_none());
+    assert!(guild.highest_role(1).is_none());
+}
+
+#[test]
+fn can_moderate() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert!(guild.can_moderate(ADMIN, MOD).unwrap());
+    assert!(guild.can_moderate(MOD, MEMBER).unwrap());
+    assert!(!guild.can_moderate(MOD, ADMIN).unwrap());
+    assert!(!guild.can_moderate(MEMBER, MEMBER).unwrap());
+
+    // Members with the same highest role can not moderate each other.
+    assert!(!guild.can_moderate(ADMIN, ADMIN_2).unwrap());
+
+    // The owner can moderate anyone but themselves, and can not be moderated.
+    assert!(guild.can_moderate(guild.owner_id, ADMIN).unwrap());
+    assert!(!guild.can_moderate(guild.owner_id, guild.owner_id).unwrap());
+    assert!(!guild.can_moderate(ADMIN, guild.owner_id).unwrap());
+
+    match guild.can_moderate(ADMIN, 1) {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the member to be missing, got {:?}", other),
+    }
+}
+
+#[test]
+fn can_manage_role() {
+    let guild = p!(Guild, "guild_create_1");
+
+    assert!(guild.can_manage_role(ADMIN, MODS_ROLE).unwrap());
+    assert!(!guild.can_manage_role(ADMIN, ADMIN_ROLE).unwrap());
+    assert!(!guild.can_manage_role(ADMIN, VOLTYVOLT_ROLE).unwrap());
+    assert!(!guild.can_manage_role(MEMBER, MODS_ROLE).unwrap());
+    assert!(guild.can_manage_role(guild.owner_id, VOLTYVOLT_ROLE).unwrap());
+
+    match guild.can_manage_role(ADMIN, 1) {
+        Err(Error::Client(ClientError::ItemMissing)) => {},
+        other => panic!("expected the role to be missing, got {:?}", other),
+    }
+}
+
+#[test]
+fn equal_positions() {
+    // Roles at the same position, in ascending order of Id.
+    let (rewrite, updates, design) = (281523439271936001, 291196872549335040, 291196901095899146);
+
+    let mut guild = p!(Guild, "guild_create_1");
+    guild.members.get_mut(&UserId(MEMBER)).unwrap().roles = vec![RoleId(design), RoleId(updates)];
+    guild.members.get_mut(&UserId(MOD)).unwrap().roles = vec![RoleId(design)];
+
+    // Discord ranks the role with the lower Id higher when two roles share a
+    // position, both when finding the highest role and when comparing members
+    // in the hierarchy.
+    assert_eq!(guild.highest_role(MEMBER).unwrap().id, RoleId(updates));
+    assert!(!guild.can_manage_role(MEMBER, rewrite).unwrap());
+    assert!(guild.can_manage_role(MEMBER, design).unwrap());
+    assert!(guild.can_moderate(MEMBER, MOD).unwrap());
+    assert!(!guild.can_moderate(MOD, MEMBER).unwrap());
+}
+
+#[cfg(feature="cache")]
+#[test]
+fn moderation_prechecks() {
+    let guild = p!(Guild, "guild_create_1");
+    let guild_id = guild.id;
+    let owner_id = guild.owner_id;
+    let member = guild.members[&UserId(MOD)].clone();
+    let mut admin = guild.members[&UserId(ADMIN)].clone();
+    let mut regular = guild.members[&UserId(MEMBER)].clone();
+
+    {
+        let mut cache = CACHE.write().unwrap();
+        cache.user.id = UserId(MOD);
+        cache.update_with_guild_create(&GuildCreateEvent {
+            guild: guild,
+        });
+    }
+
+    assert_eq!(member.highest_role().unwrap().id, RoleId(MODS_ROLE));
+
+    // Each of these is refused without making a request.
+    match guild_id.kick(ADMIN) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the kick to be refused, got {:?}", other),
+    }
+
+    match guild_id.ban(owner_id, 0) {
+        Err(Error::Client(ClientError::CannotModerate(user_id))) => assert_eq!(user_id, owner_id),
+        other => panic!("expected the ban to be refused, got {:?}", other),
+    }
+
+    match guild_id.edit_member(ADMIN, |m| m.nickname("demoted")) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the edit to be refused, got {:?}", other),
+    }
+
+    match guild_id.edit_member(MEMBER, |m| m.roles(&[RoleId(ADMIN_ROLE)])) {
+        Err(Error::Client(ClientError::CannotManageRole(RoleId(ADMIN_ROLE)))) => {},
+        other => panic!("expected the edit to be refused, got {:?}", other),
+    }
+
+    // As are the same actions made through the members themselves.
+    match admin.ban(0) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the ban to be refused, got {:?}", other),
+    }
+
+    match admin.edit(|m| m.nickname("demoted")) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the edit to be refused, got {:?}", other),
+    }
+
+    match regular.add_role(ADMIN_ROLE) {
+        Err(Error::Client(ClientError::CannotManageRole(RoleId(ADMIN_ROLE)))) => {},
+        other => panic!("expected the role to be refused, got {:?}", other),
+    }
+
+    match regular.add_roles(&[RoleId(ADMIN_ROLE)]) {
+        Err(Error::Client(ClientError::CannotManageRole(RoleId(ADMIN_ROLE)))) => {},
+        other => panic!("expected the roles to be refused, got {:?}", other),
+    }
+
+    assert!(regular.roles.is_empty());
+
+    match admin.remove_role(ADMIN_ROLE) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the role removal to be refused, got {:?}", other),
+    }
+
+    match admin.remove_roles(&[RoleId(ADMIN_ROLE)]) {
+        Err(Error::Client(ClientError::CannotModerate(UserId(ADMIN)))) => {},
+        other => panic!("expected the role removal to be refused, got {:?}", other),
+    }
+
+    assert!(admin.roles.contains(&RoleId(ADMIN_ROLE)));
+}
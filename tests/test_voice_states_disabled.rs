@@ -0,0 +1,51 @@
+#![cfg(feature="cache")]
+
+extern crate serenity;
+
+use serenity::Client;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// Kept in its own file, as it changes the configuration of the global cache.
+#[test]
+fn no_voice_events_without_voice_states() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+
+    macro_rules! record {
+        ($client:ident, $method:ident, |$($arg:pat),*| $value:expr) => {{
+            let tx = Mutex::new(tx.lock().unwrap().clone());
+
+            $client.$method(move |_context, $($arg),*| {
+                let _ = tx.lock().unwrap().send($value);
+            });
+        }}
+    }
+
+    // A single worker runs the handlers in the order they were dispatched.
+    let mut client = Client::login("");
+    client.with_pool(|p| p.size(1));
+    client.with_cache_config(|c| c.voice_states(false));
+    record!(client, on_voice_channel_join, |_, user_id, _| format!("join {}", user_id.0));
+    record!(client, on_voice_channel_leave, |_, user_id, _| format!("leave {}", user_id.0));
+    record!(client, on_voice_channel_move, |_, user_id, _| format!("move {}", user_id.0));
+    record!(client, on_voice_server_mute_update, |_, user_id, _| format!("mute {}", user_id.0));
+    record!(client, on_voice_server_deaf_update, |_, user_id, _| format!("deaf {}", user_id.0));
+    record!(client, on_voice_state_update, |_, state| format!("state {}", state.user_id.0));
+    drop(tx);
+
+    client.replay("./tests/resources/session_2.jsonl", None).unwrap();
+
+    let received = (0..4)
+        .map(|_| rx.recv_timeout(Duration::from_secs(5)).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(received, vec![
+        "state 300000000000000002".to_owned(),
+        "state 300000000000000002".to_owned(),
+        "state 300000000000000002".to_owned(),
+        "state 300000000000000001".to_owned(),
+    ]);
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}